@@ -50,11 +50,9 @@ impl Act for ActAttack {
         let Some(target_unit) = board.get_unit(&board.get_target_pos(&self.target)) else {
             return false;
         };
-        let this_player = board.get_player(&this_unit.owner);
-        let target_player = board.get_player(&target_unit.owner);
         let distance = (self.this.at - self.target.at).length();
         let in_range = distance <= ActAttack::get_range(board, &self.this);
-        this_player.is_hostile(target_player) && in_range
+        board.is_hostile(&this_unit.owner, &target_unit.owner) && in_range
     }
 
     fn apply(&self, board: &mut Board) {
@@ -164,6 +162,17 @@ impl Act for ActAttack {
             def_tile_mut.terrain.collectable = Some(Collectable::Relic)
         }
         def_tile_mut.set_unit(def_damaged.clone(), def_bp);
+
+        if board.events.is_collecting() {
+            for (before, after, by) in [
+                (&self.this, &atk_damaged, &self.target.unit.owner),
+                (&self.target, &def_damaged, &self.this.unit.owner),
+            ] {
+                if let Some(event) = GameEvent::fight(before, after, by) {
+                    board.events.emit(|| event);
+                }
+            }
+        }
     }
 
     fn undo(&self, board: &mut Board) {
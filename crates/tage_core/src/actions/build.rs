@@ -54,8 +54,13 @@ impl Act for ActBuild {
         let build_bp = bp.get_unit(&self.build_id);
 
         let bonus = board.get_player_bonus(&owner, Some(&self.build_id));
+        let cost = build_bp.resources.cost.apply_cost(bonus);
         let player = board.get_player_mut(&owner);
-        player.resources -= build_bp.resources.cost.apply_cost(bonus);
+        player.resources -= cost.clone();
+        board.events.emit(|| GameEvent::Spent {
+            player: owner.clone(),
+            resources: cost,
+        });
 
         for pos in self.area.iter() {
             let linked_units = if self.area.iter().count() == 1 {
@@ -155,6 +160,14 @@ impl ActBuild {
                                 .count() as i32;
                             comp.compare(number, *val)
                         }
+                        BuildConstraint::WallChain(ids) => {
+                            ActBuild::is_wall_chained(board, &from, &player.id, ids)
+                        }
+                        BuildConstraint::IsAdjacentToTerrain(terrain_id) => board
+                            .grid
+                            .get_adjacent(&from)
+                            .iter()
+                            .any(|(_dir, t)| t.terrain.blueprint_id == *terrain_id.terrain()),
                         BuildConstraint::OnlyOnFoodResource => {
                             tile.terrain.resource == Some(Resource::Food)
                         }
@@ -188,7 +201,7 @@ impl ActBuild {
                 .get_at(&from)
                 .unit
                 .as_ref()
-                .map(|u| board.get_player(&u.owner).is_hostile(player))
+                .map(|u| board.is_hostile(&u.owner, &player.id))
                 .unwrap_or(false);
 
             let level = player.level >= potential_build.header.level;
@@ -261,6 +274,14 @@ impl ActBuild {
                             .map_or_else(|| 100, |pos| (upos.xy - pos).length());
                     comp.compare(closest, *val)
                 }
+                BuildConstraint::WallChain(ids) => {
+                    ActBuild::is_wall_chained(board, &upos.xy, &player.id, ids)
+                }
+                BuildConstraint::IsAdjacentToTerrain(terrain_id) => board
+                    .grid
+                    .get_adjacent(&upos.xy)
+                    .iter()
+                    .any(|(_dir, t)| t.terrain.blueprint_id == *terrain_id.terrain()),
                 BuildConstraint::OnlyOnFoodResource => {
                     tile.terrain.resource == Some(Resource::Food)
                 }
@@ -284,6 +305,21 @@ impl ActBuild {
 
         constraints && any_terrain
     }
+
+    /// Walls and gates are built next to each other, starting from one of the `anchors`
+    fn is_wall_chained(board: &Board, xy: &IVec2, owner: &PlayerId, anchors: &[IdName]) -> bool {
+        board
+            .grid
+            .get_adjacent(xy)
+            .iter()
+            .any(|(_dir, t)| match &t.building {
+                Some(building) if &building.owner == owner => {
+                    board.bp.get_unit(&building.blueprint_id).barrier != Barrier::None
+                        || anchors.iter().any(|id| id.unit() == &building.blueprint_id)
+                }
+                _ => false,
+            })
+    }
 }
 
 impl From<ActBuild> for UnitAction {
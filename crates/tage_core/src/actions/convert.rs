@@ -53,10 +53,8 @@ impl Act for ActConvert {
             return false;
         }
 
-        let this_player = board.get_player(&this_unit.owner);
-        let target_player = board.get_player(&target_unit.owner);
         let in_range = (self.this.at - self.target.at).length() <= 2;
-        this_player.is_hostile(target_player) && in_range
+        board.is_hostile(&this_unit.owner, &target_unit.owner) && in_range
     }
 
     fn apply(&self, board: &mut Board) {
@@ -70,9 +68,31 @@ impl Act for ActConvert {
                 target_terrain,
                 (self.this.at - self.target.at).length(),
             );
-        board.modify_unit(&board.get_target_pos(&self.target), |unit| {
-            unit.conversion_attempt = Some((self.target.unit.owner.clone(), bonus.convert))
-        });
+        let owner = this_unit.owner.clone();
+        if board.rules.no_rng {
+            // the target switches sides when the damage would kill it
+            let damage = conversion_chance(bonus.convert);
+            board.modify_unit(&board.get_target_pos(&self.target), |unit| {
+                if unit.health > damage {
+                    unit.health -= damage;
+                } else {
+                    unit.owner = owner.clone();
+                }
+            });
+            board.events.emit(|| {
+                let target = self.target.clone();
+                if target.unit.health > damage {
+                    let health = target.unit.health - damage;
+                    GameEvent::UnitDamaged { target, health }
+                } else {
+                    GameEvent::UnitConverted { target, to: owner }
+                }
+            });
+        } else {
+            board.modify_unit(&board.get_target_pos(&self.target), |unit| {
+                unit.conversion_attempt = Some((owner.clone(), bonus.convert))
+            });
+        }
     }
 
     fn undo(&self, board: &mut Board) {
@@ -82,6 +102,6 @@ impl Act for ActConvert {
 
 impl From<ActConvert> for UnitAction {
     fn from(value: ActConvert) -> Self {
-        UnitAction::Convert(value.this)
+        UnitAction::Convert(value.target)
     }
 }
@@ -0,0 +1,116 @@
+use crate::prelude::*;
+
+/// Amount of resources sent with a tribute
+pub const TRIBUTE_AMOUNT: i32 = 100;
+
+#[derive(Debug, Clone)]
+pub struct ActDiplomacy {
+    pub action: DiplomacyAction,
+}
+
+impl Act for ActDiplomacy {
+    type Precondition = ();
+
+    fn generate(_pre: &Self::Precondition, board: &mut Board) -> Vec<Self> {
+        let current = board.current_player_turn.clone();
+        board
+            .players
+            .iter()
+            .filter(|player| player.id != current)
+            .flat_map(|player| {
+                [
+                    DiplomacyAction::Propose(player.id.clone(), Relation::Alliance),
+                    DiplomacyAction::Propose(player.id.clone(), Relation::Ceasefire),
+                    DiplomacyAction::Accept(player.id.clone()),
+                    DiplomacyAction::Break(player.id.clone()),
+                    DiplomacyAction::Tribute(player.id.clone(), Resource::Food),
+                    DiplomacyAction::Tribute(player.id.clone(), Resource::Gold),
+                ]
+            })
+            .map(|action| ActDiplomacy { action })
+            .filter(|act| act.is_valid(board))
+            .collect()
+    }
+
+    fn is_valid(&self, board: &Board) -> bool {
+        let current = &board.current_player_turn;
+        let other = match &self.action {
+            DiplomacyAction::Propose(other, _)
+            | DiplomacyAction::Accept(other)
+            | DiplomacyAction::Break(other)
+            | DiplomacyAction::Tribute(other, _) => other,
+        };
+        // nobody talks with gaia
+        if other == current
+            || other.is_gaia()
+            || current.is_gaia()
+            || !board.players.iter().any(|p| &p.id == other)
+        {
+            return false;
+        }
+
+        let relation = board.get_relation(current, other);
+        match &self.action {
+            DiplomacyAction::Propose(_, proposed) => {
+                // a ceasefire can only be proposed during a war
+                board.diplomacy.get_proposal(current, other).is_none()
+                    && *proposed != relation
+                    && (*proposed == Relation::Alliance || relation == Relation::War)
+            }
+            DiplomacyAction::Accept(_) => board
+                .diplomacy
+                .get_proposal(other, current)
+                .is_some_and(|proposed| proposed != relation),
+            DiplomacyAction::Break(_) => relation != Relation::War,
+            DiplomacyAction::Tribute(_, resource) => {
+                board.get_current_player().get_resource(resource) >= TRIBUTE_AMOUNT
+            }
+        }
+    }
+
+    fn apply(&self, board: &mut Board) {
+        let current = board.current_player_turn.clone();
+        match &self.action {
+            DiplomacyAction::Propose(other, relation) => {
+                board.diplomacy.propose(&current, other, *relation)
+            }
+            DiplomacyAction::Accept(other) => {
+                if let Some(relation) = board.diplomacy.get_proposal(other, &current) {
+                    board.diplomacy.withdraw(other, &current);
+                    board.diplomacy.set(&current, other, relation);
+                }
+            }
+            DiplomacyAction::Break(other) => {
+                board.diplomacy.set(&current, other, Relation::War);
+            }
+            DiplomacyAction::Tribute(other, resource) => {
+                *board.get_player_mut(&current).get_resource_mut(resource) -= TRIBUTE_AMOUNT;
+                *board.get_player_mut(other).get_resource_mut(resource) += TRIBUTE_AMOUNT;
+            }
+        }
+    }
+
+    fn undo(&self, board: &mut Board) {
+        let current = board.current_player_turn.clone();
+        match &self.action {
+            DiplomacyAction::Propose(other, _) => board.diplomacy.withdraw(&current, other),
+            DiplomacyAction::Accept(other) => {
+                if let Some((_, _, relation)) = board.diplomacy.changes.last().cloned() {
+                    board.diplomacy.unset();
+                    board.diplomacy.propose(other, &current, relation);
+                }
+            }
+            DiplomacyAction::Break(_) => board.diplomacy.unset(),
+            DiplomacyAction::Tribute(other, resource) => {
+                *board.get_player_mut(other).get_resource_mut(resource) -= TRIBUTE_AMOUNT;
+                *board.get_player_mut(&current).get_resource_mut(resource) += TRIBUTE_AMOUNT;
+            }
+        }
+    }
+}
+
+impl From<ActDiplomacy> for PlayerAction {
+    fn from(value: ActDiplomacy) -> Self {
+        PlayerAction::Diplomacy(value.action)
+    }
+}
@@ -1,6 +1,6 @@
 use crate::prelude::*;
 use end_turn::build::ActBuild;
-use rand::{thread_rng, Rng};
+use rand::Rng;
 use tracing::trace;
 
 use self::{research::ActResearch, train::ActTrain};
@@ -21,7 +21,9 @@ impl Act for ActEndTurn {
 
     fn apply(&self, board: &mut Board) {
         let bp = board.bp.clone();
-        let mut rng = thread_rng();
+        // rolled on a copy because the board is borrowed while rolling, written back at the end
+        let mut rng = board.rng.clone();
+        let rules = board.rules.clone();
 
         // conversions and ruin rng
         for xy in iter_area(board.grid.size) {
@@ -31,30 +33,44 @@ impl Act for ActEndTurn {
                     continue;
                 }
                 if let Some((player, conversion_strenght)) = unit.conversion_attempt.take() {
-                    if match conversion_strenght {
-                        -1 => rng.gen_bool(0.1),
-                        0 => rng.gen_bool(0.25),
-                        1 => rng.gen_bool(0.33),
-                        _ => rng.gen_bool(0.50),
-                    } {
+                    if rng.gen_bool(conversion_chance(conversion_strenght) as f64 / 100.0) {
+                        board.events.emit(|| GameEvent::UnitConverted {
+                            target: UnitTarget::new(unit.clone(), xy),
+                            to: player.clone(),
+                        });
                         unit.owner = player;
                     }
                 }
                 if Some(Collectable::Ruins) == unit.holding_collectable {
+                    let reward = if rules.no_rng {
+                        Some(rules.ruins_reward.clone())
+                    } else {
+                        match rng.gen_range(0..=4) {
+                            0 => None,
+                            1 => Some(RuinsReward::Food),
+                            2 => Some(RuinsReward::Gold),
+                            3 => Some(RuinsReward::Tech),
+                            _ => Some(RuinsReward::Militia),
+                        }
+                    };
+                    board.events.emit(|| GameEvent::Ruins {
+                        target: UnitTarget::new(unit.clone(), xy),
+                        reward: reward.clone(),
+                    });
                     unit.holding_collectable = None;
-                    match rng.gen_range(0..=4) {
-                        0 => {
+                    match reward {
+                        None => {
                             board.grid.get_at_mut(&xy).unit = None;
                         }
-                        1 => {
+                        Some(RuinsReward::Food) => {
                             let player = board.get_current_player_mut();
-                            player.resources.food += 200;
+                            player.resources.food += RuinsReward::RESOURCES;
                         }
-                        2 => {
+                        Some(RuinsReward::Gold) => {
                             let player = board.get_current_player_mut();
-                            player.resources.gold += 200;
+                            player.resources.gold += RuinsReward::RESOURCES;
                         }
-                        3 => {
+                        Some(RuinsReward::Tech) => {
                             if let Some(ActResearch { tech_id }) =
                                 ActResearch::generate(&(), board).first()
                             {
@@ -62,7 +78,7 @@ impl Act for ActEndTurn {
                                 player.researched_technologies.push(tech_id.clone());
                             }
                         }
-                        4 => {
+                        Some(RuinsReward::Militia) => {
                             let target =
                                 board.grid.get_adjacent(&xy).iter().find_map(|(dir, t)| {
                                     let terrain_bp = bp.get_terrain(&t.terrain.blueprint_id);
@@ -84,7 +100,6 @@ impl Act for ActEndTurn {
                                 });
                             }
                         }
-                        _ => unreachable!(),
                     }
                 }
             }
@@ -106,13 +121,26 @@ impl Act for ActEndTurn {
         let player = board.get_player_mut(&board.current_player_turn.clone());
         player.train_discount = Resources::default();
         player.tech_discount = Resources::default();
+        player.trade_pressure -= player.trade_pressure.signum();
 
-        if let Some(queued) = player.research_queued.take() {
-            match queued {
-                QueuedResearch::Tech(id) => player.researched_technologies.push(id),
+        if !player.research_queue.is_empty() {
+            let player_id = player.id.clone();
+            match player.research_queue.remove(0).0 {
+                QueuedResearch::Tech(tech_id) => {
+                    player.researched_technologies.push(tech_id.clone());
+                    board.events.emit(|| GameEvent::ResearchFinished {
+                        player: player_id,
+                        tech_id,
+                    });
+                }
                 QueuedResearch::AgeUp => {
                     player.level += 1;
+                    let level = player.level;
                     upgrade_units(bp.as_ref(), board);
+                    board.events.emit(|| GameEvent::AgeUp {
+                        player: player_id,
+                        level,
+                    });
                 }
             }
         }
@@ -138,7 +166,7 @@ impl Act for ActEndTurn {
                     unit.affected_by_powers.clear()
                 }
             });
-            let mut check_capture = false;
+            let mut completed = None;
             board.modify_unit(&UnitPos::bot(xy), |building| {
                 if building.owner == current_player {
                     building.done = false;
@@ -146,19 +174,21 @@ impl Act for ActEndTurn {
                         if unit_build_list
                             .contains(&IdName::Id(Id::Unit(building.blueprint_id.clone())))
                         {
+                            completed = Some(building.clone());
                             building.in_construction = false;
                             building.health = (building.health + 50).min(100);
-                            check_capture = true;
                         }
                     }
                     let market = bp.get_unit_from_name("Market");
                     if market == Some(building.blueprint_id.clone()) {
                         building.train_list_override.clear();
                         let mut list = market_list.clone();
-                        if list.len() >= 3 {
+                        if rules.no_rng {
+                            building.train_list_override = list;
+                        } else if list.len() >= 3 {
                             let override_list = (0..3)
                                 .map(|_| {
-                                    let pick = rng.clone().gen_range(0..list.len());
+                                    let pick = rng.gen_range(0..list.len());
                                     list.swap_remove(pick)
                                 })
                                 .collect();
@@ -167,21 +197,31 @@ impl Act for ActEndTurn {
                     }
                 }
             });
-            if check_capture {
+            if let Some(building) = completed {
+                board.events.emit(|| GameEvent::BuildingCompleted {
+                    target: UnitTarget::new(building, xy),
+                });
                 capture(board, xy);
             }
         }
+        board.rng = rng;
 
         // production
         let (production, _) = calculate_production(board, &board.current_player_turn, false);
         let player = board.get_player_mut(&board.current_player_turn.clone());
-        player.resources += production;
+        player.resources += production.clone();
         player.train_discount = Resources::default();
         player.tech_discount = Resources::default();
+        board.events.emit(|| GameEvent::Production {
+            player: board.current_player_turn.clone(),
+            resources: production,
+        });
+
+        board.update_victory();
     }
 
     fn undo(&self, _: &mut Board) {
-        // end turn does not support undo
+        // end turn does not support undo, the game session keeps a snapshot of the board instead
     }
 }
 
@@ -321,7 +361,12 @@ fn capture(board: &mut Board, xy: IVec2) {
 
     for pos in activated_buildings.iter() {
         let building = board.get_unit_mut(&UnitPos::bot(*pos)).unwrap();
+        let before = UnitTarget::new(building.clone(), *pos);
         building.owner = original_owner.clone();
+        board.events.emit(|| GameEvent::BuildingCaptured {
+            target: before,
+            by: original_owner.clone(),
+        });
     }
 
     trace!(target: "capture", "captured {:?}", activated_buildings);
@@ -10,6 +10,7 @@ pub mod player_action;
 pub mod attack;
 pub mod build;
 pub mod convert;
+pub mod diplomacy;
 pub mod done;
 pub mod end_turn;
 pub mod heal;
@@ -21,6 +22,7 @@ pub mod repair;
 pub mod research;
 pub mod trade;
 pub mod train;
+pub mod transport;
 pub mod travel;
 
 /// Interface for implementation of player action's logic
@@ -60,6 +62,8 @@ pub enum PlayerAction {
         action: BuildingAction,
     },
     Research(TechId),
+    ResearchQueue(ResearchQueueAction),
+    Diplomacy(DiplomacyAction),
     PassTurn,
 }
 
@@ -73,6 +77,12 @@ pub enum UnitAction {
     Merge(UnitTarget),
     Repair(UnitTarget),
     Power(PowerId, Vec<UnitTarget>),
+
+    /// Board a transport ship
+    Embark(UnitTarget),
+
+    /// Drop the carried unit at this index on an adjacent tile
+    Unload(usize, IVec2),
     Done,
 }
 
@@ -83,3 +93,31 @@ pub enum BuildingAction {
     AgeUp,
     Done,
 }
+
+/// Changes to the paid research of the current player, holding the queued research for undo
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, bincode::Encode, bincode::Decode)]
+pub enum ResearchQueueAction {
+    /// Remove the research at this index of the queue, refunding what was paid for it
+    Cancel(usize, QueuedResearch, Resources),
+
+    /// Replace the research at this index of the queue with another tech,
+    /// refunding what was paid for it and paying the other tech
+    Switch(usize, QueuedResearch, Resources, TechId),
+}
+
+#[derive(
+    Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, bincode::Encode, bincode::Decode,
+)]
+pub enum DiplomacyAction {
+    /// Propose a relation to another player, it changes only when accepted
+    Propose(PlayerId, Relation),
+
+    /// Accept the relation proposed by another player
+    Accept(PlayerId),
+
+    /// Break an alliance or a ceasefire, going back to war
+    Break(PlayerId),
+
+    /// Send resources to another player
+    Tribute(PlayerId, Resource),
+}
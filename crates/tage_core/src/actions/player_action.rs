@@ -4,6 +4,7 @@ use self::{
     attack::ActAttack,
     build::ActBuild,
     convert::ActConvert,
+    diplomacy::{ActDiplomacy, TRIBUTE_AMOUNT},
     done::{ActDone, ActNone},
     end_turn::ActEndTurn,
     heal::ActHeal,
@@ -12,9 +13,10 @@ use self::{
     power::ActPower,
     relic::ActRelic,
     repair::ActRepair,
-    research::{ActAgeUp, ActResearch},
+    research::{ActAgeUp, ActResearch, ActResearchQueue},
     trade::ActTrade,
     train::ActTrain,
+    transport::{ActEmbark, ActUnload},
     travel::ActTravel,
 };
 
@@ -68,12 +70,20 @@ impl Act for PlayerAction {
                 tech_id: tech_id.clone(),
             }
             .is_valid(board),
+            PlayerAction::ResearchQueue(action) => ActResearchQueue {
+                action: action.clone(),
+            }
+            .is_valid(board),
+            PlayerAction::Diplomacy(action) => ActDiplomacy {
+                action: action.clone(),
+            }
+            .is_valid(board),
             PlayerAction::PassTurn => ActEndTurn.is_valid(board),
         }
     }
 
     fn apply(&self, board: &mut Board) {
-        self.wrap_check_invariants(board, |board| match self.clone() {
+        let apply = |board: &mut Board| match self.clone() {
             PlayerAction::Unit {
                 target: this,
                 destination,
@@ -133,6 +143,17 @@ impl Act for PlayerAction {
                         targets,
                     }
                     .apply(board),
+                    UnitAction::Embark(target) => ActEmbark {
+                        this: moved.clone(),
+                        target,
+                    }
+                    .apply(board),
+                    UnitAction::Unload(index, destination) => ActUnload {
+                        this: moved.clone(),
+                        index,
+                        destination,
+                    }
+                    .apply(board),
                     UnitAction::Done => {}
                 };
                 ActPickup {
@@ -166,8 +187,11 @@ impl Act for PlayerAction {
                 ActDone { this: this.clone() }.apply(board);
             }
             PlayerAction::Research(tech_id) => ActResearch { tech_id }.apply(board),
+            PlayerAction::ResearchQueue(action) => ActResearchQueue { action }.apply(board),
+            PlayerAction::Diplomacy(action) => ActDiplomacy { action }.apply(board),
             PlayerAction::PassTurn => ActEndTurn.apply(board),
-        })
+        };
+        apply_with_triggers(board, |board| self.wrap_check_invariants(board, apply))
     }
 
     fn undo(&self, board: &mut Board) {
@@ -231,6 +255,17 @@ impl Act for PlayerAction {
                         targets,
                     }
                     .undo(board),
+                    UnitAction::Embark(target) => ActEmbark {
+                        this: moved.clone(),
+                        target,
+                    }
+                    .undo(board),
+                    UnitAction::Unload(index, destination) => ActUnload {
+                        this: moved.clone(),
+                        index,
+                        destination,
+                    }
+                    .undo(board),
                     UnitAction::Done => ActDone {
                         this: moved.clone(),
                     }
@@ -264,6 +299,8 @@ impl Act for PlayerAction {
                 }
             }
             PlayerAction::Research(tech_id) => ActResearch { tech_id }.undo(board),
+            PlayerAction::ResearchQueue(action) => ActResearchQueue { action }.undo(board),
+            PlayerAction::Diplomacy(action) => ActDiplomacy { action }.undo(board),
             PlayerAction::PassTurn => ActEndTurn.undo(board),
         })
     }
@@ -368,6 +405,8 @@ impl PlayerAction {
                 acts.extend(Self::gen_unit_moved::<ActConvert>(pre, to, board, p));
                 acts.extend(Self::gen_unit_moved::<ActPower>(pre, to, board, p));
                 acts.extend(Self::gen_unit_moved::<ActRelic>(pre, to, board, p));
+                acts.extend(Self::gen_unit_moved::<ActEmbark>(pre, to, board, p));
+                acts.extend(Self::gen_unit_moved::<ActUnload>(pre, to, board, p));
                 acts.extend(Self::gen_unit_moved::<ActNone>(pre, to, board, p));
                 act_travel.undo(board);
                 acts
@@ -464,6 +503,16 @@ impl PlayerAction {
 
     fn gen_global(board: &mut Board) -> Vec<PlayerAction> {
         let mut res = Self::gen_research(board);
+        res.extend(
+            ActResearchQueue::generate(&(), board)
+                .into_iter()
+                .map(|act| act.into()),
+        );
+        res.extend(
+            ActDiplomacy::generate(&(), board)
+                .into_iter()
+                .map(|act| act.into()),
+        );
         res.push(PlayerAction::PassTurn);
         res
     }
@@ -500,6 +549,14 @@ impl PlayerAction {
                             t.view(bp)
                         ))
                     ),
+                    UnitAction::Embark(t) => format!("embarks on {}", t.view(bp)),
+                    UnitAction::Unload(index, xy) => format!(
+                        "unloads {} at {}",
+                        bp.get_unit(&target.unit.cargo[*index].blueprint_id)
+                            .header
+                            .name,
+                        xy
+                    ),
                     UnitAction::Done => format!("does nothing"),
                 },
                 match pickup {
@@ -519,11 +576,37 @@ impl PlayerAction {
                 }
             ),
             PlayerAction::Research(tech_id) => format!("Research {}", bp.get_tech(tech_id).name),
+            PlayerAction::ResearchQueue(action) => match action {
+                ResearchQueueAction::Cancel(_, research, _) => {
+                    format!("Cancel research of {}", research.view(bp))
+                }
+                ResearchQueueAction::Switch(_, research, _, tech_id) => format!(
+                    "Switch research of {} to {}",
+                    research.view(bp),
+                    bp.get_tech(tech_id).name
+                ),
+            },
+            PlayerAction::Diplomacy(action) => action.view(),
             PlayerAction::PassTurn => format!("Pass Turn"),
         }
     }
 }
 
+impl DiplomacyAction {
+    pub fn view(&self) -> String {
+        match self {
+            DiplomacyAction::Propose(other, relation) => {
+                format!("Propose {} to {}", relation.view(), other.view())
+            }
+            DiplomacyAction::Accept(other) => format!("Accept the proposal of {}", other.view()),
+            DiplomacyAction::Break(other) => format!("Declare war to {}", other.view()),
+            DiplomacyAction::Tribute(other, resource) => {
+                format!("Send {} {:?} to {}", TRIBUTE_AMOUNT, resource, other.view())
+            }
+        }
+    }
+}
+
 impl UnitTarget {
     pub fn view(&self, bp: &Blueprints) -> String {
         let unit_bp = bp.get_unit(&self.unit.blueprint_id);
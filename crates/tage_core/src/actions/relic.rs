@@ -54,6 +54,9 @@ impl Act for ActRelic {
                 tile.unit
                     .as_mut()
                     .map(|unit| unit.holding_collectable = tile.terrain.collectable.take());
+                board.events.emit(|| GameEvent::RelicPickedUp {
+                    target: self.this.clone(),
+                });
             }
             _ => {
                 // unit to building
@@ -41,7 +41,11 @@ impl Act for ActRepair {
 
     fn apply(&self, board: &mut Board) {
         let cost = self.get_cost(board);
-        board.get_player_mut(&self.this.unit.owner).resources -= cost;
+        board.get_player_mut(&self.this.unit.owner).resources -= cost.clone();
+        board.events.emit(|| GameEvent::Spent {
+            player: self.this.unit.owner.clone(),
+            resources: cost,
+        });
         let pos = board.get_target_pos(&self.target);
         board.modify_unit(&pos, |building| building.health = 100)
     }
@@ -24,15 +24,44 @@ impl Act for ActResearch {
 
     fn is_valid(&self, board: &Board) -> bool {
         let player = board.get_current_player();
-        if player
-            .researched_technologies
-            .iter()
-            .any(|known| known == &self.tech_id)
+        let cost =
+            player.get_research_cost(board.bp(), &QueuedResearch::Tech(self.tech_id.clone()));
+        Self::can_research(board, &self.tech_id) && player.resources.contains(&cost)
+    }
+
+    fn apply(&self, board: &mut Board) {
+        let bp = board.bp.clone();
+        let player = board.get_current_player_mut();
+        let research = QueuedResearch::Tech(self.tech_id.clone());
+        let cost = player.get_research_cost(&bp, &research);
+        player.resources -= cost.clone();
+        player.research_queue.push((research, cost.clone()));
+        let player = player.id.clone();
+        board.events.emit(|| GameEvent::Spent {
+            player,
+            resources: cost,
+        });
+    }
+
+    fn undo(&self, board: &mut Board) {
+        let player = board.get_current_player_mut();
+        if let Some((_, paid)) = player.research_queue.pop() {
+            player.resources += paid;
+        }
+    }
+}
+
+impl ActResearch {
+    /// The tech is neither known nor queued and the current player could research it, ignoring the cost
+    pub fn can_research(board: &Board, tech_id: &TechId) -> bool {
+        let player = board.get_current_player();
+        if player.researched_technologies.contains(tech_id)
+            || player.is_researching(&QueuedResearch::Tech(tech_id.clone()))
         {
             return false;
         }
 
-        let tech_bp = board.bp.get_tech(&self.tech_id);
+        let tech_bp = board.bp.get_tech(tech_id);
         if !board.get_player_units(&player.id).any(|unit| {
             !unit.in_construction
                 && tech_bp
@@ -42,42 +71,129 @@ impl Act for ActResearch {
             return false;
         }
 
-        let cost = tech_bp.cost.clone() - player.tech_discount.clone();
-        let affordable = player.resources.contains(&cost);
-        tech_bp.level <= player.level && affordable
+        tech_bp.level <= player.level
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ActResearchQueue {
+    pub action: ResearchQueueAction,
+}
+
+impl Act for ActResearchQueue {
+    type Precondition = ();
+
+    fn generate(_pre: &Self::Precondition, board: &mut Board) -> Vec<Self> {
+        let queue = board.get_current_player().research_queue.clone();
+        let techs: Vec<TechId> = board
+            .bp()
+            .techs
+            .keys()
+            .filter(|tech_id| ActResearch::can_research(board, tech_id))
+            .cloned()
+            .collect();
+        queue
+            .into_iter()
+            .enumerate()
+            .flat_map(|(index, (research, paid))| {
+                techs
+                    .iter()
+                    .map(|tech_id| {
+                        ResearchQueueAction::Switch(
+                            index,
+                            research.clone(),
+                            paid.clone(),
+                            tech_id.clone(),
+                        )
+                    })
+                    .chain([ResearchQueueAction::Cancel(
+                        index,
+                        research.clone(),
+                        paid.clone(),
+                    )])
+                    .collect::<Vec<_>>()
+            })
+            .map(|action| ActResearchQueue { action })
+            .filter(|act| act.is_valid(board))
+            .collect()
+    }
+
+    fn is_valid(&self, board: &Board) -> bool {
+        let player = board.get_current_player();
+        let (index, research, paid) = match &self.action {
+            ResearchQueueAction::Cancel(index, research, paid)
+            | ResearchQueueAction::Switch(index, research, paid, _) => (index, research, paid),
+        };
+        if player.research_queue.get(*index) != Some(&(research.clone(), paid.clone())) {
+            return false;
+        }
+        match &self.action {
+            ResearchQueueAction::Cancel(..) => true,
+            ResearchQueueAction::Switch(.., tech_id) => {
+                let cost =
+                    player.get_research_cost(board.bp(), &QueuedResearch::Tech(tech_id.clone()));
+                ActResearch::can_research(board, tech_id)
+                    && (player.resources.clone() + paid.clone()).contains(&cost)
+            }
+        }
     }
 
     fn apply(&self, board: &mut Board) {
         let bp = board.bp.clone();
-        let player_id = board.current_player_turn.clone();
-        let tech_bp = bp.get_tech(&self.tech_id);
-        let player = board.get_player_mut(&player_id);
-        player.resources -= tech_bp.cost.clone() - player.tech_discount.clone();
-        if let Some(prev) = player.research_queued.take() {
-            match prev {
-                QueuedResearch::Tech(_) => {
-                    player.resources += tech_bp.cost.clone() - player.tech_discount.clone();
-                }
-                QueuedResearch::AgeUp => {
-                    let cost = player.get_age_up_cost();
-                    player.resources += cost;
-                }
+        let player = board.get_current_player_mut();
+        let player_id = player.id.clone();
+        match &self.action {
+            ResearchQueueAction::Cancel(index, _, paid) => {
+                player.research_queue.remove(*index);
+                player.resources += paid.clone();
+                board.events.emit(|| GameEvent::Refunded {
+                    player: player_id,
+                    resources: paid.clone(),
+                });
+            }
+            ResearchQueueAction::Switch(index, _, paid, tech_id) => {
+                let switched = QueuedResearch::Tech(tech_id.clone());
+                let cost = player.get_research_cost(&bp, &switched);
+                player.resources += paid.clone();
+                player.resources -= cost.clone();
+                player.research_queue[*index] = (switched, cost.clone());
+                board.events.emit(|| GameEvent::Refunded {
+                    player: player_id.clone(),
+                    resources: paid.clone(),
+                });
+                board.events.emit(|| GameEvent::Spent {
+                    player: player_id,
+                    resources: cost,
+                });
             }
         }
-        player.research_queued = Some(QueuedResearch::Tech(self.tech_id.clone()));
     }
 
     fn undo(&self, board: &mut Board) {
-        let bp = board.bp.clone();
-        let player_id = board.current_player_turn.clone();
-        let player = board.get_player_mut(&player_id);
-        let tech_bp = bp.get_tech(&self.tech_id);
-        if let Some(_queued) = player.research_queued.take() {
-            player.resources += tech_bp.cost.clone() - player.tech_discount.clone();
+        let player = board.get_current_player_mut();
+        match &self.action {
+            ResearchQueueAction::Cancel(index, research, paid) => {
+                player.resources -= paid.clone();
+                player
+                    .research_queue
+                    .insert(*index, (research.clone(), paid.clone()));
+            }
+            ResearchQueueAction::Switch(index, research, paid, _) => {
+                let (_, switched_paid) = player.research_queue[*index].clone();
+                player.resources += switched_paid;
+                player.resources -= paid.clone();
+                player.research_queue[*index] = (research.clone(), paid.clone());
+            }
         }
     }
 }
 
+impl From<ActResearchQueue> for PlayerAction {
+    fn from(value: ActResearchQueue) -> Self {
+        PlayerAction::ResearchQueue(value.action)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ActAgeUp {
     pub this: UnitTarget,
@@ -101,38 +217,30 @@ impl Act for ActAgeUp {
         let player = board.get_player(&self.this.unit.owner);
         player.can_age_up(board.bp())
             && player.resources.contains(&player.get_age_up_cost())
-            && player.research_queued != Some(QueuedResearch::AgeUp)
+            && !player.is_researching(&QueuedResearch::AgeUp)
             && !self.this.unit.done
             && !self.this.unit.in_construction
             && player.id == board.current_player_turn
     }
 
     fn apply(&self, board: &mut Board) {
-        let bp = board.bp.clone();
-        let player_id = board.current_player_turn.clone();
-        let player = board.get_player_mut(&player_id);
-        let cost = player.get_age_up_cost().clone();
+        let player = board.get_current_player_mut();
+        let cost = player.get_age_up_cost();
         player.resources -= cost.clone();
-        if let Some(prev) = player.research_queued.take() {
-            match prev {
-                QueuedResearch::Tech(id) => {
-                    player.resources +=
-                        bp.as_ref().get_tech(&id).cost.clone() - player.tech_discount.clone();
-                }
-                QueuedResearch::AgeUp => {
-                    player.resources += cost - player.tech_discount.clone();
-                }
-            }
-        }
-        player.research_queued = Some(QueuedResearch::AgeUp);
+        player
+            .research_queue
+            .push((QueuedResearch::AgeUp, cost.clone()));
+        let player = player.id.clone();
+        board.events.emit(|| GameEvent::Spent {
+            player,
+            resources: cost,
+        });
     }
 
     fn undo(&self, board: &mut Board) {
-        let player_id = board.current_player_turn.clone();
-        let player = board.get_player_mut(&player_id);
-        let cost = player.get_age_up_cost();
-        if let Some(_queued) = player.research_queued.take() {
-            player.resources += cost;
+        let player = board.get_current_player_mut();
+        if let Some((_, paid)) = player.research_queue.pop() {
+            player.resources += paid;
         }
     }
 }
@@ -1,7 +1,6 @@
 #[cfg(test)]
 mod travel {
     use crate::{actions::travel::ActTravel, prelude::*, v};
-    use std::{collections::HashMap, sync::Arc};
 
     #[macro_export]
     macro_rules! travel_case {
@@ -11,21 +10,16 @@ mod travel {
                 let blueprints =
                     Blueprints::from_assets_location(&("../../".to_string() + BLUEPRINTS_PATH))
                         .unwrap();
-                let mut board = Board {
-                    bp: Arc::new(blueprints.clone()),
-                    grid: parse_map(&blueprints, &$terrain).unwrap().grid,
-                    players: vec![Player {
+                let mut board = Board::new(
+                    &blueprints,
+                    parse_map(&blueprints, &$terrain).unwrap().grid,
+                    vec![Player {
                         id: PlayerId::new(0),
                         color: 0x00900000,
                         symbol: "@".to_string(),
                         ..Default::default()
                     }],
-                    day: 0,
-                    current_player_turn: PlayerId::new(0),
-                    player_turn_order: vec![PlayerId::new(0)],
-                    fog: HashMap::new(),
-                    fog_base: FogTile::Visible,
-                };
+                );
                 {
                     let tile = board.grid.get_at_mut(&$from);
                     let _ = tile.unit.insert(Unit {
@@ -102,12 +96,23 @@ mod travel {
         v!(0, 1),
         UnitId(0)
     );
+
+    travel_case!(
+        ship,
+        r"
+--- ... ... ---
+--- ... ... ...",
+        r"
+ 0   1   1   0
+ 0   1   1   1",
+        v!(1, 0),
+        UnitId(58)
+    );
 }
 
 #[cfg(test)]
 mod exhaustive {
     use crate::{actions::player_action::Pre, prelude::*, v};
-    use std::{collections::HashMap, sync::Arc};
 
     #[macro_export]
     macro_rules! undo_case {
@@ -118,35 +123,33 @@ mod exhaustive {
                     Blueprints::from_assets_location(&("../../".to_string() + BLUEPRINTS_PATH))
                         .unwrap();
                 let mut board = Board {
-                    bp: Arc::new(blueprints.clone()),
-                    grid: parse_map(&blueprints, &$terrain).unwrap().grid,
-                    players: vec![
-                        Player {
-                            id: PlayerId::new(0),
-                            color: 0x00900000,
-                            symbol: "@".to_string(),
-                            resources: Resources {
-                                food: 1200,
-                                gold: 1200,
+                    player_turn_order: vec![PlayerId::new(0)],
+                    ..Board::new(
+                        &blueprints,
+                        parse_map(&blueprints, &$terrain).unwrap().grid,
+                        vec![
+                            Player {
+                                id: PlayerId::new(0),
+                                color: 0x00900000,
+                                symbol: "@".to_string(),
+                                resources: Resources {
+                                    food: 1200,
+                                    gold: 1200,
+                                },
+                                ..Default::default()
                             },
-                            ..Default::default()
-                        },
-                        Player {
-                            id: PlayerId::new(1),
-                            color: 0x00900090,
-                            symbol: "!".to_string(),
-                            resources: Resources {
-                                food: 1200,
-                                gold: 1200,
+                            Player {
+                                id: PlayerId::new(1),
+                                color: 0x00900090,
+                                symbol: "!".to_string(),
+                                resources: Resources {
+                                    food: 1200,
+                                    gold: 1200,
+                                },
+                                ..Default::default()
                             },
-                            ..Default::default()
-                        },
-                    ],
-                    day: 0,
-                    current_player_turn: PlayerId::new(0),
-                    player_turn_order: vec![PlayerId::new(0)],
-                    fog: HashMap::new(),
-                    fog_base: FogTile::Visible,
+                        ],
+                    )
                 };
                 for (pos, unit, owner) in $units.iter() {
                     let tile = board.grid.get_at_mut(&pos);
@@ -217,43 +220,41 @@ mod exhaustive {
         let bp =
             Blueprints::from_assets_location(&("../../".to_string() + BLUEPRINTS_PATH)).unwrap();
         let mut board = Board {
-            bp: Arc::new(bp.clone()),
-            grid: parse_map(
+            player_turn_order: vec![PlayerId::new(0)],
+            ..Board::new(
                 &bp,
-                r"
+                parse_map(
+                    &bp,
+                    r"
 /\\ /\\ ---
 =-- /\\ =--
 =-- ... =--",
-            )
-            .unwrap()
-            .grid,
-            players: vec![
-                Player {
-                    id: PlayerId::new(0),
-                    color: 0x00900000,
-                    symbol: "@".to_string(),
-                    resources: Resources {
-                        food: 1200,
-                        gold: 1200,
+                )
+                .unwrap()
+                .grid,
+                vec![
+                    Player {
+                        id: PlayerId::new(0),
+                        color: 0x00900000,
+                        symbol: "@".to_string(),
+                        resources: Resources {
+                            food: 1200,
+                            gold: 1200,
+                        },
+                        ..Default::default()
                     },
-                    ..Default::default()
-                },
-                Player {
-                    id: PlayerId::new(1),
-                    color: 0x00900090,
-                    symbol: "!".to_string(),
-                    resources: Resources {
-                        food: 1200,
-                        gold: 1200,
+                    Player {
+                        id: PlayerId::new(1),
+                        color: 0x00900090,
+                        symbol: "!".to_string(),
+                        resources: Resources {
+                            food: 1200,
+                            gold: 1200,
+                        },
+                        ..Default::default()
                     },
-                    ..Default::default()
-                },
-            ],
-            day: 0,
-            current_player_turn: PlayerId::new(0),
-            player_turn_order: vec![PlayerId::new(0)],
-            fog: HashMap::new(),
-            fog_base: FogTile::Visible,
+                ],
+            )
         };
 
         if let Some(unit_id) = bp.get_unit_from_name("Monk") {
@@ -310,43 +311,41 @@ mod exhaustive {
         let bp =
             Blueprints::from_assets_location(&("../../".to_string() + BLUEPRINTS_PATH)).unwrap();
         let mut board = Board {
-            bp: Arc::new(bp.clone()),
-            grid: parse_map(
+            player_turn_order: vec![PlayerId::new(0)],
+            ..Board::new(
                 &bp,
-                r"
+                parse_map(
+                    &bp,
+                    r"
 --- --- ---
 --- --- ---
 --- --- ---",
-            )
-            .unwrap()
-            .grid,
-            players: vec![
-                Player {
-                    id: PlayerId::new(0),
-                    color: 0x00900000,
-                    symbol: "@".to_string(),
-                    resources: Resources {
-                        food: 1200,
-                        gold: 1200,
+                )
+                .unwrap()
+                .grid,
+                vec![
+                    Player {
+                        id: PlayerId::new(0),
+                        color: 0x00900000,
+                        symbol: "@".to_string(),
+                        resources: Resources {
+                            food: 1200,
+                            gold: 1200,
+                        },
+                        ..Default::default()
                     },
-                    ..Default::default()
-                },
-                Player {
-                    id: PlayerId::new(1),
-                    color: 0x00900090,
-                    symbol: "!".to_string(),
-                    resources: Resources {
-                        food: 1200,
-                        gold: 1200,
+                    Player {
+                        id: PlayerId::new(1),
+                        color: 0x00900090,
+                        symbol: "!".to_string(),
+                        resources: Resources {
+                            food: 1200,
+                            gold: 1200,
+                        },
+                        ..Default::default()
                     },
-                    ..Default::default()
-                },
-            ],
-            day: 0,
-            current_player_turn: PlayerId::new(0),
-            player_turn_order: vec![PlayerId::new(0)],
-            fog: HashMap::new(),
-            fog_base: FogTile::Visible,
+                ],
+            )
         };
 
         let initial = board.clone();
@@ -434,3 +433,1087 @@ mod exhaustive {
         }
     }
 }
+
+#[cfg(test)]
+mod end_turn {
+    use crate::{
+        prelude::*,
+        test::{players_board, test_bp},
+        v,
+    };
+
+    fn test_board(seed: u64) -> Board {
+        let bp = test_bp();
+        let map = "--- --- --- ---\n--- --- --- ---\n--- --- --- ---";
+        let mut board = Board {
+            rng: BoardRng::new(seed),
+            ..players_board(&bp, map, 2, Resources::default())
+        };
+        let militia = bp.get_unit_from_name("Militia").unwrap();
+        for x in 0..4 {
+            board.grid.get_at_mut(&v!(x, 1)).unit = Some(Unit {
+                blueprint_id: militia.clone(),
+                owner: PlayerId::new(0),
+                holding_collectable: Some(Collectable::Ruins),
+                conversion_attempt: Some((PlayerId::new(1), 0)),
+                ..Default::default()
+            });
+        }
+        board
+    }
+
+    #[test]
+    fn same_seed_same_outcome() {
+        for seed in 0..20 {
+            let mut board_a = test_board(seed);
+            let mut board_b = test_board(seed);
+            PlayerAction::PassTurn.apply(&mut board_a);
+            PlayerAction::PassTurn.apply(&mut board_b);
+            assert_eq!(board_a, board_b, "seed: {}", seed);
+        }
+    }
+
+    #[test]
+    fn rng_advances() {
+        let mut board = test_board(7);
+        let before = board.rng.clone();
+        PlayerAction::PassTurn.apply(&mut board);
+        assert_ne!(before, board.rng);
+        assert_eq!(board.rng.seed(), 7);
+    }
+}
+
+#[cfg(test)]
+mod fog_undo {
+    use crate::{
+        actions::travel::ActTravel,
+        prelude::*,
+        test::{place_unit, players_board, test_bp},
+        v,
+    };
+
+    fn test_board() -> Board {
+        let row = ["---"; 12].join(" ");
+        let map = [row.as_str(); 3].join("\n");
+        let mut board = Board {
+            fog_base: FogTile::Hidden,
+            ..players_board(&test_bp(), &map, 1, Resources::default())
+        };
+        place_unit(&mut board, v!(0, 1), "Militia", 0);
+        board.init_fog();
+        board.refresh_fog();
+        board
+    }
+
+    fn move_to(board: &Board, destination: IVec2, action: UnitAction) -> PlayerAction {
+        let target = board.get_pos_target(&UnitPos::top(v!(0, 1))).unwrap();
+        let reachable = ActTravel::get_reachable(&target, board)
+            .into_iter()
+            .find(|r| r.destination == destination)
+            .unwrap();
+        PlayerAction::Unit {
+            target,
+            destination,
+            pickup: None,
+            action,
+            path: reachable.path,
+        }
+    }
+
+    #[test]
+    fn undo_allowed_if_nothing_revealed() {
+        let mut board = test_board();
+        let action = move_to(&board, v!(0, 1), UnitAction::Done);
+        let (applied, undoable) = board.apply_fogged(action.clone());
+        assert_eq!(applied, action);
+        assert!(undoable);
+    }
+
+    #[test]
+    fn undo_refused_if_tiles_revealed() {
+        let mut board = test_board();
+        let before = board.fog.get(&PlayerId::new(0)).unwrap().clone();
+        let action = move_to(&board, v!(2, 1), UnitAction::Done);
+        let (_, undoable) = board.apply_fogged(action);
+        assert!(!undoable);
+        assert!(!board.revealed_tiles(&PlayerId::new(0), &before).is_empty());
+    }
+}
+
+#[cfg(test)]
+mod diplomacy {
+    use crate::{
+        actions::player_action::Pre,
+        prelude::*,
+        test::{place_unit, players_board, test_bp},
+        v,
+    };
+
+    fn test_board() -> Board {
+        let map = "--- --- --- ---\n--- --- --- ---";
+        let mut board = players_board(&test_bp(), map, 2, Resources::default());
+        board.players[0].resources = Resources::new(200, 0);
+        place_unit(&mut board, v!(0, 0), "Militia", 0);
+        place_unit(&mut board, v!(1, 0), "Militia", 1);
+        board
+    }
+
+    fn apply_valid(board: &mut Board, action: PlayerAction) {
+        assert!(action.is_valid(board), "{:?}", action);
+        action.apply(board);
+    }
+
+    fn alliance(board: &mut Board) {
+        let (p0, p1) = (PlayerId::new(0), PlayerId::new(1));
+        apply_valid(
+            board,
+            PlayerAction::Diplomacy(DiplomacyAction::Propose(p1.clone(), Relation::Alliance)),
+        );
+        board.current_player_turn = p1;
+        apply_valid(board, PlayerAction::Diplomacy(DiplomacyAction::Accept(p0)));
+    }
+
+    #[test]
+    fn accepted_alliance_stops_hostilities() {
+        let mut board = test_board();
+        let (p0, p1) = (PlayerId::new(0), PlayerId::new(1));
+        assert!(board.is_hostile(&p0, &p1));
+        assert_eq!(board.get_outcome(), None);
+
+        alliance(&mut board);
+        assert!(board.is_allied(&p0, &p1));
+        assert!(board.diplomacy.proposals.is_empty());
+        assert_eq!(
+            board.get_outcome().map(|outcome| outcome.victory),
+            Some(Victory::Conquest)
+        );
+
+        apply_valid(
+            &mut board,
+            PlayerAction::Diplomacy(DiplomacyAction::Break(p0.clone())),
+        );
+        assert!(board.is_hostile(&p0, &p1));
+    }
+
+    #[test]
+    fn ceasefire_only_during_war() {
+        let mut board = test_board();
+        let p1 = PlayerId::new(1);
+        let ceasefire =
+            PlayerAction::Diplomacy(DiplomacyAction::Propose(p1.clone(), Relation::Ceasefire));
+        assert!(ceasefire.is_valid(&board));
+        alliance(&mut board);
+        board.current_player_turn = PlayerId::new(0);
+        assert!(!ceasefire.is_valid(&board));
+    }
+
+    #[test]
+    fn tribute_moves_resources() {
+        let mut board = test_board();
+        let p1 = PlayerId::new(1);
+        apply_valid(
+            &mut board,
+            PlayerAction::Diplomacy(DiplomacyAction::Tribute(p1.clone(), Resource::Food)),
+        );
+        assert_eq!(board.get_player(&PlayerId::new(0)).resources.food, 100);
+        assert_eq!(board.get_player(&p1).resources.food, 100);
+        assert!(
+            !PlayerAction::Diplomacy(DiplomacyAction::Tribute(p1, Resource::Gold)).is_valid(&board)
+        );
+    }
+
+    #[test]
+    fn undo_restores_board() {
+        let board = test_board();
+        let mut pre = board.clone();
+        let actions: Vec<PlayerAction> = PlayerAction::generate(&Pre::Global, &mut pre)
+            .into_iter()
+            .filter(|action| matches!(action, PlayerAction::Diplomacy(_)))
+            .collect();
+        assert!(!actions.is_empty());
+        for action in actions {
+            let mut local = board.clone();
+            action.apply(&mut local);
+            action.undo(&mut local);
+            assert_eq!(board, local, "{:?}", action);
+        }
+
+        let mut allied = test_board();
+        allied
+            .diplomacy
+            .propose(&PlayerId::new(1), &PlayerId::new(0), Relation::Alliance);
+        let accept = PlayerAction::Diplomacy(DiplomacyAction::Accept(PlayerId::new(1)));
+        let mut local = allied.clone();
+        apply_valid(&mut local, accept.clone());
+        accept.undo(&mut local);
+        assert_eq!(allied, local);
+    }
+}
+
+#[cfg(test)]
+mod walls {
+    use crate::{
+        actions::{build::ActBuild, travel::ActTravel},
+        prelude::*,
+        test::{place_unit, players_board, test_bp},
+        v,
+    };
+    fn test_board() -> Board {
+        let map = "--- --- --- --- ---\n--- --- --- --- ---\n--- --- --- --- ---";
+        let mut board = players_board(&test_bp(), map, 2, Resources::default());
+        for (y, name) in [(0, "Stone Wall"), (1, "Gate"), (2, "Stone Wall")] {
+            place_unit(&mut board, v!(2, y), name, 1);
+        }
+        board
+    }
+
+    fn reachable_by(board: &mut Board, owner: u32) -> Vec<IVec2> {
+        board.grid.get_at_mut(&v!(0, 1)).unit = Some(Unit {
+            blueprint_id: board.bp.get_unit_from_name("Militia").unwrap(),
+            owner: PlayerId::new(owner),
+            ..Default::default()
+        });
+        let target = board.get_pos_target(&UnitPos::top(v!(0, 1))).unwrap();
+        ActTravel::get_reachable(&target, board)
+            .into_iter()
+            .map(|reachable| reachable.destination)
+            .collect()
+    }
+
+    #[test]
+    fn hostile_units_are_stopped() {
+        let mut board = test_board();
+        let reachable = reachable_by(&mut board, 0);
+        assert!(reachable.iter().all(|xy| xy.x < 2), "{:?}", reachable);
+    }
+
+    #[test]
+    fn friendly_units_pass_gates() {
+        let mut board = test_board();
+        let reachable = reachable_by(&mut board, 1);
+        assert!(reachable.contains(&v!(2, 1)));
+        assert!(reachable.contains(&v!(3, 1)));
+        assert!(!reachable.contains(&v!(2, 0)));
+        assert!(!reachable.contains(&v!(2, 2)));
+    }
+
+    #[test]
+    fn allied_units_pass_gates() {
+        let mut board = test_board();
+        board
+            .diplomacy
+            .set(&PlayerId::new(0), &PlayerId::new(1), Relation::Alliance);
+        let reachable = reachable_by(&mut board, 0);
+        assert!(reachable.contains(&v!(3, 1)));
+        assert!(!reachable.contains(&v!(2, 0)));
+    }
+
+    #[test]
+    fn walls_are_chained() {
+        let mut board = test_board();
+        let wall = board.bp.get_unit_from_name("Stone Wall").unwrap();
+        let chained = v!(2, 1);
+        let alone = v!(4, 1);
+        for xy in [chained, alone] {
+            board.grid.get_at_mut(&xy).building = Some(Unit {
+                blueprint_id: wall.clone(),
+                owner: PlayerId::new(1),
+                ..Default::default()
+            });
+        }
+        assert!(ActBuild::is_building_active(&board, UnitPos::bot(chained)));
+        assert!(!ActBuild::is_building_active(&board, UnitPos::bot(alone)));
+    }
+
+    #[test]
+    fn walls_reinforce_each_other() {
+        let board = test_board();
+        let gate = board.get_pos_target(&UnitPos::bot(v!(2, 1))).unwrap();
+        let bonus = board.get_building_bonus(&gate);
+        assert_eq!(bonus.perc.stats.defence, 20);
+    }
+}
+
+#[cfg(test)]
+mod naval {
+    use crate::{
+        actions::{player_action::Pre, travel::ActTravel},
+        prelude::*,
+        test::{place_unit, players_board, test_bp},
+        v,
+    };
+    fn test_board() -> Board {
+        let map = "--- --- ... ... --- ---\n--- --- ... ... --- ---";
+        let mut board = players_board(&test_bp(), map, 1, Resources::default());
+        place_unit(&mut board, v!(1, 0), "Militia", 0);
+        place_unit(&mut board, v!(2, 0), "Transport Ship", 0);
+        board
+    }
+
+    fn unit_actions(board: &mut Board, at: IVec2) -> Vec<PlayerAction> {
+        PlayerAction::generate(&Pre::Target(UnitPos::top(at)), board)
+    }
+
+    #[test]
+    fn land_units_cross_water_on_transports() {
+        let mut board = test_board();
+        let embark = unit_actions(&mut board, v!(1, 0))
+            .into_iter()
+            .find(|action| {
+                matches!(
+                    action,
+                    PlayerAction::Unit {
+                        action: UnitAction::Embark(_),
+                        ..
+                    }
+                )
+            })
+            .unwrap();
+        let before_embark = board.clone();
+        embark.apply(&mut board);
+        assert!(board.grid.get_at(&v!(1, 0)).unit.is_none());
+        assert_eq!(
+            board
+                .grid
+                .get_at(&v!(2, 0))
+                .unit
+                .as_ref()
+                .unwrap()
+                .cargo
+                .len(),
+            1
+        );
+
+        let unload = unit_actions(&mut board, v!(2, 0))
+            .into_iter()
+            .find(|action| {
+                matches!(
+                    action,
+                    PlayerAction::Unit {
+                        action: UnitAction::Unload(0, xy),
+                        ..
+                    } if xy.x == 4
+                )
+            })
+            .unwrap();
+        let before_unload = board.clone();
+        unload.apply(&mut board);
+        let landed = board
+            .get_units_pos()
+            .find(|(unit, _)| board.bp.get_unit(&unit.blueprint_id).domain == MovementDomain::Land);
+        assert_eq!(landed.map(|(_, xy)| xy.x), Some(4));
+
+        unload.undo(&mut board);
+        assert_eq!(board, before_unload);
+        embark.undo(&mut board);
+        assert_eq!(board, before_embark);
+    }
+
+    #[test]
+    fn ships_enter_friendly_docks() {
+        let mut board = test_board();
+        let dock = board.bp.get_unit_from_name("Dock").unwrap();
+        board.grid.get_at_mut(&v!(4, 1)).building = Some(Unit {
+            blueprint_id: dock,
+            owner: PlayerId::new(0),
+            ..Default::default()
+        });
+        let ship = board.get_pos_target(&UnitPos::top(v!(2, 0))).unwrap();
+        let reachable: Vec<IVec2> = ActTravel::get_reachable(&ship, &board)
+            .into_iter()
+            .map(|reachable| reachable.destination)
+            .collect();
+        assert!(reachable.contains(&v!(4, 1)));
+        assert!(!reachable.contains(&v!(4, 0)));
+        assert!(!reachable.contains(&v!(1, 1)));
+    }
+}
+
+#[cfg(test)]
+mod gaia {
+    use crate::{
+        actions::diplomacy::ActDiplomacy,
+        prelude::*,
+        test::{place_unit, players_board, test_bp},
+        v,
+    };
+
+    const MAP: &str = r"
+--- --- --- ---
+--- --- --- ---
+gaia 2 0 Wolf
+gaia 3 1 Guardian";
+
+    fn test_board() -> Board {
+        let bp = test_bp();
+        let mut board = players_board(&bp, MAP, 1, Resources::default());
+        for (unit_id, xy) in parse_map(&bp, MAP).unwrap().gaia_units {
+            board.grid.get_at_mut(&xy).unit = Some(Unit {
+                blueprint_id: unit_id,
+                owner: PlayerId::GAIA,
+                ..Default::default()
+            });
+        }
+        place_unit(&mut board, v!(1, 0), "Militia", 0);
+        board.add_gaia();
+        board
+    }
+
+    #[test]
+    fn map_places_gaia_units() {
+        let board = test_board();
+        assert_eq!(board.get_player_units(&PlayerId::GAIA).count(), 2);
+        assert_eq!(
+            board.player_turn_order,
+            vec![PlayerId::new(0), PlayerId::GAIA]
+        );
+        let bp = board.bp();
+        assert!(matches!(
+            parse_map(bp, "--- ---\ngaia 0 0 Dragon"),
+            Err(ParseMapError::GaiaUnitNotRecognized(_))
+        ));
+        assert!(matches!(
+            parse_map(bp, "--- ---\ngaia 5 0 Wolf"),
+            Err(ParseMapError::GaiaUnitNotRecognized(_))
+        ));
+    }
+
+    #[test]
+    fn gaia_is_hostile_to_everyone() {
+        let board = test_board();
+        assert!(board.is_hostile(&PlayerId::new(0), &PlayerId::GAIA));
+        let mut board = board;
+        board
+            .diplomacy
+            .set(&PlayerId::new(0), &PlayerId::GAIA, Relation::Alliance);
+        assert!(board.is_hostile(&PlayerId::new(0), &PlayerId::GAIA));
+        assert!(ActDiplomacy::generate(&(), &mut board).is_empty());
+    }
+
+    #[test]
+    fn gaia_attacks_and_passes() {
+        let mut board = test_board();
+        PlayerAction::PassTurn.apply(&mut board);
+        assert_eq!(board.current_player_turn, PlayerId::GAIA);
+        let before = board.clone();
+        let actions = get_machine_turn(&board.bp().clone(), &mut board, &MachineOpponent::Gaia);
+        assert_eq!(board, before);
+        assert_eq!(actions.last(), Some(&PlayerAction::PassTurn));
+        assert!(actions.iter().any(|act| matches!(
+            act,
+            PlayerAction::Unit {
+                action: UnitAction::Attack(_),
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn gaia_never_wins() {
+        let mut board = test_board();
+        assert_eq!(board.get_winners(), Some(vec![PlayerId::new(0)]));
+        board.grid.get_at_mut(&v!(1, 0)).unit = None;
+        assert_eq!(board.get_winners(), Some(vec![]));
+    }
+}
+
+#[cfg(test)]
+mod map_starts {
+    use crate::{
+        prelude::*,
+        test::{temp_path, test_bp},
+        v,
+    };
+
+    const MAP: &str = "\
+--- -0- --- -1-
+--- --- --- ---
+start 1 300 200 2
+spawn 1 2 1 Knights
+spawn 1 3 1 Town Center";
+
+    fn settings(path: &str, players: u32) -> MapSettings {
+        MapSettings {
+            path: path.to_string(),
+            players: (0..players)
+                .map(|id| MapPlayerSettings {
+                    id: PlayerId::new(id),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn map_declares_units_and_starts() {
+        let bp = test_bp();
+        let path = temp_path("tage_map_starts.txt");
+        std::fs::write(&path, MAP).unwrap();
+        let (grid, starts) = load_map_with_starts(&bp, &settings(&path, 2)).unwrap();
+
+        let name = |unit: &Option<Unit>| {
+            let unit = unit.as_ref().unwrap();
+            (
+                unit.owner.clone(),
+                bp.get_unit(&unit.blueprint_id).header.name.clone(),
+            )
+        };
+        // spawn 0 has nothing declared and gets the default units
+        assert_eq!(
+            name(&grid.get_at(&v!(1, 0)).unit),
+            (PlayerId::new(0), "Villager".to_string())
+        );
+        assert_eq!(
+            name(&grid.get_at(&v!(2, 1)).unit),
+            (PlayerId::new(1), "Knights".to_string())
+        );
+        assert_eq!(
+            name(&grid.get_at(&v!(3, 1)).building),
+            (PlayerId::new(1), "Town Center".to_string())
+        );
+        assert!(grid.get_at(&v!(3, 0)).unit.is_none());
+        assert_eq!(
+            starts.get(&PlayerId::new(1)),
+            Some(&PlayerStart {
+                resources: Resources::new(300, 200),
+                level: 2,
+            })
+        );
+        assert!(!starts.contains_key(&PlayerId::new(0)));
+
+        // without a player the spawn point is left empty
+        let (grid, starts) = load_map_with_starts(&bp, &settings(&path, 1)).unwrap();
+        assert!(grid.get_at(&v!(2, 1)).unit.is_none());
+        assert!(starts.is_empty());
+    }
+
+    #[test]
+    fn map_rejects_bad_lines() {
+        let bp = test_bp();
+        for line in [
+            "spawn 0 9 0 Knights",
+            "spawn 0 0 0 Dragon",
+            "spawn 0 0 0 Castle",
+        ] {
+            assert!(matches!(
+                parse_map(&bp, &format!("--- ---\n{}", line)),
+                Err(ParseMapError::SpawnUnitNotRecognized(_))
+            ));
+        }
+        for line in [
+            "start 0 100 100",
+            "start 0 100 100 4",
+            "start 0 a 100 1",
+            "start 0 -100 100 1",
+            "start 0 100 -1 1",
+        ] {
+            assert!(matches!(
+                parse_map(&bp, &format!("--- ---\n{}", line)),
+                Err(ParseMapError::StartNotRecognized(_))
+            ));
+        }
+        for lines in [
+            "gaia 0 0 Wolf\nspawn 0 0 0 Knights",
+            "spawn 0 1 0 Knights\nspawn 1 1 0 Militia",
+        ] {
+            assert!(matches!(
+                parse_map(&bp, &format!("--- ---\n{}", lines)),
+                Err(ParseMapError::UnitsOverlap(_))
+            ));
+        }
+        // a building and a unit share the tile
+        assert!(parse_map(&bp, "--- ---\nspawn 0 1 0 Knights\nspawn 0 1 0 Town Center").is_ok());
+    }
+
+    #[test]
+    fn map_rejects_units_on_the_default_spawn_units() {
+        let bp = test_bp();
+        let path = temp_path("tage_map_overlap.txt");
+        std::fs::write(&path, "--- -0- ---\n--- --- ---\ngaia 1 1 Wolf").unwrap();
+        assert!(matches!(
+            load_map_with_starts(&bp, &settings(&path, 1)),
+            Err(ParseMapError::UnitsOverlap(pos)) if pos == v!(1, 1)
+        ));
+        assert!(load_map_with_starts(&bp, &settings(&path, 0)).is_ok());
+    }
+
+    #[test]
+    fn map_lines_roundtrip() {
+        let bp = test_bp();
+        let written = write_parsed_map(&bp, &parse_map(&bp, MAP).unwrap());
+        assert_eq!(written.trim(), MAP);
+    }
+}
+
+#[cfg(test)]
+mod no_rng {
+    use crate::{
+        actions::player_action::Pre,
+        prelude::*,
+        test::{place_unit, players_board, test_bp},
+        v,
+    };
+
+    fn test_board(no_rng: bool) -> Board {
+        let map = "--- --- --- ---\n--- --- --- ---";
+        let mut board = Board {
+            rng: BoardRng::new(0),
+            rules: Rules {
+                no_rng,
+                ruins_reward: RuinsReward::Food,
+                ..Default::default()
+            },
+            ..players_board(&test_bp(), map, 2, Resources::default())
+        };
+        board.players[0].level = 2;
+        board.players[1].level = 1;
+        board
+    }
+
+    fn convert(board: &mut Board) {
+        let action = PlayerAction::generate(&Pre::Tile(v!(0, 0)), board)
+            .into_iter()
+            .find(|act| {
+                matches!(
+                    act,
+                    PlayerAction::Unit {
+                        action: UnitAction::Convert(_),
+                        ..
+                    }
+                )
+            })
+            .unwrap();
+        action.apply(board);
+    }
+
+    #[test]
+    fn monks_deal_conversion_damage() {
+        let mut board = test_board(true);
+        place_unit(&mut board, v!(0, 0), "Monks", 0);
+        place_unit(&mut board, v!(1, 0), "Militia", 1);
+        convert(&mut board);
+        let target = board.grid.get_at(&v!(1, 0)).unit.as_ref().unwrap();
+        assert!(target.health < 100);
+        assert_eq!(target.owner, PlayerId::new(1));
+        assert!(target.conversion_attempt.is_none());
+
+        board.modify_unit(&UnitPos::top(v!(1, 0)), |unit| unit.health = 1);
+        board.modify_unit(&UnitPos::top(v!(0, 0)), |unit| unit.done = false);
+        convert(&mut board);
+        let target = board.grid.get_at(&v!(1, 0)).unit.as_ref().unwrap();
+        assert_eq!(target.owner, PlayerId::new(0));
+        assert_eq!(target.health, 1);
+    }
+
+    #[test]
+    fn monks_attempt_conversion_on_the_target() {
+        let mut board = test_board(false);
+        place_unit(&mut board, v!(0, 0), "Monks", 0);
+        place_unit(&mut board, v!(1, 0), "Militia", 1);
+        convert(&mut board);
+        let monk = board.grid.get_at(&v!(0, 0)).unit.as_ref().unwrap();
+        assert!(monk.conversion_attempt.is_none());
+        let target = board.grid.get_at(&v!(1, 0)).unit.as_ref().unwrap();
+        assert_eq!(target.health, 100);
+        assert!(target
+            .conversion_attempt
+            .as_ref()
+            .is_some_and(|(player, _)| *player == PlayerId::new(0)));
+    }
+
+    #[test]
+    fn ruins_give_the_chosen_reward() {
+        let mut board = test_board(true);
+        place_unit(&mut board, v!(0, 0), "Militia", 0);
+        board.modify_unit(&UnitPos::top(v!(0, 0)), |unit| {
+            unit.holding_collectable = Some(Collectable::Ruins)
+        });
+        PlayerAction::PassTurn.apply(&mut board);
+        assert_eq!(
+            board.get_player(&PlayerId::new(0)).resources.food,
+            RuinsReward::RESOURCES
+        );
+        assert!(board.grid.get_at(&v!(0, 0)).unit.is_some());
+    }
+
+    #[test]
+    fn markets_offer_every_unit() {
+        for no_rng in [false, true] {
+            let mut board = test_board(no_rng);
+            place_unit(&mut board, v!(3, 1), "Market", 1);
+            PlayerAction::PassTurn.apply(&mut board);
+            let market = board.grid.get_at(&v!(3, 1)).building.as_ref().unwrap();
+            if no_rng {
+                assert!(market.train_list_override.len() > 3);
+            } else {
+                assert_eq!(market.train_list_override.len(), 3);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod research_queue {
+    use crate::{
+        actions::{player_action::Pre, research::ActResearchQueue},
+        prelude::*,
+        test::{place_unit, players_board, test_bp},
+        v,
+    };
+    fn test_board() -> Board {
+        let map = "--- --- ---\n--- --- ---";
+        let mut board = players_board(&test_bp(), map, 1, Resources::new(1000, 1000));
+        place_unit(&mut board, v!(1, 1), "Town Center", 0);
+        board
+    }
+
+    fn tech(board: &Board, name: &str) -> TechId {
+        board.bp.get_tech_from_name(name).unwrap()
+    }
+
+    fn resources(board: &Board) -> Resources {
+        board.get_current_player().resources.clone()
+    }
+
+    #[test]
+    fn queued_techs_complete_one_per_turn() {
+        let mut board = test_board();
+        let (loom, scouts) = (tech(&board, "Loom"), tech(&board, "Town Scouts"));
+        PlayerAction::Research(loom.clone()).apply(&mut board);
+        assert!(!PlayerAction::Research(loom.clone()).is_valid(&board));
+        PlayerAction::Research(scouts.clone()).apply(&mut board);
+        assert_eq!(resources(&board), Resources::new(900, 700));
+
+        PlayerAction::PassTurn.apply(&mut board);
+        let player = board.get_current_player();
+        assert_eq!(player.researched_technologies, vec![loom.clone()]);
+        assert_eq!(
+            player.research_queue,
+            vec![(
+                QueuedResearch::Tech(scouts.clone()),
+                Resources::new(50, 150)
+            )]
+        );
+
+        PlayerAction::PassTurn.apply(&mut board);
+        let player = board.get_current_player();
+        assert_eq!(player.researched_technologies, vec![loom, scouts]);
+        assert!(player.research_queue.is_empty());
+    }
+
+    #[test]
+    fn cancel_refunds_and_undoes() {
+        let mut board = test_board();
+        let (loom, scouts) = (tech(&board, "Loom"), tech(&board, "Town Scouts"));
+        PlayerAction::Research(loom.clone()).apply(&mut board);
+        PlayerAction::Research(scouts.clone()).apply(&mut board);
+        let queued = board.clone();
+
+        let cancel = PlayerAction::ResearchQueue(ResearchQueueAction::Cancel(
+            0,
+            QueuedResearch::Tech(loom.clone()),
+            Resources::new(50, 150),
+        ));
+        assert!(PlayerAction::generate(&Pre::Global, &mut board).contains(&cancel));
+        cancel.apply(&mut board);
+        assert_eq!(resources(&board), Resources::new(950, 850));
+        assert_eq!(
+            board.get_current_player().research_queue,
+            vec![(QueuedResearch::Tech(scouts), Resources::new(50, 150))]
+        );
+        cancel.undo(&mut board);
+        assert_eq!(board, queued);
+    }
+
+    #[test]
+    fn switch_keeps_the_position() {
+        let mut board = test_board();
+        let (loom, scouts, soles) = (
+            tech(&board, "Loom"),
+            tech(&board, "Town Scouts"),
+            tech(&board, "Leather Soles"),
+        );
+        PlayerAction::Research(loom.clone()).apply(&mut board);
+        PlayerAction::Research(scouts.clone()).apply(&mut board);
+        let queued = board.clone();
+
+        let switch = ActResearchQueue {
+            action: ResearchQueueAction::Switch(
+                0,
+                QueuedResearch::Tech(loom.clone()),
+                Resources::new(50, 150),
+                soles.clone(),
+            ),
+        };
+        assert!(switch.is_valid(&board));
+        assert!(!ActResearchQueue {
+            action: ResearchQueueAction::Switch(
+                0,
+                QueuedResearch::Tech(loom),
+                Resources::new(50, 150),
+                scouts.clone(),
+            ),
+        }
+        .is_valid(&board));
+        switch.apply(&mut board);
+        assert_eq!(
+            board.get_current_player().research_queue,
+            vec![
+                (QueuedResearch::Tech(soles), Resources::new(50, 150)),
+                (QueuedResearch::Tech(scouts), Resources::new(50, 150))
+            ]
+        );
+        assert_eq!(resources(&board), queued.get_current_player().resources);
+        switch.undo(&mut board);
+        assert_eq!(board, queued);
+    }
+
+    #[test]
+    fn cancel_refunds_the_discounted_price() {
+        let mut board = test_board();
+        let (loom, scouts) = (tech(&board, "Loom"), tech(&board, "Town Scouts"));
+        board.players[0].tech_discount = Resources::new(50, 50);
+        PlayerAction::Research(loom).apply(&mut board);
+        PlayerAction::Research(scouts.clone()).apply(&mut board);
+        assert_eq!(resources(&board), Resources::new(1000, 800));
+
+        PlayerAction::PassTurn.apply(&mut board);
+        assert_eq!(
+            board.get_current_player().tech_discount,
+            Resources::default()
+        );
+        let before = resources(&board);
+        let cancel = ActResearchQueue {
+            action: ResearchQueueAction::Cancel(
+                0,
+                QueuedResearch::Tech(scouts),
+                Resources::new(0, 100),
+            ),
+        };
+        assert!(cancel.is_valid(&board));
+        cancel.apply(&mut board);
+        assert_eq!(resources(&board), before + Resources::new(0, 100));
+    }
+}
+
+#[cfg(test)]
+mod trade {
+    use crate::{
+        actions::trade::{ActTrade, BASE_TRADE_RATE, TRADE_RATE_STEP},
+        prelude::*,
+        test::{place_unit, players_board, test_bp},
+        v,
+    };
+    fn test_board(market: MarketModel) -> Board {
+        let map = "--- --- ---\n--- --- ---";
+        let mut board = Board {
+            rules: Rules {
+                market,
+                ..Default::default()
+            },
+            ..players_board(&test_bp(), map, 2, Resources::new(2000, 2000))
+        };
+        place_unit(&mut board, v!(0, 0), "Town Center", 0);
+        place_unit(&mut board, v!(2, 0), "Town Center", 1);
+        board
+    }
+
+    fn trade(board: &mut Board, x: i32, resource: Resource) -> PlayerAction {
+        let target = board.get_pos_target(&UnitPos::bot(v!(x, 0))).unwrap();
+        let action: PlayerAction = ActTrade {
+            this: target,
+            resource,
+        }
+        .into();
+        action.apply(board);
+        action
+    }
+
+    fn rate(board: &Board, owner: u32, resource: Resource) -> i32 {
+        ActTrade::get_rate(&PlayerId::new(owner), &resource, board)
+    }
+
+    #[test]
+    fn rates_move_with_trades_and_recover() {
+        let mut board = test_board(MarketModel::PerPlayer);
+        assert_eq!(rate(&board, 0, Resource::Food), BASE_TRADE_RATE);
+        let before = board.clone();
+        let action = trade(&mut board, 0, Resource::Food);
+        assert_eq!(
+            board.get_current_player().resources,
+            Resources::new(2000 - BASE_TRADE_RATE, 2100)
+        );
+        assert_eq!(
+            rate(&board, 0, Resource::Food),
+            BASE_TRADE_RATE + TRADE_RATE_STEP
+        );
+        assert_eq!(
+            rate(&board, 0, Resource::Gold),
+            BASE_TRADE_RATE - TRADE_RATE_STEP
+        );
+        assert_eq!(rate(&board, 1, Resource::Food), BASE_TRADE_RATE);
+        action.undo(&mut board);
+        assert_eq!(board, before);
+
+        trade(&mut board, 0, Resource::Food);
+        PlayerAction::PassTurn.apply(&mut board);
+        PlayerAction::PassTurn.apply(&mut board);
+        assert_eq!(rate(&board, 0, Resource::Food), BASE_TRADE_RATE);
+    }
+
+    #[test]
+    fn shared_market_moves_everyone() {
+        let mut board = test_board(MarketModel::Shared);
+        trade(&mut board, 0, Resource::Gold);
+        assert_eq!(
+            rate(&board, 1, Resource::Gold),
+            BASE_TRADE_RATE + TRADE_RATE_STEP
+        );
+        assert_eq!(
+            rate(&board, 1, Resource::Food),
+            BASE_TRADE_RATE - TRADE_RATE_STEP
+        );
+    }
+
+    #[test]
+    fn markets_improve_rates() {
+        let mut board = test_board(MarketModel::PerPlayer);
+        board.grid.get_at_mut(&v!(1, 1)).building = Some(Unit {
+            blueprint_id: board.bp.get_unit_from_name("Market").unwrap(),
+            owner: PlayerId::new(0),
+            ..Default::default()
+        });
+        assert_eq!(
+            rate(&board, 0, Resource::Food),
+            BASE_TRADE_RATE - TRADE_RATE_STEP
+        );
+    }
+}
+
+#[cfg(test)]
+mod events {
+    use crate::{
+        actions::player_action::Pre,
+        prelude::*,
+        test::{place_unit, players_board, test_bp},
+        v,
+    };
+
+    fn test_board() -> Board {
+        let mut board = players_board(&test_bp(), "--- --- ---", 2, Resources::new(1000, 1000));
+        place_unit(&mut board, v!(0, 0), "Town Center", 0);
+        place_unit(&mut board, v!(1, 0), "Militia", 0);
+        place_unit(&mut board, v!(2, 0), "Militia", 1);
+        board
+            .grid
+            .get_at_mut(&v!(2, 0))
+            .unit
+            .as_mut()
+            .unwrap()
+            .health = 10;
+        board
+    }
+
+    fn attack(board: &mut Board) {
+        let action = PlayerAction::generate(&Pre::Target(UnitPos::top(v!(1, 0))), board)
+            .into_iter()
+            .find(|act| {
+                matches!(
+                    act,
+                    PlayerAction::Unit {
+                        action: UnitAction::Attack(_),
+                        ..
+                    }
+                )
+            })
+            .unwrap();
+        action.apply(board);
+    }
+
+    #[test]
+    fn events_are_off_by_default() {
+        let mut board = test_board();
+        attack(&mut board);
+        assert!(!board.events.is_collecting());
+        assert_eq!(board.events.take(), vec![]);
+    }
+
+    #[test]
+    fn attack_emits_kills() {
+        let mut board = test_board();
+        board.events.collect();
+        let defender = board.get_pos_target(&UnitPos::top(v!(2, 0))).unwrap();
+        attack(&mut board);
+        let events = board.events.take();
+        assert!(events.contains(&GameEvent::UnitKilled {
+            target: defender,
+            by: PlayerId::new(0)
+        }));
+        assert_eq!(board.events.take(), vec![]);
+
+        let serialized = ron::to_string(&events).unwrap();
+        assert_eq!(
+            ron::from_str::<Vec<GameEvent>>(&serialized).unwrap(),
+            events
+        );
+    }
+
+    #[test]
+    fn undo_restores_the_board_while_collecting() {
+        let mut board = test_board();
+        let before = board.clone();
+        board.events.collect();
+        let action = PlayerAction::generate(&Pre::Target(UnitPos::top(v!(1, 0))), &mut board)
+            .into_iter()
+            .find(|act| {
+                matches!(
+                    act,
+                    PlayerAction::Unit {
+                        action: UnitAction::Attack(_),
+                        ..
+                    }
+                )
+            })
+            .unwrap();
+        action.apply(&mut board);
+        action.undo(&mut board);
+        assert!(board.events.is_collecting());
+        assert_eq!(board, before);
+        assert!(!board.events.take().is_empty());
+    }
+
+    #[test]
+    fn training_and_research_emit_events() {
+        let mut board = test_board();
+        board.events.collect();
+        let tech_id = board.bp.techs.keys().next().unwrap().clone();
+        board.players[0]
+            .research_queue
+            .push((QueuedResearch::Tech(tech_id.clone()), Resources::default()));
+        let train = PlayerAction::generate(&Pre::Target(UnitPos::bot(v!(0, 0))), &mut board)
+            .into_iter()
+            .find(|act| {
+                matches!(
+                    act,
+                    PlayerAction::Building {
+                        action: BuildingAction::Train(_),
+                        ..
+                    }
+                )
+            })
+            .unwrap();
+        train.apply(&mut board);
+        PlayerAction::PassTurn.apply(&mut board);
+        PlayerAction::PassTurn.apply(&mut board);
+        let events = board.events.take();
+        assert!(matches!(
+            events.first(),
+            Some(GameEvent::UnitTrained { target }) if target.at == v!(0, 0)
+        ));
+        assert!(events.contains(&GameEvent::ResearchFinished {
+            player: PlayerId::new(0),
+            tech_id
+        }));
+    }
+}
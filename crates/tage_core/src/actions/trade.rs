@@ -1,5 +1,14 @@
 use crate::prelude::*;
 
+/// Resources paid for 100 of the other resource before bonuses and market pressure
+pub const BASE_TRADE_RATE: i32 = 250;
+
+/// Change of the rate for each trade, trade bonus and owned Market
+pub const TRADE_RATE_STEP: i32 = 25;
+
+pub const MIN_TRADE_RATE: i32 = 100;
+pub const MAX_TRADE_RATE: i32 = 500;
+
 #[derive(Debug, Clone)]
 pub struct ActTrade {
     pub this: UnitTarget,
@@ -29,7 +38,7 @@ impl Act for ActTrade {
             return false;
         }
 
-        let rate = ActTrade::get_rate(&self.this.unit.owner, board);
+        let rate = ActTrade::get_rate(&self.this.unit.owner, &self.resource, board);
         let player = board.get_player(&building.owner);
         player.get_resource(&self.resource) >= rate
             && !self.this.unit.done
@@ -38,14 +47,17 @@ impl Act for ActTrade {
     }
 
     fn apply(&self, board: &mut Board) {
-        let rate = ActTrade::get_rate(&self.this.unit.owner, board);
+        let rate = ActTrade::get_rate(&self.this.unit.owner, &self.resource, board);
         let player = board.get_player_mut(&self.this.unit.owner);
         *player.get_resource_mut(&self.resource) -= rate;
         *player.get_resource_mut(&self.resource.other()) += 100;
+        player.trade_pressure += Self::pressure(&self.resource);
     }
 
     fn undo(&self, board: &mut Board) {
-        let rate = ActTrade::get_rate(&self.this.unit.owner, board);
+        let player = board.get_player_mut(&self.this.unit.owner);
+        player.trade_pressure -= Self::pressure(&self.resource);
+        let rate = ActTrade::get_rate(&self.this.unit.owner, &self.resource, board);
         let player = board.get_player_mut(&self.this.unit.owner);
         *player.get_resource_mut(&self.resource.other()) -= 100;
         *player.get_resource_mut(&self.resource) += rate;
@@ -53,10 +65,29 @@ impl Act for ActTrade {
 }
 
 impl ActTrade {
-    pub fn get_rate(owner: &PlayerId, board: &Board) -> i32 {
-        let trade = board.get_player_bonus(&owner, None).trade;
-        let rate = (250 - trade * 25).max(100);
-        rate
+    /// Amount of `resource` the player pays for 100 of the other one.
+    /// Selling a resource makes it worth less for the next trades, depending on the rules
+    /// only the trades of the player count or the trades of everyone
+    pub fn get_rate(owner: &PlayerId, resource: &Resource, board: &Board) -> i32 {
+        let market = board.bp.get_unit_from_name("Market");
+        let owns_market = board
+            .get_player_units(owner)
+            .any(|unit| Some(&unit.blueprint_id) == market.as_ref() && !unit.in_construction);
+        let trade = board.get_player_bonus(owner, None).trade + owns_market as i32;
+        let pressure = match board.rules.market {
+            MarketModel::PerPlayer => board.get_player(owner).trade_pressure,
+            MarketModel::Shared => board.players.iter().map(|p| p.trade_pressure).sum(),
+        } * Self::pressure(resource);
+        (BASE_TRADE_RATE + (pressure - trade) * TRADE_RATE_STEP)
+            .clamp(MIN_TRADE_RATE, MAX_TRADE_RATE)
+    }
+
+    /// Food sold pushes the pressure up, gold sold pushes it down
+    fn pressure(resource: &Resource) -> i32 {
+        match resource {
+            Resource::Food => 1,
+            Resource::Gold => -1,
+        }
     }
 }
 
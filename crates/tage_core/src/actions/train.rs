@@ -58,17 +58,26 @@ impl Act for ActTrain {
         let cost = board.bp.get_unit(&self.train_id).resources.cost.clone();
 
         let bonus = Self::get_bonus(board, &owner, &self.train_id, &building_id);
+        let cost = cost.apply_cost(bonus);
         let player = board.get_player_mut(&owner);
-        player.resources -= cost.apply_cost(bonus);
+        player.resources -= cost.clone();
 
-        board.grid.get_at_mut(&self.this.at).unit = Some(Unit {
+        let unit = Unit {
             blueprint_id: self.train_id.clone(),
             health: 50,
             done: true,
-            owner,
+            owner: owner.clone(),
             in_construction: true,
             ..Default::default()
+        };
+        board.events.emit(|| GameEvent::UnitTrained {
+            target: UnitTarget::new(unit.clone(), self.this.at),
+        });
+        board.events.emit(|| GameEvent::Spent {
+            player: owner,
+            resources: cost,
         });
+        board.grid.get_at_mut(&self.this.at).unit = Some(unit);
     }
 
     fn undo(&self, board: &mut Board) {
@@ -0,0 +1,135 @@
+use crate::prelude::*;
+
+use self::travel::ActTravel;
+
+#[derive(Debug, Clone)]
+pub struct ActEmbark {
+    pub this: UnitTarget,
+    pub target: UnitTarget,
+}
+
+impl Act for ActEmbark {
+    type Precondition = UnitTarget;
+
+    fn generate(pre: &Self::Precondition, board: &mut Board) -> Vec<Self> {
+        board
+            .grid
+            .get_adjacent(&pre.at)
+            .iter()
+            .filter_map(|(dir, tile)| {
+                tile.unit.as_ref().map(|target_unit| ActEmbark {
+                    this: pre.clone(),
+                    target: UnitTarget::new(target_unit.clone(), pre.at + **dir),
+                })
+            })
+            .filter(|act| act.is_valid(board))
+            .collect()
+    }
+
+    fn is_valid(&self, board: &Board) -> bool {
+        let this_bp = board.bp.get_unit(&self.this.unit.blueprint_id);
+        let target_bp = board.bp.get_unit(&self.target.unit.blueprint_id);
+        // the collectable would be lost with nobody on the tile to hold it
+        let collectable = board
+            .grid
+            .get_at(&self.this.at)
+            .terrain
+            .collectable
+            .is_some();
+        self.this.unit.owner == self.target.unit.owner
+            && this_bp.domain == MovementDomain::Land
+            && this_bp.capacity == 0
+            && (self.target.unit.cargo.len() as i32) < target_bp.capacity
+            && (self.this.at - self.target.at).length() == 1
+            && !collectable
+    }
+
+    fn apply(&self, board: &mut Board) {
+        if let Some(mut unit) = board.grid.get_at_mut(&self.this.at).unit.take() {
+            unit.done = true;
+            board.modify_unit(&UnitPos::top(self.target.at), |ship| {
+                ship.cargo.push(unit.clone())
+            });
+        }
+    }
+
+    fn undo(&self, board: &mut Board) {
+        board.set_unit_target(self.target.clone());
+        board.set_unit_target(self.this.clone());
+    }
+}
+
+impl From<ActEmbark> for UnitAction {
+    fn from(value: ActEmbark) -> Self {
+        UnitAction::Embark(value.target)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ActUnload {
+    pub this: UnitTarget,
+    pub index: usize,
+    pub destination: IVec2,
+}
+
+impl Act for ActUnload {
+    type Precondition = UnitTarget;
+
+    fn generate(pre: &Self::Precondition, board: &mut Board) -> Vec<Self> {
+        let cargo = &pre.unit.cargo;
+        (0..cargo.len())
+            // units in the same state would unload in the same way
+            .filter(|i| !cargo[..*i].contains(&cargo[*i]))
+            .flat_map(|index| {
+                board
+                    .grid
+                    .get_adjacent(&pre.at)
+                    .into_iter()
+                    .map(move |(dir, _)| ActUnload {
+                        this: pre.clone(),
+                        index,
+                        destination: pre.at + *dir,
+                    })
+            })
+            .filter(|act| act.is_valid(board))
+            .collect()
+    }
+
+    fn is_valid(&self, board: &Board) -> bool {
+        let Some(unit) = self.this.unit.cargo.get(self.index) else {
+            return false;
+        };
+        if (self.destination - self.this.at).length() != 1
+            || !board.grid.contains(&self.destination)
+        {
+            return false;
+        }
+        let unit_bp = board.bp.get_unit(&unit.blueprint_id);
+        let tile = board.grid.get_at(&self.destination);
+        tile.unit.is_none()
+            && !ActTravel::is_blocked(board, unit, tile)
+            && tile.get_movement_cost(board.bp(), &unit_bp.domain) < IMPASSABLE_MOVE_COST
+    }
+
+    fn apply(&self, board: &mut Board) {
+        let mut unit = None;
+        board.modify_unit(&UnitPos::top(self.this.at), |ship| {
+            unit = Some(ship.cargo.remove(self.index))
+        });
+        board.grid.get_at_mut(&self.destination).unit = unit;
+    }
+
+    fn undo(&self, board: &mut Board) {
+        if let Some(unit) = board.grid.get_at_mut(&self.destination).unit.take() {
+            board.modify_unit(&UnitPos::top(self.this.at), |ship| {
+                ship.cargo.insert(self.index, unit.clone())
+            });
+        }
+    }
+}
+
+impl From<ActUnload> for UnitAction {
+    fn from(value: ActUnload) -> Self {
+        UnitAction::Unload(value.index, value.destination)
+    }
+}
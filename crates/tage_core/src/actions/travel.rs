@@ -64,19 +64,43 @@ impl ActTravel {
     // Use this function after generating a move from a fog-stripped board passing the clear board.
     pub fn has_bonked(&self, clear_board: &Board) -> Option<Reachable> {
         for i in 1..self.path.len() {
-            if let Some(look_unit) = &clear_board.grid.get_at(&self.path[i]).get_top_unit() {
-                if look_unit.owner != self.this.unit.owner {
-                    // bonk
-                    return Some(Reachable {
-                        destination: self.path[i - 1],
-                        path: self.path.iter().take(i - 1).cloned().collect(),
-                    });
-                }
+            let look_tile = clear_board.grid.get_at(&self.path[i]);
+            if ActTravel::is_blocked(clear_board, &self.this.unit, look_tile) {
+                // bonk
+                return Some(Reachable {
+                    destination: self.path[i - 1],
+                    path: self.path.iter().take(i - 1).cloned().collect(),
+                });
             }
         }
         None
     }
 
+    /// Units of other players stop the travel, as do walls and gates of hostile players.
+    /// Walls stop every unit.
+    pub fn is_blocked(board: &Board, unit: &Unit, tile: &BoardTile) -> bool {
+        if let Some(building) = &tile.building {
+            match board.bp.get_unit(&building.blueprint_id).barrier {
+                Barrier::Wall => return true,
+                Barrier::Gate if tile.unit.is_none() => {
+                    return board.is_hostile(&building.owner, &unit.owner)
+                }
+                _ => {}
+            }
+        }
+        tile.get_top_unit()
+            .is_some_and(|look_unit| look_unit.owner != unit.owner)
+    }
+
+    /// Ships can enter the tiles of friendly buildings of the water domain
+    fn is_port(board: &Board, unit: &Unit, tile: &BoardTile) -> bool {
+        board.bp.get_unit(&unit.blueprint_id).domain == MovementDomain::Water
+            && tile.building.as_ref().is_some_and(|building| {
+                board.bp.get_unit(&building.blueprint_id).domain == MovementDomain::Water
+                    && !board.is_hostile(&building.owner, &unit.owner)
+            })
+    }
+
     pub fn get_reachable(pre: &UnitTarget, board: &Board) -> Vec<Reachable> {
         let tile = board.grid.get_at(&pre.at);
         let Some(ref unit) = tile.unit else {
@@ -130,20 +154,24 @@ impl ActTravel {
                 if visited.contains(&look) {
                     continue;
                 }
-                let look_points = if let Some(point_override) = bonus
+                let is_port = ActTravel::is_port(board, unit, look_tile);
+                let domain_points = if is_port {
+                    look_tile.get_movement_cost(board.bp(), &MovementDomain::Land)
+                } else {
+                    look_tile.get_movement_cost(board.bp(), &unit_bp.domain)
+                };
+                // overrides change the cost of terrains in the unit domain, they never open a way out of it
+                let point_override = bonus
                     .terrain_movement_cost_override
                     .iter()
                     .find(|(id, _)| id.terrain() == &look_tile.terrain.blueprint_id)
-                    .map(|(_, ov)| ov)
-                {
-                    *point_override
-                } else {
-                    look_tile.get_movement_cost(board.bp())
+                    .map(|(_, ov)| *ov);
+                let look_points = match point_override {
+                    Some(ov) if !is_port && domain_points < IMPASSABLE_MOVE_COST => ov,
+                    _ => domain_points,
                 };
-                if let Some(look_unit) = &look_tile.get_top_unit() {
-                    if look_unit.owner != unit.owner {
-                        continue;
-                    }
+                if ActTravel::is_blocked(board, unit, look_tile) {
+                    continue;
                 }
                 if look_points >= 0 {
                     let diff = points - look_points;
@@ -211,9 +239,10 @@ impl From<ActTravel> for UnitTarget {
 
 #[cfg(test)]
 mod travel_path {
-    use std::sync::Arc;
-
-    use crate::v;
+    use crate::{
+        test::{place_unit, players_board, test_bp},
+        v,
+    };
 
     use super::*;
 
@@ -226,19 +255,14 @@ mod travel_path {
     fn test_board(map: &str) -> Board {
         let blueprints =
             Blueprints::from_assets_location(&("../../".to_string() + BLUEPRINTS_PATH)).unwrap();
-        let board = Board {
-            bp: Arc::new(blueprints.clone()),
-            grid: parse_map(&blueprints, &map).unwrap().grid,
-            players: vec![Player {
+        let board = Board::new(
+            &blueprints,
+            parse_map(&blueprints, &map).unwrap().grid,
+            vec![Player {
                 id: PlayerId::new(0),
                 ..Default::default()
             }],
-            day: 0,
-            current_player_turn: PlayerId::new(0),
-            player_turn_order: vec![PlayerId::new(0)],
-            fog: HashMap::new(),
-            fog_base: FogTile::Visible,
-        };
+        );
         board
     }
 
@@ -331,4 +355,29 @@ mod travel_path {
             assert_eq!(reach.path, e.path, "at {}", reach.destination);
         }
     }
+    #[test]
+    fn override_inside_domain() {
+        let map = format!("--- {}", ["..."; 11].join(" "));
+        let mut blueprints = test_bp();
+        let terrain =
+            |name: &str| IdName::Id(Id::Terrain(blueprints.get_terrain_from_name(name).unwrap()));
+        let fast_ships = BattleBonus {
+            require_this: UnitConstraint::Class(UnitClass::Nav),
+            bonus: Bonus {
+                terrain_movement_cost_override: vec![(terrain("Plains"), 1), (terrain("Water"), 1)],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        blueprints.base_bonuses.push(fast_ships);
+        let mut board = players_board(&blueprints, &map, 1, Resources::default());
+        place_unit(&mut board, v!(1, 0), "Galley", 0);
+        let reachables = ActTravel::get_reachable(
+            &board.get_pos_target(&UnitPos::top(v!(1, 0))).unwrap(),
+            &board,
+        );
+        let destinations: Vec<IVec2> = reachables.iter().map(|r| r.destination).collect();
+        assert!(destinations.contains(&v!(11, 0)), "{:?}", destinations);
+        assert!(!destinations.contains(&v!(0, 0)), "{:?}", destinations);
+    }
 }
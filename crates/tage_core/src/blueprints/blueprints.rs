@@ -1,10 +1,12 @@
 use std::collections::HashMap;
+use std::fmt::Display;
 use std::fs::read_to_string;
 use std::io::{self};
 
 use ron::error::SpannedError;
+use serde::de::DeserializeOwned;
 
-use crate::get_assets_dir;
+use crate::{get_blueprints_dir, mods::ModSet};
 
 use super::*;
 
@@ -29,16 +31,28 @@ pub enum BlueprintLoadError {
         path: String,
         current_dir: String,
     },
-    Parsing(SpannedError),
+
+    /// Files that don't parse, or blueprints that would break the game
+    Invalid(Vec<BlueprintDiagnostic>),
 }
 
-impl From<SpannedError> for BlueprintLoadError {
-    fn from(value: SpannedError) -> Self {
-        Self::Parsing(value)
+impl Display for BlueprintLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ReadingFile {
+                error,
+                path,
+                current_dir,
+            } => write!(f, "can't read {} from {}: {}", path, current_dir, error),
+            Self::Invalid(diagnostics) => {
+                let lines: Vec<String> = diagnostics.iter().map(|d| d.to_string()).collect();
+                write!(f, "{}", lines.join("\n"))
+            }
+        }
     }
 }
 
-fn read_file(path: &str) -> Result<String, BlueprintLoadError> {
+pub(crate) fn read_file(path: &str) -> Result<String, BlueprintLoadError> {
     read_to_string(path).map_err(|io_err| BlueprintLoadError::ReadingFile {
         error: io_err,
         path: path.to_string(),
@@ -49,26 +63,156 @@ fn read_file(path: &str) -> Result<String, BlueprintLoadError> {
     })
 }
 
+/// Parses a blueprint file, the errors point to where they are in the file
+pub(crate) fn parse_file<T: DeserializeOwned>(
+    file: &str,
+    text: &str,
+) -> Result<T, BlueprintLoadError> {
+    ron::from_str(text).map_err(|err: SpannedError| {
+        BlueprintLoadError::Invalid(vec![BlueprintDiagnostic {
+            file: file.to_string(),
+            position: Some(err.position),
+            message: err.code.to_string(),
+        }])
+    })
+}
+
+/// Files in the blueprints directory, in loading order
+pub const BLUEPRINT_FILES: [&str; 7] = [
+    "terrains.ron",
+    "units.ron",
+    "techs.ron",
+    "abilities.ron",
+    "powers.ron",
+    "base_bonuses.ron",
+    "civilizations.ron",
+];
+
+/// Text of the blueprint files, kept to point the diagnostics to the line of the mistake
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlueprintSources {
+    /// File name and text, in the order of `BLUEPRINT_FILES`.
+    /// The files of the mods follow, named `<mod dir>/<file>`, in load order
+    pub files: Vec<(String, String)>,
+}
+
+impl BlueprintSources {
+    pub fn read(base_path: &str) -> Result<Self, BlueprintLoadError> {
+        let files = BLUEPRINT_FILES
+            .iter()
+            .map(|file| {
+                Ok((
+                    file.to_string(),
+                    read_file(&format!("{}/{}", base_path, file))?,
+                ))
+            })
+            .collect::<Result<_, BlueprintLoadError>>()?;
+        Ok(Self { files })
+    }
+
+    pub fn get(&self, file: &str) -> &str {
+        self.files
+            .iter()
+            .find(|(name, _)| name == file)
+            .map(|(_, text)| text.as_str())
+            .unwrap_or_default()
+    }
+
+    /// The base file followed by the files of the mods with the same name
+    pub fn layers<'a>(
+        &'a self,
+        file: &'a str,
+    ) -> impl DoubleEndedIterator<Item = &'a (String, String)> {
+        self.files
+            .iter()
+            .filter(move |(name, _)| name == file || name.ends_with(&format!("/{}", file)))
+    }
+
+    /// Parses the base file, then lays the files of the mods over it.
+    /// The blueprints of a mod replace the ones with the same name, keeping their id,
+    /// or are added with a new id
+    fn parse_layers<T: NamedBlueprint>(&self, file: &str) -> Result<Vec<T>, BlueprintLoadError> {
+        let mut blueprints: Vec<T> = vec![];
+        for (name, text) in self.layers(file) {
+            for mut blueprint in parse_file::<Vec<T>>(name, text)? {
+                if name == file {
+                    blueprints.push(blueprint);
+                    continue;
+                }
+                match blueprints.iter_mut().find(|b| b.name() == blueprint.name()) {
+                    Some(replaced) => {
+                        blueprint.set_id(replaced.id());
+                        *replaced = blueprint;
+                    }
+                    None => {
+                        blueprint.set_id(blueprints.iter().map(|b| b.id() + 1).max().unwrap_or(0));
+                        blueprints.push(blueprint);
+                    }
+                }
+            }
+        }
+        Ok(blueprints)
+    }
+}
+
+/// Blueprints that mods can add, or replace by name
+pub trait NamedBlueprint: DeserializeOwned {
+    fn name(&self) -> &str;
+    fn id(&self) -> u32;
+    fn set_id(&mut self, id: u32);
+}
+
+macro_rules! named_blueprint {
+    ($blueprint: ty, $($name: ident).+, $($id: ident).+) => {
+        impl NamedBlueprint for $blueprint {
+            fn name(&self) -> &str {
+                &self.$($name).+
+            }
+            fn id(&self) -> u32 {
+                self.$($id).+.0
+            }
+            fn set_id(&mut self, id: u32) {
+                self.$($id).+.0 = id;
+            }
+        }
+    };
+}
+
+named_blueprint!(TerrainBlueprint, header.name, header.id);
+named_blueprint!(UnitBlueprint, header.name, header.id);
+named_blueprint!(TechBlueprint, name, id);
+named_blueprint!(AbilityBlueprint, name, id);
+named_blueprint!(PowerBlueprint, name, id);
+named_blueprint!(CivilizationBlueprint, name, id);
+
 impl Blueprints {
     pub fn from_assets() -> Result<Self, BlueprintLoadError> {
-        Blueprints::from_assets_location(&format!("{}/blueprints", get_assets_dir()))
+        Blueprints::from_assets_location(&get_blueprints_dir())
     }
 
     pub fn from_assets_location(base_path: &str) -> Result<Self, BlueprintLoadError> {
-        let path = base_path.to_string() + "/terrains.ron";
-        let terrain: Vec<TerrainBlueprint> = ron::from_str(&read_file(path.as_str())?)?;
-        let path = base_path.to_string() + "/units.ron";
-        let units: Vec<UnitBlueprint> = ron::from_str(&read_file(path.as_str())?)?;
-        let path = base_path.to_string() + "/techs.ron";
-        let techs: Vec<TechBlueprint> = ron::from_str(&read_file(path.as_str())?)?;
-        let path = base_path.to_string() + "/abilities.ron";
-        let abilities: Vec<AbilityBlueprint> = ron::from_str(&read_file(path.as_str())?)?;
-        let path = base_path.to_string() + "/powers.ron";
-        let powers: Vec<PowerBlueprint> = ron::from_str(&read_file(path.as_str())?)?;
-        let path = base_path.to_string() + "/base_bonuses.ron";
-        let base_bonuses: Vec<BattleBonus> = ron::from_str(&read_file(path.as_str())?)?;
-        let path = base_path.to_string() + "/civilizations.ron";
-        let civilizations: Vec<CivilizationBlueprint> = ron::from_str(&read_file(path.as_str())?)?;
+        Self::from_sources(&BlueprintSources::read(base_path)?)
+    }
+
+    /// Loads the blueprints of the assets with the mods laid over them
+    pub fn from_assets_with_mods(mods: &ModSet) -> Result<Self, BlueprintLoadError> {
+        Self::from_sources(&mods.blueprint_sources(&get_blueprints_dir())?)
+    }
+
+    /// Parses and validates the blueprints, then resolves the names into ids
+    pub fn from_sources(sources: &BlueprintSources) -> Result<Self, BlueprintLoadError> {
+        let terrain: Vec<TerrainBlueprint> = sources.parse_layers("terrains.ron")?;
+        let units: Vec<UnitBlueprint> = sources.parse_layers("units.ron")?;
+        let techs: Vec<TechBlueprint> = sources.parse_layers("techs.ron")?;
+        let abilities: Vec<AbilityBlueprint> = sources.parse_layers("abilities.ron")?;
+        let powers: Vec<PowerBlueprint> = sources.parse_layers("powers.ron")?;
+        // battle bonuses have no name, the ones of the mods are added
+        let mut base_bonuses: Vec<BattleBonus> = vec![];
+        for (name, text) in sources.layers("base_bonuses.ron") {
+            base_bonuses.extend(parse_file::<Vec<BattleBonus>>(name, text)?);
+        }
+        let civilizations: Vec<CivilizationBlueprint> =
+            sources.parse_layers("civilizations.ron")?;
         let bp = Self {
             terrain: terrain
                 .into_iter()
@@ -87,6 +231,10 @@ impl Blueprints {
                 .map(|t| (t.id.clone(), t))
                 .collect(),
         };
+        let diagnostics = bp.validate(sources);
+        if !diagnostics.is_empty() {
+            return Err(BlueprintLoadError::Invalid(diagnostics));
+        }
         let res = &ResolveInto::Id;
         Ok(Self {
             units: bp
@@ -129,16 +277,17 @@ impl Blueprints {
         })
     }
 
-    pub fn to_assets(&self) {
+    pub fn to_assets(&self) -> io::Result<()> {
         self.to_assets_location(BLUEPRINTS_PATH)
     }
 
-    // used for testing
-    // i plan to use this later for an ingame editor/randomizer
-    #[allow(dead_code)]
-    pub fn to_assets_location(&self, base_path: &str) {
+    /// Writes the blueprints with names instead of ids,
+    /// the directory can be loaded back with `from_assets_location`
+    pub fn to_assets_location(&self, base_path: &str) -> io::Result<()> {
+        std::fs::create_dir_all(base_path)?;
         let config = ron::ser::PrettyConfig::default()
             .compact_arrays(true)
+            .escape_strings(false)
             .depth_limit(1);
 
         let res = &ResolveInto::Name;
@@ -186,49 +335,51 @@ impl Blueprints {
             bp.terrain.iter().map(|(_, u)| u.clone().into()).collect();
         terrains.sort_by(|a, b| a.header.id.0.cmp(&b.header.id.0));
         let terrain_string: String = ron::ser::to_string_pretty(&terrains, config.clone()).unwrap();
-        std::fs::write(
-            base_path.to_string() + "/terrains_serde.ron",
-            terrain_string,
-        )
-        .unwrap();
+        std::fs::write(base_path.to_string() + "/terrains.ron", terrain_string)?;
 
         let mut units: Vec<UnitBlueprint> = bp.units.iter().map(|(_, t)| t.clone()).collect();
         units.sort_by(|a, b| a.header.id.0.cmp(&b.header.id.0));
         let unit_string: String = ron::ser::to_string_pretty(&units, config.clone()).unwrap();
-        std::fs::write(base_path.to_string() + "/units_serde.ron", unit_string).unwrap();
+        std::fs::write(base_path.to_string() + "/units.ron", unit_string)?;
 
         let mut techs: Vec<TechBlueprint> = bp.techs.iter().map(|(_, t)| t.clone()).collect();
         techs.sort_by(|a, b| a.id.0.cmp(&b.id.0));
         let techs_string: String = ron::ser::to_string_pretty(&techs, config.clone()).unwrap();
-        std::fs::write(base_path.to_string() + "/techs_serde.ron", techs_string).unwrap();
+        std::fs::write(base_path.to_string() + "/techs.ron", techs_string)?;
 
         let config_abilities = ron::ser::PrettyConfig::default()
             .compact_arrays(true)
+            .escape_strings(false)
             .depth_limit(2);
         let mut abilities: Vec<AbilityBlueprint> =
             bp.abilities.iter().map(|(_, t)| t.clone()).collect();
         abilities.sort_by(|a, b| a.id.0.cmp(&b.id.0));
         let abilities_string: String =
             ron::ser::to_string_pretty(&abilities, config_abilities.clone()).unwrap();
-        std::fs::write(
-            base_path.to_string() + "/abilities_serde.ron",
-            abilities_string,
-        )
-        .unwrap();
+        std::fs::write(base_path.to_string() + "/abilities.ron", abilities_string)?;
 
         let base_bonuses_string: String =
-            ron::ser::to_string_pretty(&self.base_bonuses, config_abilities.clone()).unwrap();
+            ron::ser::to_string_pretty(&bp.base_bonuses, config_abilities.clone()).unwrap();
         std::fs::write(
-            base_path.to_string() + "/base_bonuses_serde.ron",
+            base_path.to_string() + "/base_bonuses.ron",
             base_bonuses_string,
-        )
-        .unwrap();
+        )?;
 
         let mut powers: Vec<PowerBlueprint> = bp.powers.iter().map(|(_, t)| t.clone()).collect();
         powers.sort_by(|a, b| a.id.0.cmp(&b.id.0));
         let powers_string: String =
             ron::ser::to_string_pretty(&powers, config_abilities.clone()).unwrap();
-        std::fs::write(base_path.to_string() + "/powers.ron", powers_string).unwrap();
+        std::fs::write(base_path.to_string() + "/powers.ron", powers_string)?;
+
+        let mut civilizations: Vec<CivilizationBlueprint> =
+            bp.civilizations.values().cloned().collect();
+        civilizations.sort_by_key(|civ| civ.id.0);
+        let civilizations_string: String =
+            ron::ser::to_string_pretty(&civilizations, config_abilities.clone()).unwrap();
+        std::fs::write(
+            base_path.to_string() + "/civilizations.ron",
+            civilizations_string,
+        )
     }
 
     pub fn get<'a>(&'a self, gen_id: &Id) -> Blueprint {
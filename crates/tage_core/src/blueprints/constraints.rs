@@ -28,6 +28,11 @@ pub enum BuildConstraint {
 
     NumberOf(IdName, Compare, i32),
     OnTerrain(IdName),
+    IsAdjacentToTerrain(IdName),
+
+    // adjacent to one of your walls, gates or any of the listed buildings
+    WallChain(Vec<IdName>),
+
     OnlyOnFoodResource,
     OnlyOnGoldResource,
     NotOnFoodResource,
@@ -118,7 +123,9 @@ impl Resolve for BuildConstraint {
             Self::IsDiagonalTo(id) => Self::IsDiagonalTo(id.resolve(res, bp)),
             Self::DistanceFrom(id, comp, val) => Self::DistanceFrom(id.resolve(res, bp), comp, val),
             Self::OnTerrain(id) => Self::OnTerrain(id.resolve(res, bp)),
+            Self::IsAdjacentToTerrain(id) => Self::IsAdjacentToTerrain(id.resolve(res, bp)),
             Self::NumberOf(id, comp, val) => Self::NumberOf(id.resolve(res, bp), comp, val),
+            Self::WallChain(ids) => Self::WallChain(ids.resolve(res, bp)),
             _ => self,
         }
     }
@@ -195,12 +202,20 @@ impl BuildConstraint {
                 "Must be on {}",
                 bp.get_terrain(terrain_id.terrain()).header.name
             ),
+            BuildConstraint::IsAdjacentToTerrain(terrain_id) => format!(
+                "Must be adjacent to {}",
+                bp.get_terrain(terrain_id.terrain()).header.name
+            ),
             BuildConstraint::NumberOf(unit_id, comp, val) => format!(
                 "Number of {} must be {} {}",
                 bp.get_unit(unit_id.unit()).header.name,
                 comp.view(),
                 val
             ),
+            BuildConstraint::WallChain(ids) => ids.iter().fold(
+                "Must be adjacent to your walls, gates".to_string(),
+                |s, id| format!("{}, {}", s, bp.get_unit(id.unit()).header.name),
+            ),
             BuildConstraint::OnlyOnFoodResource => format!("Must be on a food resource"),
             BuildConstraint::OnlyOnGoldResource => format!("Must be on a gold resource"),
             BuildConstraint::NotOnFoodResource => format!("Must not be on a food resource"),
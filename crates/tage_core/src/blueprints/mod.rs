@@ -5,10 +5,12 @@ pub mod civilization_bp;
 pub mod constraints;
 pub mod id;
 pub mod power_bp;
+pub mod randomizer;
 pub mod resources;
 pub mod tech_bp;
 pub mod terrain_bp;
 pub mod unit_bp;
+pub mod validate;
 
 pub use ability_bp::*;
 pub use blueprints::*;
@@ -17,10 +19,12 @@ pub use civilization_bp::*;
 pub use constraints::*;
 pub use id::*;
 pub use power_bp::*;
+pub use randomizer::*;
 pub use resources::*;
 pub use tech_bp::*;
 pub use terrain_bp::*;
 pub use unit_bp::*;
+pub use validate::*;
 
 pub fn view_level(level: i32) -> String {
     match level {
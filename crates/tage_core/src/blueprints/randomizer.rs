@@ -0,0 +1,228 @@
+use std::{collections::HashMap, hash::Hash};
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::rng::BoardRng;
+
+use super::*;
+
+/// How far the randomizer moves the blueprints away from the original ones
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct RandomizerSettings {
+    /// The same seed and settings give the same blueprints on every machine
+    pub seed: u64,
+
+    /// Most percent a unit stat moves up or down, the range is kept
+    pub stats: i32,
+
+    /// Most percent the cost of a unit or technology moves up or down
+    pub costs: i32,
+
+    /// Most percent the values of the civilization, technology and battle bonuses move up or down
+    pub bonuses: i32,
+
+    /// Deals the bonuses of the civilizations out again, each civilization keeps how many it has
+    pub shuffle_bonuses: bool,
+
+    /// Swaps the heroes between the civilizations, each civilization keeps a hero per age
+    pub shuffle_heroes: bool,
+}
+
+impl Default for RandomizerSettings {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            stats: 20,
+            costs: 20,
+            bonuses: 30,
+            shuffle_bonuses: true,
+            shuffle_heroes: false,
+        }
+    }
+}
+
+impl Blueprints {
+    /// A copy of the blueprints with the stats, costs and bonuses moved at random
+    /// within the bounds of the settings. Values never change sign and never become zero
+    pub fn randomized(&self, settings: &RandomizerSettings) -> Blueprints {
+        let mut rng = BoardRng::new(settings.seed);
+        let mut bp = self.clone();
+
+        // the blueprints are visited in order of id, the hash maps would give another order every run
+        for id in sorted_ids(&bp.units, |id| id.0) {
+            let unit = bp.units.get_mut(&id).unwrap();
+            let stats = &mut unit.stats;
+            for stat in [
+                &mut stats.movement,
+                &mut stats.attack,
+                &mut stats.defence,
+                &mut stats.sight,
+            ] {
+                *stat = perturb(&mut rng, *stat, settings.stats);
+            }
+            perturb_cost(&mut rng, &mut unit.resources.cost, settings.costs);
+        }
+
+        for id in sorted_ids(&bp.techs, |id| id.0) {
+            let tech = bp.techs.get_mut(&id).unwrap();
+            perturb_cost(&mut rng, &mut tech.cost, settings.costs);
+            let bonuses = tech
+                .unit_bonuses
+                .iter_mut()
+                .map(|b| &mut b.bonus)
+                .chain(tech.battle_bonuses.iter_mut().map(|b| &mut b.bonus))
+                .chain(tech.trained_from_bonus.iter_mut().map(|(_, b)| b));
+            for bonus in bonuses {
+                perturb_bonus(&mut rng, bonus, settings.bonuses);
+            }
+        }
+
+        for battle_bonus in bp.base_bonuses.iter_mut() {
+            perturb_bonus(&mut rng, &mut battle_bonus.bonus, settings.bonuses);
+        }
+
+        let civilizations = sorted_ids(&bp.civilizations, |id| id.0);
+        for id in civilizations.iter() {
+            let civ = bp.civilizations.get_mut(id).unwrap();
+            for unit_bonus in civ.unit_bonuses.iter_mut() {
+                perturb_bonus(&mut rng, &mut unit_bonus.bonus, settings.bonuses);
+            }
+        }
+
+        if settings.shuffle_bonuses {
+            let mut pool: Vec<UnitBonus> = civilizations
+                .iter()
+                .flat_map(|id| bp.civilizations[id].unit_bonuses.clone())
+                .collect();
+            pool.shuffle(&mut rng);
+            let mut discounts: Vec<Resources> = civilizations
+                .iter()
+                .map(|id| bp.civilizations[id].tech_discount.clone())
+                .collect();
+            discounts.shuffle(&mut rng);
+            for (id, discount) in civilizations.iter().zip(discounts) {
+                let civ = bp.civilizations.get_mut(id).unwrap();
+                civ.unit_bonuses = pool.drain(..civ.unit_bonuses.len()).collect();
+                civ.tech_discount = discount;
+            }
+        }
+
+        // every civilization gets the whole line of heroes of another one,
+        // so each one still has a hero per age
+        if settings.shuffle_heroes {
+            let mut heroes: Vec<Vec<IdName>> = civilizations
+                .iter()
+                .map(|id| bp.civilizations[id].heroes.clone())
+                .collect();
+            heroes.shuffle(&mut rng);
+            for (id, heroes) in civilizations.iter().zip(heroes) {
+                bp.civilizations.get_mut(id).unwrap().heroes = heroes;
+            }
+        }
+
+        bp
+    }
+}
+
+fn sorted_ids<K: Clone + Eq + Hash, V>(map: &HashMap<K, V>, key: impl Fn(&K) -> u32) -> Vec<K> {
+    let mut ids: Vec<K> = map.keys().cloned().collect();
+    ids.sort_by_key(key);
+    ids
+}
+
+/// Moves the value by up to `percent` of it, keeping its sign and at least 1 away from zero
+fn perturb(rng: &mut BoardRng, value: i32, percent: i32) -> i32 {
+    if value == 0 || percent <= 0 {
+        return value;
+    }
+    let moved = value + value * rng.gen_range(-percent..=percent) / 100;
+    if value > 0 {
+        moved.max(1)
+    } else {
+        moved.min(-1)
+    }
+}
+
+/// Costs stay multiples of 5
+fn perturb_cost(rng: &mut BoardRng, cost: &mut Resources, percent: i32) {
+    for value in [&mut cost.food, &mut cost.gold] {
+        if *value > 0 {
+            *value = ((perturb(rng, *value, percent) + 2) / 5 * 5).max(5);
+        }
+    }
+}
+
+fn perturb_bonus(rng: &mut BoardRng, bonus: &mut Bonus, percent: i32) {
+    for value in [&mut bonus.incr, &mut bonus.perc] {
+        let stats = &mut value.stats;
+        let resources = &mut value.resources;
+        for number in [
+            &mut stats.movement,
+            &mut stats.attack,
+            &mut stats.defence,
+            &mut stats.range,
+            &mut stats.sight,
+            &mut resources.cost.food,
+            &mut resources.cost.gold,
+            &mut resources.produces.food,
+            &mut resources.produces.gold,
+        ] {
+            *number = perturb(rng, *number, percent);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::{temp_path, test_bp};
+
+    use super::*;
+
+    #[test]
+    fn randomized_blueprints_are_playable() {
+        let bp = test_bp();
+        let settings = RandomizerSettings {
+            seed: 42,
+            shuffle_heroes: true,
+            ..Default::default()
+        };
+        let randomized = bp.randomized(&settings);
+        assert_eq!(randomized, bp.randomized(&settings));
+        assert_ne!(randomized, bp);
+        assert_ne!(
+            randomized,
+            bp.randomized(&RandomizerSettings {
+                seed: 43,
+                ..settings.clone()
+            })
+        );
+
+        for (id, unit) in randomized.units.iter() {
+            let original = bp.get_unit(id);
+            assert_eq!(unit.stats.range, original.stats.range);
+            let bound = original.stats.attack * settings.stats / 100;
+            assert!((unit.stats.attack - original.stats.attack).abs() <= bound);
+            assert_eq!(unit.resources.cost.food % 5, 0);
+        }
+
+        // loading the written set validates it, heroes included
+        let path = temp_path("tage_randomized_blueprints");
+        randomized.to_assets_location(&path).unwrap();
+        assert_eq!(Blueprints::from_assets_location(&path).unwrap(), randomized);
+    }
+
+    #[test]
+    fn no_bounds_keep_the_blueprints() {
+        let bp = test_bp();
+        let settings = RandomizerSettings {
+            seed: 7,
+            stats: 0,
+            costs: 0,
+            bonuses: 0,
+            shuffle_bonuses: false,
+            shuffle_heroes: false,
+        };
+        assert_eq!(bp.randomized(&settings), bp);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::is_default;
+
 #[derive(
     serde::Serialize,
     serde::Deserialize,
@@ -35,4 +37,38 @@ pub struct TerrainStats {
     pub range_bonus: i32,
     pub defence_bonus: i32,
     pub sight_bonus: i32,
+
+    /// Movement cost for units of the water domain, the terrain is impassable to them if None
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub water_move_cost: Option<i32>,
+}
+
+/// Movement cost high enough that no unit can afford it
+pub const IMPASSABLE_MOVE_COST: i32 = 100;
+
+/// Where a unit is allowed to travel
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub enum MovementDomain {
+    #[default]
+    Land,
+    Water,
+}
+
+impl TerrainStats {
+    pub fn get_move_cost(&self, domain: &MovementDomain) -> i32 {
+        match domain {
+            MovementDomain::Land => self.move_cost,
+            MovementDomain::Water => self.water_move_cost.unwrap_or(IMPASSABLE_MOVE_COST),
+        }
+    }
+}
+
+impl MovementDomain {
+    pub fn view(&self) -> String {
+        match self {
+            MovementDomain::Land => "Land",
+            MovementDomain::Water => "Water",
+        }
+        .to_string()
+    }
 }
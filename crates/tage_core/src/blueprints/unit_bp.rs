@@ -60,6 +60,31 @@ pub struct UnitBlueprint {
 
     #[serde(default, skip_serializing_if = "is_default")]
     pub train_cost_bonus: Bonus,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub barrier: Barrier,
+
+    /// Units travel only on terrains of their domain.
+    /// Buildings of the water domain are ports that friendly ships can enter
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub domain: MovementDomain,
+
+    /// Number of land units that can be carried
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub capacity: i32,
+}
+
+/// Buildings that stop units from travelling through their tile
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, PartialEq, Eq, Clone)]
+pub enum Barrier {
+    #[default]
+    None,
+
+    /// No unit can travel through a wall segment
+    Wall,
+
+    /// Only hostile units are stopped by a gate
+    Gate,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
@@ -81,6 +106,7 @@ pub enum UnitClass {
     Cav,
     Sie,
     Ran,
+    Nav,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone, PartialEq, Eq)]
@@ -228,6 +254,17 @@ impl Resolve for UnitBlueprint {
     }
 }
 
+impl Barrier {
+    pub fn view(&self) -> String {
+        match self {
+            Barrier::None => "None",
+            Barrier::Wall => "Blocks every unit",
+            Barrier::Gate => "Blocks hostile units",
+        }
+        .to_string()
+    }
+}
+
 impl UnitClass {
     pub fn view(&self) -> String {
         match self {
@@ -236,6 +273,7 @@ impl UnitClass {
             UnitClass::Cav => "Cavalry",
             UnitClass::Sie => "Siege",
             UnitClass::Ran => "Ranged",
+            UnitClass::Nav => "Naval",
         }
         .to_string()
     }
@@ -0,0 +1,379 @@
+use std::{collections::HashMap, fmt::Display};
+
+use ron::error::Position;
+
+use super::*;
+
+/// Levels of the units, from the Dark Age to the Imperial Age
+const AGES: [i32; 4] = [0, 1, 2, 3];
+
+/// Units placed at the spawn points of every map
+const SPAWN_UNITS: [&str; 2] = ["Villager", "Militia"];
+
+/// A mistake in the blueprints and where it is written
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlueprintDiagnostic {
+    pub file: String,
+
+    /// `None` if the mistake isn't written anywhere, like a missing blueprint
+    pub position: Option<Position>,
+
+    pub message: String,
+}
+
+impl Display for BlueprintDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.position {
+            Some(position) => write!(f, "{}:{}: {}", self.file, position, self.message),
+            None => write!(f, "{}: {}", self.file, self.message),
+        }
+    }
+}
+
+impl Blueprints {
+    /// Finds the mistakes that would otherwise panic during the game:
+    /// names that don't resolve, upgrade chains that loop or stop before the last age,
+    /// civilizations without a hero per age and terrains that maps can't tell apart.
+    /// Works both before and after the names are resolved
+    pub fn validate(&self, sources: &BlueprintSources) -> Vec<BlueprintDiagnostic> {
+        let mut diagnostics = vec![];
+        self.validate_names(sources, &mut diagnostics);
+        self.validate_upgrades(sources, &mut diagnostics);
+        self.validate_heroes(sources, &mut diagnostics);
+        self.validate_glyphs(sources, &mut diagnostics);
+        diagnostics
+    }
+
+    fn validate_names(
+        &self,
+        sources: &BlueprintSources,
+        diagnostics: &mut Vec<BlueprintDiagnostic>,
+    ) {
+        for (file, source) in sources.files.iter() {
+            for (offset, name) in name_references(source) {
+                if self.get_from_name(&name).is_none() {
+                    diagnostics.push(BlueprintDiagnostic {
+                        file: file.clone(),
+                        position: Some(position_at(source, offset)),
+                        message: format!("nothing named \"{}\"", name),
+                    });
+                }
+            }
+        }
+    }
+
+    fn validate_upgrades(
+        &self,
+        sources: &BlueprintSources,
+        diagnostics: &mut Vec<BlueprintDiagnostic>,
+    ) {
+        let mut units: Vec<&UnitBlueprint> = self.units.values().collect();
+        units.sort_by_key(|unit| unit.header.id.0);
+        for unit in units.iter() {
+            let Some(upgrade) = &unit.upgrades_to else {
+                continue;
+            };
+            let Some(next) = self.find_unit(upgrade) else {
+                // names that don't resolve are already reported
+                if self.find(upgrade).is_some() {
+                    diagnostics.push(unit_diagnostic(
+                        sources,
+                        &unit.header.name,
+                        format!(
+                            "\"{}\" upgrades to something that isn't a unit",
+                            unit.header.name
+                        ),
+                    ));
+                }
+                continue;
+            };
+
+            let mut chain = vec![unit.header.id.clone()];
+            let mut current = next;
+            while !chain.contains(&current.header.id) {
+                chain.push(current.header.id.clone());
+                match current
+                    .upgrades_to
+                    .as_ref()
+                    .and_then(|id| self.find_unit(id))
+                {
+                    Some(next) => current = next,
+                    None => break,
+                }
+            }
+            // the loop is reported once, by the unit with the lowest id in it
+            let looping = current.header.id == unit.header.id;
+            if looping && chain.iter().all(|id| id.0 >= unit.header.id.0) {
+                let names: Vec<&str> = chain
+                    .iter()
+                    .chain([&unit.header.id])
+                    .map(|id| self.get_unit(id).header.name.as_str())
+                    .collect();
+                diagnostics.push(unit_diagnostic(
+                    sources,
+                    &unit.header.name,
+                    format!("upgrades loop: {}", names.join(" -> ")),
+                ));
+            }
+        }
+
+        for name in SPAWN_UNITS {
+            if self.get_unit_from_name(name).is_none() {
+                diagnostics.push(BlueprintDiagnostic {
+                    file: "units.ron".to_string(),
+                    position: None,
+                    message: format!(
+                        "no unit named \"{}\", it is placed at the spawn points",
+                        name
+                    ),
+                });
+            }
+        }
+
+        // the starting army is upgraded once per age of the player
+        if let Some(id) = self.get_unit_from_name("Militia") {
+            let mut unit = self.get_unit(&id);
+            for _ in AGES.iter().skip(1) {
+                match unit.upgrades_to.as_ref().and_then(|id| self.find_unit(id)) {
+                    Some(next) => unit = next,
+                    None => {
+                        diagnostics.push(unit_diagnostic(
+                            sources,
+                            &unit.header.name,
+                            format!(
+                                "\"Militia\" upgrades only up to \"{}\", one upgrade per age is needed to place it at the spawn points",
+                                unit.header.name
+                            ),
+                        ));
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    fn validate_heroes(
+        &self,
+        sources: &BlueprintSources,
+        diagnostics: &mut Vec<BlueprintDiagnostic>,
+    ) {
+        let file = "civilizations.ron";
+        let mut civilizations: Vec<&CivilizationBlueprint> = self.civilizations.values().collect();
+        civilizations.sort_by_key(|civ| civ.id.0);
+        for civ in civilizations {
+            let heroes: Vec<&UnitBlueprint> = civ
+                .heroes
+                .iter()
+                .filter_map(|hero| self.find_unit(hero))
+                .collect();
+            // names that don't resolve are already reported
+            if civ
+                .heroes
+                .iter()
+                .any(|hero| self.find(hero).is_some() && self.find_unit(hero).is_none())
+            {
+                diagnostics.push(sources.diagnostic(
+                    file,
+                    &civ.name,
+                    format!("a hero of \"{}\" isn't a unit", civ.name),
+                ));
+            }
+            for age in AGES {
+                if !heroes.iter().any(|hero| hero.header.level == age) {
+                    diagnostics.push(sources.diagnostic(
+                        file,
+                        &civ.name,
+                        format!("\"{}\" has no hero in the {}", civ.name, view_level(age)),
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Maps match the terrain on the third character of the tile
+    fn validate_glyphs(
+        &self,
+        sources: &BlueprintSources,
+        diagnostics: &mut Vec<BlueprintDiagnostic>,
+    ) {
+        let file = "terrains.ron";
+        let mut terrains: Vec<&TerrainBlueprint> = self.terrain.values().collect();
+        terrains.sort_by_key(|terrain| terrain.header.id.0);
+        let mut matched: HashMap<char, &str> = HashMap::new();
+        for terrain in terrains {
+            let name = &terrain.header.name;
+            let message = match terrain.header.glyph.chars().nth(2) {
+                None => format!("the glyph of \"{}\" has less than 3 characters", name),
+                Some(c) => match matched.get(&c) {
+                    Some(other) => format!(
+                        "\"{}\" and \"{}\" both end their glyph with '{}', maps can't tell them apart",
+                        other, name, c
+                    ),
+                    None => {
+                        matched.insert(c, name);
+                        continue;
+                    }
+                },
+            };
+            diagnostics.push(sources.diagnostic(file, name, message));
+        }
+    }
+
+    /// The id of the reference, resolved or not
+    fn find(&self, id_name: &IdName) -> Option<Id> {
+        match id_name {
+            IdName::Id(id) => Some(id.clone()),
+            IdName::Name(name) => self.get_from_name(name),
+        }
+    }
+
+    fn find_unit(&self, id_name: &IdName) -> Option<&UnitBlueprint> {
+        match self.find(id_name)? {
+            Id::Unit(id) => self.units.get(&id),
+            _ => None,
+        }
+    }
+}
+
+impl BlueprintSources {
+    /// Points the message to where the blueprint with the name is declared,
+    /// in the last mod that changes it or else in the base file
+    fn diagnostic(&self, file: &str, name: &str, message: String) -> BlueprintDiagnostic {
+        let declaration = format!("name: \"{}\"", name);
+        let found = self.layers(file).rev().find_map(|(label, source)| {
+            let offset = source.find(&declaration)?;
+            Some((label.clone(), position_at(source, offset)))
+        });
+        match found {
+            Some((label, position)) => BlueprintDiagnostic {
+                file: label,
+                position: Some(position),
+                message,
+            },
+            None => BlueprintDiagnostic {
+                file: file.to_string(),
+                position: None,
+                message,
+            },
+        }
+    }
+}
+
+fn unit_diagnostic(sources: &BlueprintSources, name: &str, message: String) -> BlueprintDiagnostic {
+    sources.diagnostic("units.ron", name, message)
+}
+
+/// Offsets and names of the `Name("...")` references in the text
+fn name_references(source: &str) -> impl Iterator<Item = (usize, String)> + '_ {
+    source.match_indices("Name(").filter_map(|(offset, _)| {
+        let preceding = source[..offset].chars().next_back();
+        if preceding.is_some_and(|c| c.is_alphanumeric() || c == '_') {
+            return None;
+        }
+        let quoted = source[offset + "Name(".len()..].trim_start();
+        let inner = quoted.strip_prefix('"')?;
+        let mut escaped = false;
+        let end = inner.find(|c| {
+            let closing = c == '"' && !escaped;
+            escaped = c == '\\' && !escaped;
+            closing
+        })?;
+        let name = ron::from_str(&quoted[..end + 2]).ok()?;
+        Some((offset, name))
+    })
+}
+
+fn position_at(source: &str, offset: usize) -> Position {
+    let before = &source[..offset];
+    Position {
+        line: before.matches('\n').count() + 1,
+        col: before.chars().rev().take_while(|c| *c != '\n').count() + 1,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sources() -> BlueprintSources {
+        BlueprintSources::read(&("../../".to_string() + BLUEPRINTS_PATH)).unwrap()
+    }
+
+    fn edit(sources: &mut BlueprintSources, file: &str, from: &str, to: &str) {
+        let (_, text) = sources.files.iter_mut().find(|(f, _)| f == file).unwrap();
+        assert!(text.contains(from));
+        *text = text.replacen(from, to, 1);
+    }
+
+    fn messages(sources: &BlueprintSources) -> Vec<String> {
+        match Blueprints::from_sources(sources) {
+            Err(BlueprintLoadError::Invalid(diagnostics)) => {
+                diagnostics.iter().map(|d| d.to_string()).collect()
+            }
+            other => panic!("expected diagnostics, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn shipped_blueprints_are_valid() {
+        let sources = sources();
+        let bp = Blueprints::from_sources(&sources).unwrap();
+        assert_eq!(bp.validate(&sources), vec![]);
+    }
+
+    #[test]
+    fn broken_references_are_diagnosed() {
+        let mut sources = sources();
+        edit(
+            &mut sources,
+            "units.ron",
+            "upgrades_to: Some(Name(\"Men at Arms\"))",
+            "upgrades_to: Some(Name(\"Man at Arms\"))",
+        );
+        edit(
+            &mut sources,
+            "units.ron",
+            "upgrades_to: Some(Name(\"Elite Archers\"))",
+            "upgrades_to: Some(Name(\"Archers\"))",
+        );
+        assert_eq!(
+            messages(&sources),
+            vec![
+                "units.ron:30:23: nothing named \"Man at Arms\"",
+                "units.ron:37:23: upgrades loop: Archers -> Archers",
+                "units.ron:27:23: \"Militia\" upgrades only up to \"Militia\", one upgrade per age is needed to place it at the spawn points",
+            ]
+        );
+    }
+
+    #[test]
+    fn names_with_escaped_quotes_are_read_whole() {
+        let source = r#"a: Name("The \"Great\" Wall"), b: MyName("x"), c: Name( "Keep")"#;
+        assert_eq!(
+            name_references(source).collect::<Vec<_>>(),
+            vec![
+                (3, "The \"Great\" Wall".to_string()),
+                (50, "Keep".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_heroes_and_glyph_collisions_are_diagnosed() {
+        let mut sources = sources();
+        edit(
+            &mut sources,
+            "civilizations.ron",
+            "Name(\"Gengis Khan I\"),",
+            "",
+        );
+        edit(&mut sources, "terrains.ron", "\"###\"", "\"##-\"");
+        assert_eq!(
+            messages(&sources),
+            vec![
+                "civilizations.ron:3:5: \"Mongols\" has no hero in the Dark Age",
+                "terrains.ron:8:23: \"Plains\" and \"Forest\" both end their glyph with '-', maps can't tell them apart",
+            ]
+        );
+    }
+}
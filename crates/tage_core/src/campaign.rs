@@ -0,0 +1,321 @@
+use std::io;
+
+use ron::de::SpannedError;
+
+use crate::{is_default, prelude::*};
+
+/// Scenarios played in order, each unlocked by winning the previous one.
+/// The same player is played in every mission.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Campaign {
+    pub name: String,
+    pub player: PlayerId,
+    pub missions: Vec<Mission>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Mission {
+    pub name: String,
+
+    /// Path of the scenario settings, relative to the assets dir
+    pub scenario: String,
+
+    /// What the player brings from the previous mission
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub carry: Vec<Carry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Carry {
+    /// The hero replaces the one of the mission, keeping its veterancy
+    Hero,
+
+    /// Researched technologies up to the age of the mission
+    Techs,
+
+    /// Resources replace the starting ones of the mission
+    Resources,
+}
+
+#[derive(Debug)]
+pub enum CampaignError {
+    ReadingFile(io::Error),
+    Parsing(SpannedError),
+}
+
+impl From<SpannedError> for CampaignError {
+    fn from(value: SpannedError) -> Self {
+        Self::Parsing(value)
+    }
+}
+
+impl From<io::Error> for CampaignError {
+    fn from(value: io::Error) -> Self {
+        Self::ReadingFile(value)
+    }
+}
+
+impl Campaign {
+    pub fn from_string(campaign_str: &str) -> Result<Self, CampaignError> {
+        Ok(ron::from_str(campaign_str)?)
+    }
+
+    pub fn load(path: &str) -> Result<Self, CampaignError> {
+        let raw = std::fs::read_to_string(path)?;
+        Self::from_string(&raw)
+    }
+
+    /// Settings of the mission with the map path prefixed by `assets_dir`
+    pub fn mission_settings(
+        &self,
+        assets_dir: &str,
+        mission: usize,
+    ) -> Result<MapSettings, CampaignError> {
+        let path = format!("{}/{}", assets_dir, self.missions[mission].scenario);
+        let settings = MapSettings::from_string(&std::fs::read_to_string(path)?)?;
+        let path = format!("{}/{}", assets_dir, settings.path);
+        Ok(settings.with_path(path))
+    }
+}
+
+/// What the campaign player had when it last won a mission
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct CarriedState {
+    /// `None` if the hero fell
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub hero: Option<Unit>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub techs: Vec<TechId>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub resources: Option<Resources>,
+}
+
+impl CarriedState {
+    pub fn from_board(board: &Board, player_id: &PlayerId) -> Self {
+        let player = board.get_player(player_id);
+        Self {
+            hero: board
+                .get_player_units(player_id)
+                .find(|unit| is_hero(&board.bp, &unit.blueprint_id))
+                .cloned(),
+            techs: player.researched_technologies.clone(),
+            resources: Some(player.resources.clone()),
+        }
+    }
+
+    /// Gives the player the parts of the state listed in `carry`
+    pub fn apply(&self, board: &mut Board, player_id: &PlayerId, carry: &[Carry]) {
+        let bp = board.bp.clone();
+        let level = board.get_player(player_id).level;
+
+        if let (true, Some(carried)) = (carry.contains(&Carry::Hero), &self.hero) {
+            // the hero keeps its name, and grows to the age of the mission
+            let hero_id = bp
+                .civilizations
+                .values()
+                .find(|civ| {
+                    civ.heroes
+                        .iter()
+                        .any(|id| id.unit() == &carried.blueprint_id)
+                })
+                .and_then(|civ| {
+                    let carried_level = bp.get_unit(&carried.blueprint_id).header.level;
+                    civ.heroes
+                        .iter()
+                        .map(|id| id.unit())
+                        .find(|id| bp.get_unit(id).header.level == level.max(carried_level))
+                })
+                .unwrap_or(&carried.blueprint_id)
+                .clone();
+            let placed = board
+                .get_player_units_pos(player_id)
+                .find(|(unit, _)| is_hero(&bp, &unit.blueprint_id))
+                .map(|(_, xy)| xy);
+            if let Some(xy) = placed {
+                let unit = board.grid.get_at_mut(&xy).unit.as_mut().unwrap();
+                unit.blueprint_id = hero_id;
+                unit.veterancy = carried.veterancy;
+            }
+        }
+
+        let player = board.get_player_mut(player_id);
+        if carry.contains(&Carry::Techs) {
+            for tech_id in self.techs.iter() {
+                if bp.get_tech(tech_id).level <= level
+                    && !player.researched_technologies.contains(tech_id)
+                {
+                    player.researched_technologies.push(tech_id.clone());
+                }
+            }
+        }
+        if let (true, Some(resources)) = (carry.contains(&Carry::Resources), &self.resources) {
+            player.resources = resources.clone();
+        }
+    }
+}
+
+fn is_hero(bp: &Blueprints, unit_id: &UnitId) -> bool {
+    bp.civilizations
+        .values()
+        .any(|civ| civ.heroes.iter().any(|id| id.unit() == unit_id))
+}
+
+/// Missions won in a campaign and what the player had at the end of each
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct CampaignProgress {
+    /// One entry per won mission, in order
+    pub won: Vec<CarriedState>,
+}
+
+impl CampaignProgress {
+    /// The missions after the first one not won are locked
+    pub fn is_unlocked(&self, mission: usize) -> bool {
+        mission <= self.won.len()
+    }
+
+    pub fn is_won(&self, mission: usize) -> bool {
+        mission < self.won.len()
+    }
+
+    /// What the player brings into the mission, from the end of the previous one
+    pub fn carried(&self, mission: usize) -> Option<&CarriedState> {
+        self.won.get(mission.checked_sub(1)?)
+    }
+
+    /// Records a victory in the mission, winning it again replaces what it carries
+    pub fn win(&mut self, mission: usize, board: &Board, player_id: &PlayerId) {
+        let carried = CarriedState::from_board(board, player_id);
+        match mission.cmp(&self.won.len()) {
+            std::cmp::Ordering::Less => self.won[mission] = carried,
+            std::cmp::Ordering::Equal => self.won.push(carried),
+            std::cmp::Ordering::Greater => {}
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Self, CampaignError> {
+        let raw = std::fs::read_to_string(path)?;
+        Ok(ron::from_str(&raw)?)
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let string = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).unwrap();
+        std::fs::write(path, string)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        test::{temp_path, test_bp},
+        v,
+    };
+
+    use super::*;
+
+    const CAMPAIGN: &str = "../../assets/campaigns/crown_of_england.ron";
+
+    #[test]
+    fn campaign_assets_load() {
+        let bp = test_bp();
+        let campaign = Campaign::load(CAMPAIGN).unwrap();
+        assert_eq!(campaign.missions.len(), 2);
+        for mission in 0..campaign.missions.len() {
+            let settings = campaign.mission_settings("../../assets", mission).unwrap();
+            let board = Board::from_settings(&bp, &settings).unwrap();
+
+            // the armies are declared by the map instead of the default units
+            for player in board.players.iter().filter(|p| !p.id.is_gaia()) {
+                assert!(board.get_player_units(&player.id).count() > 3);
+                assert!(board.has_hero(&player.id));
+            }
+            assert_eq!(board.get_outcome(), None);
+        }
+    }
+
+    #[test]
+    fn won_mission_unlocks_and_carries() {
+        let bp = test_bp();
+        let campaign = Campaign::load(CAMPAIGN).unwrap();
+        let player = campaign.player.clone();
+        let tech = |name| bp.get_tech_from_name(name).unwrap();
+
+        let mut board =
+            Board::from_settings(&bp, &campaign.mission_settings("../../assets", 0).unwrap())
+                .unwrap();
+        let (_, hero_xy) = board
+            .get_player_units_pos(&player)
+            .find(|(unit, _)| is_hero(&bp, &unit.blueprint_id))
+            .unwrap();
+        board
+            .grid
+            .get_at_mut(&hero_xy)
+            .unit
+            .as_mut()
+            .unwrap()
+            .veterancy = 3;
+        let won = board.get_player_mut(&player);
+        won.researched_technologies = vec![tech("Loom"), tech("Crop Rotation")];
+        won.resources = Resources::new(200, 300);
+
+        let mut progress = CampaignProgress::default();
+        assert!(progress.is_unlocked(0));
+        assert!(!progress.is_unlocked(1));
+        assert_eq!(progress.carried(0), None);
+        progress.win(1, &board, &player);
+        assert!(progress.won.is_empty());
+        progress.win(0, &board, &player);
+        assert!(progress.is_won(0));
+        assert!(progress.is_unlocked(1));
+        assert!(!progress.is_unlocked(2));
+
+        let mission = &campaign.missions[1];
+        let mut next =
+            Board::from_settings(&bp, &campaign.mission_settings("../../assets", 1).unwrap())
+                .unwrap();
+        let carried = progress.carried(1).unwrap();
+        carried.apply(&mut next, &player, &mission.carry);
+
+        let hero = next
+            .get_player_units(&player)
+            .find(|unit| is_hero(&bp, &unit.blueprint_id))
+            .unwrap();
+        assert_eq!(
+            bp.get_unit(&hero.blueprint_id).header.name,
+            "Richard the Lionheart III"
+        );
+        assert_eq!(hero.veterancy, 3);
+        let carried = next.get_player(&player);
+        assert_eq!(carried.researched_technologies, vec![tech("Loom")]);
+        assert_eq!(carried.resources, Resources::new(200, 300));
+        assert_eq!(
+            next.get_unit(&UnitPos::top(v!(8, 2))).unwrap().owner,
+            player
+        );
+    }
+
+    #[test]
+    fn progress_roundtrip() {
+        let progress = CampaignProgress {
+            won: vec![
+                CarriedState::default(),
+                CarriedState {
+                    hero: Some(Unit {
+                        veterancy: 2,
+                        ..Default::default()
+                    }),
+                    techs: vec![TechId(4)],
+                    resources: Some(Resources::new(10, 20)),
+                },
+            ],
+        };
+        let path = temp_path("tage_campaign_progress.ron");
+        progress.save(&path).unwrap();
+        assert_eq!(CampaignProgress::load(&path).unwrap(), progress);
+    }
+}
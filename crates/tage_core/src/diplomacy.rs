@@ -0,0 +1,98 @@
+use crate::{is_default, prelude::*};
+
+/// Relation between two players
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    bincode::Encode,
+    bincode::Decode,
+)]
+pub enum Relation {
+    /// Units can attack and convert each other
+    #[default]
+    War,
+
+    /// No attacks, but fog and victory are not shared
+    Ceasefire,
+
+    /// Allies share their fog and win together
+    Alliance,
+}
+
+impl Relation {
+    pub fn view(&self) -> String {
+        match self {
+            Relation::War => "War".to_string(),
+            Relation::Ceasefire => "Ceasefire".to_string(),
+            Relation::Alliance => "Alliance".to_string(),
+        }
+    }
+}
+
+/// Relations changed during the game, they override the teams picked in the lobby.
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    bincode::Encode,
+    bincode::Decode,
+)]
+#[serde(default)]
+pub struct Diplomacy {
+    /// Every change of relation in order, the last one of a pair is the current relation.
+    /// Kept whole so that undoing a change is popping it.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub changes: Vec<(PlayerId, PlayerId, Relation)>,
+
+    /// Proposed relations waiting to be accepted: from, to, relation
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub proposals: Vec<(PlayerId, PlayerId, Relation)>,
+}
+
+impl Diplomacy {
+    /// The relation between two players if it was changed during the game
+    pub fn get(&self, a: &PlayerId, b: &PlayerId) -> Option<Relation> {
+        self.changes
+            .iter()
+            .rev()
+            .find(|(x, y, _)| (x == a && y == b) || (x == b && y == a))
+            .map(|(_, _, relation)| *relation)
+    }
+
+    pub fn set(&mut self, a: &PlayerId, b: &PlayerId, relation: Relation) {
+        self.changes.push((a.clone(), b.clone(), relation));
+    }
+
+    /// Reverts the last call to `set`
+    pub fn unset(&mut self) {
+        self.changes.pop();
+    }
+
+    pub fn get_proposal(&self, from: &PlayerId, to: &PlayerId) -> Option<Relation> {
+        self.proposals
+            .iter()
+            .find(|(x, y, _)| x == from && y == to)
+            .map(|(_, _, relation)| *relation)
+    }
+
+    pub fn propose(&mut self, from: &PlayerId, to: &PlayerId, relation: Relation) {
+        self.proposals.push((from.clone(), to.clone(), relation));
+        // kept sorted so the board is the same after an undo
+        self.proposals.sort_by_key(|(x, y, _)| (x.get(), y.get()));
+    }
+
+    pub fn withdraw(&mut self, from: &PlayerId, to: &PlayerId) {
+        self.proposals.retain(|(x, y, _)| !(x == from && y == to));
+    }
+}
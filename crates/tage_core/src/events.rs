@@ -0,0 +1,208 @@
+use crate::prelude::*;
+
+/// Something that happened while an action was applied.
+/// Units are stored as they were before the event.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    bincode::Encode,
+    bincode::Decode,
+)]
+pub enum GameEvent {
+    /// The unit survived a fight or a conversion with this much health
+    UnitDamaged {
+        target: UnitTarget,
+        health: i32,
+    },
+
+    /// The unit was killed by a unit of the player `by`
+    UnitKilled {
+        target: UnitTarget,
+        by: PlayerId,
+    },
+
+    /// The unit switched to the player `to`
+    UnitConverted {
+        target: UnitTarget,
+        to: PlayerId,
+    },
+
+    /// The unit was just trained, it is ready next turn
+    UnitTrained {
+        target: UnitTarget,
+    },
+
+    BuildingCompleted {
+        target: UnitTarget,
+    },
+
+    /// The building switched to the player `by`
+    BuildingCaptured {
+        target: UnitTarget,
+        by: PlayerId,
+    },
+
+    RelicPickedUp {
+        target: UnitTarget,
+    },
+
+    /// The unit holding the ruins got this reward, or was lost on `None`
+    Ruins {
+        target: UnitTarget,
+        reward: Option<RuinsReward>,
+    },
+
+    ResearchFinished {
+        player: PlayerId,
+        tech_id: TechId,
+    },
+
+    /// The player reached this level
+    AgeUp {
+        player: PlayerId,
+        level: i32,
+    },
+
+    /// Resources produced at the start of the turn of the player
+    Production {
+        player: PlayerId,
+        resources: Resources,
+    },
+
+    /// Resources paid for a unit, a building, a repair or research
+    Spent {
+        player: PlayerId,
+        resources: Resources,
+    },
+
+    /// Resources paid for research given back when it left the queue
+    Refunded {
+        player: PlayerId,
+        resources: Resources,
+    },
+}
+
+impl GameEvent {
+    /// Damage or death of a unit after a fight against a unit of the player `by`
+    pub fn fight(before: &UnitTarget, after: &Option<Unit>, by: &PlayerId) -> Option<GameEvent> {
+        match after {
+            None => Some(GameEvent::UnitKilled {
+                target: before.clone(),
+                by: by.clone(),
+            }),
+            Some(unit) if unit.health < before.unit.health => Some(GameEvent::UnitDamaged {
+                target: before.clone(),
+                health: unit.health,
+            }),
+            Some(_) => None,
+        }
+    }
+
+    pub fn view(&self, bp: &Blueprints) -> String {
+        let name = |target: &UnitTarget| bp.get_unit(&target.unit.blueprint_id).header.name.clone();
+        match self {
+            GameEvent::UnitDamaged { target, health } => {
+                format!("{} at {} damaged to {}", name(target), target.at, health)
+            }
+            GameEvent::UnitKilled { target, by } => {
+                format!("{} at {} killed by {}", name(target), target.at, by.view())
+            }
+            GameEvent::UnitConverted { target, to } => {
+                format!(
+                    "{} at {} converted to {}",
+                    name(target),
+                    target.at,
+                    to.view()
+                )
+            }
+            GameEvent::UnitTrained { target } => {
+                format!("{} trained at {}", name(target), target.at)
+            }
+            GameEvent::BuildingCompleted { target } => {
+                format!("{} completed at {}", name(target), target.at)
+            }
+            GameEvent::BuildingCaptured { target, by } => {
+                format!(
+                    "{} at {} captured by {}",
+                    name(target),
+                    target.at,
+                    by.view()
+                )
+            }
+            GameEvent::RelicPickedUp { target } => {
+                format!("{} at {} picked up a relic", name(target), target.at)
+            }
+            GameEvent::Ruins { target, reward } => {
+                format!(
+                    "{} at {} explored ruins: {:?}",
+                    name(target),
+                    target.at,
+                    reward
+                )
+            }
+            GameEvent::ResearchFinished { player, tech_id } => {
+                format!("{} researched {}", player.view(), bp.get_tech(tech_id).name)
+            }
+            GameEvent::AgeUp { player, level } => {
+                format!("{} reached age {}", player.view(), level + 1)
+            }
+            GameEvent::Production { player, resources } => {
+                format!(
+                    "{} produced {} food and {} gold",
+                    player.view(),
+                    resources.food,
+                    resources.gold
+                )
+            }
+            GameEvent::Spent { player, resources } => {
+                format!(
+                    "{} spent {} food and {} gold",
+                    player.view(),
+                    resources.food,
+                    resources.gold
+                )
+            }
+            GameEvent::Refunded { player, resources } => {
+                format!(
+                    "{} got back {} food and {} gold",
+                    player.view(),
+                    resources.food,
+                    resources.gold
+                )
+            }
+        }
+    }
+}
+
+/// Collects the events of the applied actions.
+/// Off by default so that machines exploring moves don't pay for it.
+/// Undoing an action doesn't retract its events, take them right after applying.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GameEvents(Option<Vec<GameEvent>>);
+
+impl GameEvents {
+    /// Start collecting events
+    pub fn collect(&mut self) {
+        self.0.get_or_insert_with(Vec::new);
+    }
+
+    pub fn is_collecting(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Adds the event if collecting, the event is built only then
+    pub fn emit(&mut self, event: impl FnOnce() -> GameEvent) {
+        if let Some(events) = self.0.as_mut() {
+            events.push(event());
+        }
+    }
+
+    /// Removes the collected events, collection goes on
+    pub fn take(&mut self) -> Vec<GameEvent> {
+        self.0.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+}
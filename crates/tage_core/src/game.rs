@@ -5,7 +5,7 @@ use std::{collections::HashMap, sync::Arc};
 
 use self::travel::ActTravel;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub struct Board {
     pub bp: Arc<Blueprints>,
     pub grid: Grid<BoardTile>,
@@ -15,9 +15,117 @@ pub struct Board {
     pub player_turn_order: Vec<PlayerId>,
     pub fog: HashMap<PlayerId, Grid<FogTile>>,
     pub fog_base: FogTile,
+    pub rng: BoardRng,
+    pub victory: VictoryState,
+    pub diplomacy: Diplomacy,
+    pub rules: Rules,
+    pub scenario: ScenarioState,
+
+    /// Not part of the game state, see `GameEvents`
+    pub events: GameEvents,
 }
 
+/// Compares the game state, the collected events are left out
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        let Board {
+            bp,
+            grid,
+            players,
+            day,
+            current_player_turn,
+            player_turn_order,
+            fog,
+            fog_base,
+            rng,
+            victory,
+            diplomacy,
+            rules,
+            scenario,
+            events: _,
+        } = self;
+        *bp == other.bp
+            && *grid == other.grid
+            && *players == other.players
+            && *day == other.day
+            && *current_player_turn == other.current_player_turn
+            && *player_turn_order == other.player_turn_order
+            && *fog == other.fog
+            && *fog_base == other.fog_base
+            && *rng == other.rng
+            && *victory == other.victory
+            && *diplomacy == other.diplomacy
+            && *rules == other.rules
+            && *scenario == other.scenario
+    }
+}
+
+impl Eq for Board {}
+
 impl Board {
+    /// A board on the first day where the players take turns in order, without fog, victory
+    /// conditions, rules or triggers. Set the other fields with the struct update syntax
+    pub fn new(bp: &Blueprints, grid: Grid<BoardTile>, players: Vec<Player>) -> Board {
+        Board {
+            bp: Arc::new(bp.clone()),
+            grid,
+            day: 0,
+            current_player_turn: players
+                .first()
+                .map_or(PlayerId::new(0), |player| player.id.clone()),
+            player_turn_order: players.iter().map(|player| player.id.clone()).collect(),
+            players,
+            fog: HashMap::new(),
+            fog_base: FogTile::Visible,
+            rng: BoardRng::default(),
+            victory: VictoryState::default(),
+            diplomacy: Diplomacy::default(),
+            rules: Rules::default(),
+            scenario: ScenarioState::default(),
+            events: GameEvents::default(),
+        }
+    }
+
+    /// The board at the start of a game with these settings,
+    /// with the triggers already met, like the introduction of a scenario
+    pub fn from_settings(bp: &Blueprints, settings: &MapSettings) -> Result<Board, ParseMapError> {
+        let (grid, starts) = load_map_with_starts(bp, settings)?;
+        let players = settings
+            .players
+            .iter()
+            .map(|player| {
+                let start = starts.get(&player.id);
+                Player {
+                    level: start.map_or(player.level, |start| start.level),
+                    resources: start
+                        .map_or(Resources::new(1500, 1500), |start| start.resources.clone()),
+                    ..player.clone().to_player(bp)
+                }
+            })
+            .collect();
+        let mut board = Board {
+            fog_base: settings.fog_base.clone(),
+            rng: settings.rng(),
+            victory: VictoryState::new(settings.victory.clone()),
+            rules: settings.rules.clone(),
+            scenario: ScenarioState::new(settings.triggers.clone()),
+            ..Board::new(bp, grid, players)
+        };
+        board.add_gaia();
+        if let Some(player) = board
+            .scenario
+            .spawned_players()
+            .find(|player| !board.players.iter().any(|p| &p.id == *player))
+        {
+            return Err(ParseMapError::TriggerPlayerNotRecognized(player.clone()));
+        }
+        board.crown_heroes();
+        board.init_fog();
+        board.refresh_fog();
+        apply_with_triggers(&mut board, |_| {});
+        Ok(board)
+    }
+
     pub fn bp(&self) -> &Blueprints {
         self.bp.as_ref()
     }
@@ -108,6 +216,33 @@ impl Board {
         self.get_player_mut(&player_id)
     }
 
+    /// The relation between two players, the teams picked in the lobby
+    /// hold until the players change them with diplomacy actions.
+    /// Gaia is always at war with everyone.
+    pub fn get_relation(&self, a: &PlayerId, b: &PlayerId) -> Relation {
+        if a == b {
+            return Relation::Alliance;
+        }
+        if a.is_gaia() || b.is_gaia() {
+            return Relation::War;
+        }
+        self.diplomacy.get(a, b).unwrap_or_else(|| {
+            if self.get_player(a).is_hostile(self.get_player(b)) {
+                Relation::War
+            } else {
+                Relation::Alliance
+            }
+        })
+    }
+
+    pub fn is_hostile(&self, a: &PlayerId, b: &PlayerId) -> bool {
+        self.get_relation(a, b) == Relation::War
+    }
+
+    pub fn is_allied(&self, a: &PlayerId, b: &PlayerId) -> bool {
+        self.get_relation(a, b) == Relation::Alliance
+    }
+
     pub fn get_player_bonus(&self, player_id: &PlayerId, unit_id: Option<&UnitId>) -> Bonus {
         let bp = self.bp();
         let player = self.get_player(player_id);
@@ -366,9 +501,8 @@ impl Board {
     }
 
     pub fn get_hostile_units<'a>(&'a self, id: &'a PlayerId) -> impl Iterator<Item = &'a Unit> {
-        let player = self.get_player(id);
         self.get_units()
-            .filter(|unit| player.is_hostile(self.get_player(&unit.owner)))
+            .filter(|unit| self.is_hostile(id, &unit.owner))
     }
 
     pub fn get_player_units_pos<'a>(
@@ -447,36 +581,193 @@ impl Board {
     }
 
     pub fn get_winners(&self) -> Option<Vec<PlayerId>> {
+        self.get_outcome().map(|outcome| outcome.winners)
+    }
+
+    /// Checks the scenario, conquest and the victory conditions of the board, in this order.
+    /// Gaia never wins and is not needed to be defeated.
+    pub fn get_outcome(&self) -> Option<Outcome> {
+        let conditions = &self.victory.conditions;
+        let contenders = || self.players.iter().filter(|player| !player.id.is_gaia());
+
+        if let Some(winner) = &self.scenario.winner {
+            return Some(Outcome {
+                winners: self.get_alliance(winner),
+                victory: Victory::Scenario,
+            });
+        }
+
         // Conquest: the only alliance left wins
-        let mut alive: Vec<(PlayerId, Option<TeamId>)> = self
+        let regicide = |player: &Player| {
+            conditions.regicide
+                && self.victory.crowned.contains(&player.id)
+                && !self.has_hero(&player.id)
+        };
+        let defeated = |player: &Player| self.scenario.defeated.contains(&player.id);
+        let alive: Vec<PlayerId> = contenders()
+            .filter(|player| !regicide(player) && !defeated(player))
+            .filter(|player| self.get_player_units(&player.id).next().is_some())
+            .map(|player| player.id.clone())
+            .collect();
+        let conquest = alive
+            .iter()
+            .all(|a| alive.iter().all(|b| self.is_allied(a, b)));
+        if conquest {
+            let standing = |player: &Player| self.get_player_units(&player.id).next().is_some();
+            let victory = if contenders().any(|player| defeated(player) && standing(player)) {
+                Victory::Scenario
+            } else if contenders().any(|player| regicide(player) && standing(player)) {
+                Victory::Regicide
+            } else {
+                Victory::Conquest
+            };
+            return Some(Outcome {
+                winners: alive,
+                victory,
+            });
+        }
+
+        if let (Some(days), Some((holder, since))) = (conditions.relic, &self.victory.relics_held) {
+            if self.day >= since + days {
+                return Some(Outcome {
+                    winners: self.get_alliance(holder),
+                    victory: Victory::Relic,
+                });
+            }
+        }
+
+        if let Some(days) = conditions.wonder {
+            if let Some((holder, _)) = self
+                .victory
+                .wonders_held
+                .iter()
+                .find(|(_, since)| self.day >= since + days)
+            {
+                return Some(Outcome {
+                    winners: self.get_alliance(holder),
+                    victory: Victory::Wonder,
+                });
+            }
+        }
+
+        if let Some(day) = conditions.score {
+            if self.day >= day {
+                let scores = player_scores(self.bp(), self);
+                let alliance_score = |player_id: &PlayerId| -> i32 {
+                    self.get_alliance(player_id)
+                        .iter()
+                        .filter_map(|ally| self.player_turn_order.iter().position(|p| p == ally))
+                        .map(|i| scores[i])
+                        .sum()
+                };
+                let best = contenders().map(|p| alliance_score(&p.id)).max();
+                return Some(Outcome {
+                    winners: contenders()
+                        .filter(|p| Some(alliance_score(&p.id)) == best)
+                        .map(|p| p.id.clone())
+                        .collect(),
+                    victory: Victory::Score,
+                });
+            }
+        }
+
+        None
+    }
+
+    /// The player and its allies
+    pub fn get_alliance(&self, player_id: &PlayerId) -> Vec<PlayerId> {
+        self.players
+            .iter()
+            .filter(|other| self.is_allied(player_id, &other.id))
+            .map(|other| other.id.clone())
+            .collect()
+    }
+
+    pub fn has_hero(&self, player_id: &PlayerId) -> bool {
+        self.get_player_units(player_id).any(|unit| {
+            self.bp
+                .civilizations
+                .values()
+                .any(|civ| civ.heroes.iter().any(|id| id.unit() == &unit.blueprint_id))
+        })
+    }
+
+    /// Records the players that have a hero now, call it once the starting units are placed
+    pub fn crown_heroes(&mut self) {
+        self.victory.crowned = self
             .players
             .iter()
-            .filter_map(|player| {
-                self.get_player_units(&player.id)
-                    .next()
-                    .map(|_| (player.id.clone(), player.team.clone()))
-            })
+            .filter(|player| self.has_hero(&player.id))
+            .map(|player| player.id.clone())
             .collect();
-        let mut winners = vec![];
-        while let Some((player, team)) = alive.pop() {
-            if team == None && winners.is_empty() {
-                winners.push((player, team))
-            } else {
-                if winners.iter().all(|(_, team_win)| {
-                    *team_win == team
-                         // team None can win only if it's alone
-                         && team != None
-                }) {
-                    winners.push((player, team));
-                } else {
+    }
+
+    /// A player of the alliance holding every relic of the map in its Churches
+    fn get_relics_holder(&self) -> Option<PlayerId> {
+        let church = self.bp.get_unit_from_name("Church");
+        let mut holders = vec![];
+        for (_, tile) in self.grid.iter() {
+            if tile.terrain.collectable == Some(Collectable::Relic) {
+                return None;
+            }
+            if let Some(unit) = &tile.unit {
+                if unit.holding_collectable == Some(Collectable::Relic) {
                     return None;
                 }
             }
+            if let Some(building) = &tile.building {
+                if building.holding_collectable == Some(Collectable::Relic) {
+                    if Some(&building.blueprint_id) != church.as_ref() {
+                        return None;
+                    }
+                    holders.push(building.owner.clone());
+                }
+            }
         }
-        if !winners.is_empty() && !alive.is_empty() {
-            None
-        } else {
-            Some(winners.into_iter().map(|(player, _)| player).collect())
+        let first = holders.first()?;
+        let alliance = self.get_alliance(first);
+        holders
+            .iter()
+            .all(|holder| alliance.contains(holder))
+            .then(|| first.clone())
+    }
+
+    /// Starts or stops the timers of the relic and wonder victories, called at the end of every turn
+    pub fn update_victory(&mut self) {
+        if self.victory.conditions.relic.is_some() {
+            let holder = self.get_relics_holder();
+            self.victory.relics_held = match (holder, self.victory.relics_held.take()) {
+                (Some(holder), Some((held, since)))
+                    if self.get_alliance(&held).contains(&holder) =>
+                {
+                    Some((held, since))
+                }
+                (Some(holder), _) => Some((holder, self.day)),
+                (None, _) => None,
+            };
+        }
+
+        if self.victory.conditions.wonder.is_some() {
+            let wonder = self.bp.get_unit_from_name("Wonder");
+            let mut owners: Vec<PlayerId> = self
+                .grid
+                .iter()
+                .filter_map(|(_, tile)| tile.building.as_ref())
+                .filter(|b| Some(&b.blueprint_id) == wonder.as_ref() && !b.in_construction)
+                .map(|b| b.owner.clone())
+                .collect();
+            owners.sort_by_key(PlayerId::get);
+            owners.dedup();
+            let held = std::mem::take(&mut self.victory.wonders_held);
+            self.victory.wonders_held = owners
+                .into_iter()
+                .map(|owner| {
+                    held.iter()
+                        .find(|(id, _)| id == &owner)
+                        .cloned()
+                        .unwrap_or((owner, self.day))
+                })
+                .collect();
         }
     }
 
@@ -503,6 +794,17 @@ impl Board {
         ron::ser::to_string_pretty(&BoardView::from(self.bp(), self), config.clone()).unwrap()
     }
 
+    /// Adds Gaia as the last player if the map placed any unit for it
+    pub fn add_gaia(&mut self) {
+        if self.players.iter().any(|player| player.id.is_gaia())
+            || self.get_player_units(&PlayerId::GAIA).next().is_none()
+        {
+            return;
+        }
+        self.players.push(Player::gaia());
+        self.player_turn_order.push(PlayerId::GAIA);
+    }
+
     pub fn init_fog(&mut self) {
         for player in self.players.iter() {
             self.fog.insert(
@@ -563,18 +865,18 @@ impl Board {
     }
 
     pub fn strip_fog(&self, player_id: &PlayerId) -> Board {
-        let player = self.get_player(player_id);
-
         //todo: config to not use allied fog.
         let fog: HashMap<PlayerId, Grid<FogTile>> = self
             .fog
             .iter()
-            .filter(|(id, _)| !player.is_hostile(self.get_player(id)))
+            .filter(|(id, _)| self.is_allied(player_id, id))
             .map(|(id, grid)| (id.clone(), grid.clone()))
             .collect();
 
+        // the rng state would leak the outcome of the next rolls
         let mut stripped = Board {
             fog: fog.clone(),
+            rng: BoardRng::default(),
             ..self.clone()
         };
 
@@ -621,9 +923,44 @@ impl Board {
         }
     }
 
-    pub fn can_undo(&self) -> bool {
+    /// Without fog nothing can be revealed by undoing, so any action or turn can be taken back.
+    /// Under fog only the actions `apply_fogged` marks as undoable can
+    pub fn fog_allows_undo(&self) -> bool {
         self.fog_base == FogTile::Visible
     }
+
+    /// Tiles turned from Hidden or Explored to Visible for a player, `before` is its fog grid
+    /// before the change
+    pub fn revealed_tiles(&self, player_id: &PlayerId, before: &Grid<FogTile>) -> Vec<IVec2> {
+        let Some(after) = self.fog.get(player_id) else {
+            return vec![];
+        };
+        iter_area(self.grid.size)
+            .filter(|xy| before.get_at(xy) != &FogTile::Visible)
+            .filter(|xy| after.get_at(xy) == &FogTile::Visible)
+            .collect()
+    }
+
+    /// Applies an action of the current player through its fog and refreshes the fog.
+    /// Returns the applied action and if it can be undone without leaking hidden information,
+    /// which is not the case if `fog_bonk` truncated the move or new tiles were revealed.
+    pub fn apply_fogged(&mut self, action: PlayerAction) -> (PlayerAction, bool) {
+        let bonked = self.fog_bonk(action.clone());
+        let player_id = self.current_player_turn.clone();
+        let before = self.fog.get(&player_id).cloned();
+        let fired = self.scenario.fired.len();
+
+        bonked.apply(self);
+        self.refresh_fog();
+
+        // an accepted alliance shares the fog of the ally
+        let allied = matches!(bonked, PlayerAction::Diplomacy(DiplomacyAction::Accept(_)));
+        let revealed = before
+            .is_some_and(|before| allied || !self.revealed_tiles(&player_id, &before).is_empty());
+        let triggered = self.scenario.fired.len() > fired;
+        let undoable = bonked == action && !revealed && !triggered;
+        (bonked, undoable)
+    }
 }
 
 /// A tile in the map, identified by an IVec2 into the `board.grid`.
@@ -706,11 +1043,13 @@ impl BoardTile {
         }
     }
 
-    pub fn get_movement_cost(&self, bp: &Blueprints) -> i32 {
-        if self.terrain.has_road {
+    pub fn get_movement_cost(&self, bp: &Blueprints, domain: &MovementDomain) -> i32 {
+        if self.terrain.has_road && *domain == MovementDomain::Land {
             1
         } else {
-            bp.get_terrain(&self.terrain.blueprint_id).stats.move_cost
+            bp.get_terrain(&self.terrain.blueprint_id)
+                .stats
+                .get_move_cost(domain)
         }
     }
 }
@@ -846,6 +1185,21 @@ pub struct BoardView {
 
     #[serde(default, skip_serializing_if = "is_default")]
     fog_base: FogTile,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    rng: BoardRng,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    victory: VictoryState,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    diplomacy: Diplomacy,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    rules: Rules,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    scenario: ScenarioState,
 }
 
 impl BoardView {
@@ -878,6 +1232,11 @@ impl BoardView {
                 .map(|(player_id, grid)| (player_id.clone(), write_fog_grid(grid)))
                 .collect(),
             fog_base: value.fog_base.clone(),
+            rng: value.rng.clone(),
+            victory: value.victory.clone(),
+            diplomacy: value.diplomacy.clone(),
+            rules: value.rules.clone(),
+            scenario: value.scenario.clone(),
         }
     }
 
@@ -895,6 +1254,12 @@ impl BoardView {
                 .map(|(player_id, s)| (player_id, parse_fog_grid(&s).unwrap()))
                 .collect(),
             fog_base: self.fog_base,
+            rng: self.rng,
+            victory: self.victory,
+            diplomacy: self.diplomacy,
+            rules: self.rules,
+            scenario: self.scenario,
+            events: GameEvents::default(),
         };
         for (unit_pos, unit) in self.units {
             let unit_bp = bp.get_unit(&unit.blueprint_id);
@@ -952,6 +1317,24 @@ pub struct MapSettings {
     pub players: Vec<MapPlayerSettings>,
     pub place_hero: bool,
     pub fog_base: FogTile,
+
+    /// Seed of the board rng, a random one is picked if not specified
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub seed: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub victory: VictoryConditions,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub rules: Rules,
+
+    /// Scripted events, a map with triggers is a scenario
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub triggers: Vec<Trigger>,
+
+    /// The map is generated instead of loaded from `path`
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub generator: Option<MapGenSettings>,
 }
 
 impl MapSettings {
@@ -962,6 +1345,10 @@ impl MapSettings {
     pub fn from_string(config_str: &str) -> Result<Self, SpannedError> {
         ron::from_str(config_str)
     }
+
+    pub fn rng(&self) -> BoardRng {
+        self.seed.map_or_else(BoardRng::from_entropy, BoardRng::new)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -969,21 +1356,132 @@ pub enum ParseMapError {
     EmptyString,
     FileReadFailure,
     TerrainNotRecognized(String, IVec2),
+    GaiaUnitNotRecognized(String),
+    SpawnUnitNotRecognized(String),
+    StartNotRecognized(String),
+
+    /// Two units placed by the map in the same slot of this tile
+    UnitsOverlap(IVec2),
+    MapFileNotRecognized(String),
+    UnsupportedVersion(u32),
+    RulesNotAllowed,
+
+    /// A trigger spawns units for a player that is not in the game
+    TriggerPlayerNotRecognized(PlayerId),
 }
 
 pub struct ParseMapResult {
     pub grid: Grid<BoardTile>,
     pub spawn_points: Vec<(u32, IVec2)>,
+    pub gaia_units: Vec<(UnitId, IVec2)>,
+
+    /// Units of the player of a spawn point, they replace its default units
+    pub spawn_units: Vec<(u32, UnitId, IVec2)>,
+    pub starts: Vec<(u32, PlayerStart)>,
+}
+
+/// Resources and level of the player of a spawn point at the start of the game
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlayerStart {
+    pub resources: Resources,
+    pub level: i32,
 }
 
 const GRID_SEPARATOR: &'static str = " ";
 
-pub fn parse_map(bp: &'_ Blueprints, map_string: &str) -> Result<ParseMapResult, ParseMapError> {
-    let lines = map_string
+/// Spawn points are written as a single hex digit, numbered from 0
+pub const MAX_SPAWN_POINTS: u32 = 16;
+
+/// Lines after the grid that place a unit owned by Gaia: `gaia <x> <y> <unit name>`
+const GAIA_PREFIX: &str = "gaia ";
+
+/// Lines after the grid that place a unit for the player of a spawn point:
+/// `spawn <spawn point> <x> <y> <unit name>`. Only units and buildings of a single tile
+const SPAWN_PREFIX: &str = "spawn ";
+
+/// Lines after the grid that set the start of the player of a spawn point:
+/// `start <spawn point> <food> <gold> <level>`
+const START_PREFIX: &str = "start ";
+
+fn parse_gaia_unit(bp: &Blueprints, line: &str) -> Option<(UnitId, IVec2)> {
+    let mut parts = line
+        .strip_prefix(GAIA_PREFIX)?
+        .trim()
+        .splitn(3, GRID_SEPARATOR);
+    let x = parts.next()?.parse().ok()?;
+    let y = parts.next()?.parse().ok()?;
+    let unit_id = bp.get_unit_from_name(parts.next()?.trim())?;
+    Some((unit_id, IVec2::new(x, y)))
+}
+
+fn parse_spawn_unit(bp: &Blueprints, line: &str) -> Option<(u32, UnitId, IVec2)> {
+    let mut parts = line
+        .strip_prefix(SPAWN_PREFIX)?
+        .trim()
+        .splitn(4, GRID_SEPARATOR);
+    let num = parts.next()?.parse().ok()?;
+    let x = parts.next()?.parse().ok()?;
+    let y = parts.next()?.parse().ok()?;
+    let unit_id = bp.get_unit_from_name(parts.next()?.trim())?;
+    (bp.get_unit(&unit_id).unit_size.size == 1).then_some((num, unit_id, IVec2::new(x, y)))
+}
+
+fn parse_start(line: &str) -> Option<(u32, PlayerStart)> {
+    let parts: Vec<i32> = line
+        .strip_prefix(START_PREFIX)?
+        .split_whitespace()
+        .map(|part| part.parse().ok())
+        .collect::<Option<_>>()?;
+    match parts[..] {
+        [num, food, gold, level]
+            if num >= 0 && food >= 0 && gold >= 0 && (0..=3).contains(&level) =>
+        {
+            Some((
+                num as u32,
+                PlayerStart {
+                    resources: Resources::new(food, gold),
+                    level,
+                },
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Places a unit declared by the map, refusing to replace another one
+fn place_map_unit(
+    grid: &mut Grid<BoardTile>,
+    pos: &IVec2,
+    unit: Unit,
+    unit_bp: &UnitBlueprint,
+) -> Result<(), ParseMapError> {
+    let tile = grid.get_at_mut(pos);
+    let slot = match unit_bp.header.class {
+        UnitClass::Bld => &tile.building,
+        _ => &tile.unit,
+    };
+    if slot.is_some() {
+        return Err(ParseMapError::UnitsOverlap(*pos));
+    }
+    tile.set_unit(Some(unit), unit_bp);
+    Ok(())
+}
+
+/// Splits the lines after the grid from the rows of the grid
+pub(crate) fn split_map_lines(map_string: &str) -> (Vec<&str>, Vec<&str>) {
+    map_string
         .trim()
         .lines()
         .filter(|l| !l.is_empty())
-        .collect::<Vec<&str>>();
+        .partition(|l| {
+            [GAIA_PREFIX, SPAWN_PREFIX, START_PREFIX]
+                .iter()
+                .any(|prefix| l.starts_with(prefix))
+        })
+}
+
+pub fn parse_map(bp: &'_ Blueprints, map_string: &str) -> Result<ParseMapResult, ParseMapError> {
+    let (extra_lines, lines) = split_map_lines(map_string);
     if lines.is_empty() {
         return Err(ParseMapError::EmptyString);
     }
@@ -1036,7 +1534,51 @@ pub fn parse_map(bp: &'_ Blueprints, map_string: &str) -> Result<ParseMapResult,
             grid.set_at(&pos, board_tile);
         }
     }
-    Ok(ParseMapResult { grid, spawn_points })
+
+    let mut gaia_units = vec![];
+    let mut spawn_units = vec![];
+    let mut starts = vec![];
+    for line in extra_lines {
+        if line.starts_with(GAIA_PREFIX) {
+            match parse_gaia_unit(bp, line) {
+                Some((unit_id, pos)) if grid.contains(&pos) => gaia_units.push((unit_id, pos)),
+                _ => return Err(ParseMapError::GaiaUnitNotRecognized(line.to_string())),
+            }
+        } else if line.starts_with(SPAWN_PREFIX) {
+            match parse_spawn_unit(bp, line) {
+                Some((num, unit_id, pos)) if grid.contains(&pos) => {
+                    spawn_units.push((num, unit_id, pos))
+                }
+                _ => return Err(ParseMapError::SpawnUnitNotRecognized(line.to_string())),
+            }
+        } else {
+            match parse_start(line) {
+                Some(start) => starts.push(start),
+                None => return Err(ParseMapError::StartNotRecognized(line.to_string())),
+            }
+        }
+    }
+
+    let mut slots = vec![];
+    let declared = gaia_units
+        .iter()
+        .map(|(unit_id, pos)| (unit_id, pos))
+        .chain(spawn_units.iter().map(|(_, unit_id, pos)| (unit_id, pos)));
+    for (unit_id, pos) in declared {
+        let slot = (*pos, bp.get_unit(unit_id).header.class == UnitClass::Bld);
+        if slots.contains(&slot) {
+            return Err(ParseMapError::UnitsOverlap(*pos));
+        }
+        slots.push(slot);
+    }
+
+    Ok(ParseMapResult {
+        grid,
+        spawn_points,
+        gaia_units,
+        spawn_units,
+        starts,
+    })
 }
 
 pub fn write_map(bp: &'_ Blueprints, grid: &Grid<BoardTile>) -> String {
@@ -1071,7 +1613,7 @@ pub fn write_map(bp: &'_ Blueprints, grid: &Grid<BoardTile>) -> String {
             );
         }
         if let Some(spawn_point) = &tile.spawn_point {
-            tile_str.replace_range(1..2, &spawn_point.get().to_string())
+            tile_str.replace_range(1..2, &format!("{:x}", spawn_point.get()))
         }
 
         line += &tile_str;
@@ -1086,57 +1628,146 @@ pub fn write_map(bp: &'_ Blueprints, grid: &Grid<BoardTile>) -> String {
     s
 }
 
+/// Writes the grid and the units and starts declared after it
+pub fn write_parsed_map(bp: &'_ Blueprints, map: &ParseMapResult) -> String {
+    let mut s = write_map(bp, &map.grid);
+    for (unit_id, pos) in map.gaia_units.iter() {
+        let name = &bp.get_unit(unit_id).header.name;
+        s += &format!("{}{} {} {}\n", GAIA_PREFIX, pos.x, pos.y, name);
+    }
+    for (num, start) in map.starts.iter() {
+        let PlayerStart { resources, level } = start;
+        s += &format!(
+            "{}{} {} {} {}\n",
+            START_PREFIX, num, resources.food, resources.gold, level
+        );
+    }
+    for (num, unit_id, pos) in map.spawn_units.iter() {
+        let name = &bp.get_unit(unit_id).header.name;
+        s += &format!("{}{} {} {} {}\n", SPAWN_PREFIX, num, pos.x, pos.y, name);
+    }
+    s
+}
+
 pub fn load_map(
     bp: &'_ Blueprints,
     settings: &MapSettings,
 ) -> Result<Grid<BoardTile>, ParseMapError> {
-    let map_string =
-        std::fs::read_to_string(&settings.path).map_err(|_| ParseMapError::FileReadFailure)?;
+    load_map_with_starts(bp, settings).map(|(grid, _)| grid)
+}
+
+/// Loads or generates the map and places the starting units of the players, each player gets the
+/// spawn point with the lowest number left. A spawn point without units declared in the map
+/// gets a Villager, a Militia and a hero or another Militia.
+/// Also returns the starts the map declares for the spawn points of the players
+pub fn load_map_with_starts(
+    bp: &'_ Blueprints,
+    settings: &MapSettings,
+) -> Result<(Grid<BoardTile>, HashMap<PlayerId, PlayerStart>), ParseMapError> {
+    let map = match &settings.generator {
+        Some(generator) => {
+            MapFile::from_grid("Random", &write_map(bp, &generate_map(bp, generator)))
+        }
+        None => MapFile::load(&settings.path)?,
+    };
+    load_map_file_with_starts(bp, &map, settings)
+}
+
+/// Same as `load_map_with_starts` for a map file that is already loaded,
+/// the path and the generator of the settings are ignored
+pub fn load_map_file_with_starts(
+    bp: &'_ Blueprints,
+    map: &MapFile,
+    settings: &MapSettings,
+) -> Result<(Grid<BoardTile>, HashMap<PlayerId, PlayerStart>), ParseMapError> {
+    if !map.allows(&settings.rules) {
+        return Err(ParseMapError::RulesNotAllowed);
+    }
 
     let ParseMapResult {
         mut grid,
         mut spawn_points,
-    } = parse_map(bp, map_string.as_str())?;
+        gaia_units,
+        spawn_units,
+        starts,
+    } = parse_map(bp, &map.grid)?;
+
+    for (unit_id, pos) in gaia_units {
+        let unit = Unit {
+            blueprint_id: unit_id.clone(),
+            owner: PlayerId::GAIA,
+            ..Default::default()
+        };
+        place_map_unit(&mut grid, &pos, unit, bp.get_unit(&unit_id))?;
+    }
 
+    let mut player_starts = HashMap::new();
     spawn_points.sort_by(|a, b| a.0.cmp(&b.0).reverse());
     for player in settings.players.iter() {
-        if let Some((_, spawn_point)) = spawn_points.pop() {
-            grid.get_at_mut(&spawn_point).unit = Some(Unit {
-                blueprint_id: bp.get_unit_from_name("Villager").unwrap(),
-                owner: player.id.clone(),
-                ..Default::default()
-            });
-            let mut unit_bp = bp.get_unit(&bp.get_unit_from_name("Militia").unwrap());
-            for _ in 0..player.level {
-                unit_bp = bp.get_unit(unit_bp.upgrades_to.clone().unwrap().unit());
+        let Some((num, spawn_point)) = spawn_points.pop() else {
+            continue;
+        };
+        let start = starts.iter().find(|(n, _)| *n == num).map(|(_, s)| s);
+        if let Some(start) = start {
+            player_starts.insert(player.id.clone(), start.clone());
+        }
+        let level = start.map_or(player.level, |start| start.level);
+
+        let mut declared = spawn_units.iter().filter(|(n, _, _)| *n == num).peekable();
+        if declared.peek().is_some() {
+            for (_, unit_id, pos) in declared {
+                let unit = Unit {
+                    blueprint_id: unit_id.clone(),
+                    owner: player.id.clone(),
+                    ..Default::default()
+                };
+                place_map_unit(&mut grid, pos, unit, bp.get_unit(unit_id))?;
             }
-            grid.get_at_mut(&(spawn_point - IVec2::X)).unit = Some(Unit {
-                blueprint_id: unit_bp.header.id.clone(),
-                owner: player.id.clone(),
-                ..Default::default()
-            });
-            let other_unit = if settings.place_hero {
-                let civilization = bp.get_civilization(&player.civ(bp));
-                civilization
-                    .heroes
-                    .iter()
-                    .find_map(|id| {
-                        (bp.get_unit(id.unit()).header.level == player.level).then(|| id.unit())
-                    })
-                    .expect("every civilization must have at least one hero per age")
-                    .clone()
-            } else {
-                unit_bp.header.id.clone()
-            };
-            grid.get_at_mut(&(spawn_point + IVec2::Y)).unit = Some(Unit {
-                blueprint_id: other_unit,
-                owner: player.id.clone(),
-                ..Default::default()
-            });
+            continue;
         }
+
+        let villager_id = bp.get_unit_from_name("Villager").unwrap();
+        let villager = Unit {
+            blueprint_id: villager_id.clone(),
+            owner: player.id.clone(),
+            ..Default::default()
+        };
+        place_map_unit(&mut grid, &spawn_point, villager, bp.get_unit(&villager_id))?;
+        let mut unit_bp = bp.get_unit(&bp.get_unit_from_name("Militia").unwrap());
+        for _ in 0..level {
+            unit_bp = bp.get_unit(unit_bp.upgrades_to.clone().unwrap().unit());
+        }
+        let unit = Unit {
+            blueprint_id: unit_bp.header.id.clone(),
+            owner: player.id.clone(),
+            ..Default::default()
+        };
+        place_map_unit(&mut grid, &(spawn_point - IVec2::X), unit, unit_bp)?;
+        let other_unit = if settings.place_hero {
+            let civilization = bp.get_civilization(&player.civ(bp));
+            civilization
+                .heroes
+                .iter()
+                .find_map(|id| (bp.get_unit(id.unit()).header.level == level).then(|| id.unit()))
+                .expect("every civilization must have at least one hero per age")
+                .clone()
+        } else {
+            unit_bp.header.id.clone()
+        };
+        let unit = Unit {
+            blueprint_id: other_unit.clone(),
+            owner: player.id.clone(),
+            ..Default::default()
+        };
+        place_map_unit(
+            &mut grid,
+            &(spawn_point + IVec2::Y),
+            unit,
+            bp.get_unit(&other_unit),
+        )?;
     }
 
-    Ok(grid)
+    Ok((grid, player_starts))
 }
 
 pub fn write_fog_grid(grid: &Grid<FogTile>) -> String {
@@ -9,12 +9,25 @@ pub mod prelude;
 
 pub mod actions;
 pub mod blueprints;
+pub mod campaign;
+pub mod diplomacy;
+pub mod events;
 pub mod game;
 pub mod grid;
 pub mod machine;
+pub mod map_file;
+pub mod map_gen;
+pub mod map_report;
+pub mod mods;
 pub mod player;
+pub mod replay;
+pub mod rng;
+pub mod rules;
+pub mod scenario;
+pub mod stats;
 pub mod unit;
 pub mod vec2;
+pub mod victory;
 
 #[cfg(test)]
 mod test;
@@ -34,3 +47,9 @@ pub fn get_assets_dir() -> String {
         format!("assets")
     }
 }
+
+/// Blueprints path can be specified at runtime with an env variable, to play with a randomized set.
+/// Defaults to the blueprints in the assets
+pub fn get_blueprints_dir() -> String {
+    env::var("TAGE_BLUEPRINTS").unwrap_or_else(|_| format!("{}/blueprints", get_assets_dir()))
+}
@@ -1,6 +1,6 @@
 use std::{collections::HashMap, fmt::Debug};
 
-use boulder::{player_action::Pre, research::ActResearch};
+use boulder::{player_action::Pre, research::ActResearch, trade::ActTrade};
 use rand::{thread_rng, Rng};
 use tracing::{trace, warn};
 
@@ -115,9 +115,7 @@ impl Machine for Boulder {
                             let cost = action_cost(&act, board);
                             let resources = board.get_current_player().resources.clone();
                             let after = resources - cost;
-                            let cost_penalty = if after.food < saving_goal.food {
-                                -100
-                            } else if after.gold < saving_goal.gold {
+                            let cost_penalty = if is_short_of(board, &after, &saving_goal) {
                                 -100
                             } else {
                                 0
@@ -138,7 +136,7 @@ impl Machine for Boulder {
 
             visited.retain(|_, amt| *amt > 0);
 
-            if board.get_current_player().research_queued.is_none()
+            if board.get_current_player().research_queue.is_empty()
                 && !board.get_current_player().can_age_up(bp)
             {
                 let research_actions = PlayerAction::generate(&Pre::Research, board);
@@ -194,6 +192,8 @@ fn weight_action(player_action: &PlayerAction) -> Weighted<PlayerAction> {
         },
         PlayerAction::Building { .. } => 10,
         PlayerAction::Research(_) => 300,
+        PlayerAction::ResearchQueue(_) => 0,
+        PlayerAction::Diplomacy(_) => 0,
         PlayerAction::PassTurn => -1000,
     };
     Weighted::new(w, player_action.clone())
@@ -262,6 +262,21 @@ fn relative_distance(grid: &Grid<i32>, from: &IVec2, to: &IVec2) -> i32 {
     grid.get_at(to) - grid.get_at(from)
 }
 
+/// True if the resources miss the goal even after trading away
+/// the surplus of the other resource at the current trade rates
+fn is_short_of(board: &Board, resources: &Resources, goal: &Resources) -> bool {
+    Resource::iter().any(|resource| {
+        let missing = goal.get_res(resource) - resources.get_res(resource);
+        if missing <= 0 {
+            return false;
+        }
+        let other = resource.other();
+        let surplus = (resources.get_res(&other) - goal.get_res(&other)).max(0);
+        let rate = ActTrade::get_rate(&board.current_player_turn, &other, board);
+        surplus / rate * 100 < missing
+    })
+}
+
 fn action_cost(player_action: &PlayerAction, board: &mut Board) -> Resources {
     let has_cost = match player_action {
         PlayerAction::Unit { action, .. } => match action {
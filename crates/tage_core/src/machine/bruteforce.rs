@@ -68,8 +68,8 @@ fn all_turns(board: &mut Board) -> Vec<Vec<PlayerAction>> {
 
         if board
             .get_player(&board.current_player_turn)
-            .research_queued
-            .is_none()
+            .research_queue
+            .is_empty()
         {
             for act in PlayerAction::generate(&Pre::Global, board) {
                 star.push(partial.next_tech(act));
@@ -14,6 +14,7 @@ pub struct DistanceTravelMap {
     pub friendly_building: Grid<i32>,
     pub friendly_church: Grid<i32>,
     pub relic: Grid<i32>,
+    pub ruins: Grid<i32>,
     pub resource: Grid<i32>,
     pub unclaimed_resource: Grid<i32>,
     pub good_towncenter_spot: Grid<i32>,
@@ -29,18 +30,14 @@ impl DistanceTravelMap {
             friendly_building: grid.clone(),
             friendly_church: grid.clone(),
             relic: grid.clone(),
+            ruins: grid.clone(),
             resource: grid.clone(),
             unclaimed_resource: grid.clone(),
             good_towncenter_spot: grid.clone(),
         };
 
         let player = board.get_player(player_id);
-
-        let mut move_cost = grid.clone();
-        for xy in iter_area(board.grid.size) {
-            let tile = board.grid.get_at(&xy);
-            move_cost.set_at(&xy, tile.get_movement_cost(bp))
-        }
+        let move_cost = land_move_cost(bp, board, player_id);
 
         let town_center_bp = bp
             .get_unit_from_name("Town Center")
@@ -55,12 +52,13 @@ impl DistanceTravelMap {
         let mut unclaimed_resources_sources = vec![];
         let mut resources_sources = vec![];
         let mut relic_sources = vec![];
+        let mut ruins_sources = vec![];
         let mut tc_spot = vec![];
 
         for xy in iter_area(board.grid.size) {
             let tile = board.grid.get_at(&xy);
             if let Some(unit) = &tile.unit {
-                if player.is_hostile(board.get_player(&unit.owner)) {
+                if board.is_hostile(&player.id, &unit.owner) {
                     map.hostile_unit.set_at(&xy, 0);
                     hostile_unit_sources.push(xy);
                 } else {
@@ -69,7 +67,7 @@ impl DistanceTravelMap {
                 }
             }
             if let Some(building) = &tile.building {
-                if player.is_hostile(board.get_player(&building.owner)) {
+                if board.is_hostile(&player.id, &building.owner) {
                     map.hostile_building.set_at(&xy, 0);
                     hostile_building_sources.push(xy);
                 } else {
@@ -91,9 +89,16 @@ impl DistanceTravelMap {
                     unclaimed_resources_sources.push(xy);
                 }
             }
-            if let Some(Collectable::Relic) = &tile.terrain.collectable {
-                map.relic.set_at(&xy, 0);
-                relic_sources.push(xy);
+            match &tile.terrain.collectable {
+                Some(Collectable::Relic) => {
+                    map.relic.set_at(&xy, 0);
+                    relic_sources.push(xy);
+                }
+                Some(Collectable::Ruins) => {
+                    map.ruins.set_at(&xy, 0);
+                    ruins_sources.push(xy);
+                }
+                _ => {}
             }
             if let Some(tc) = town_center_bp {
                 let allowed_terrain =
@@ -136,10 +141,55 @@ impl DistanceTravelMap {
             unclaimed_resources_sources,
         );
         propagate_distance_sources(&mut map.relic, &move_cost, relic_sources);
+        propagate_distance_sources(&mut map.ruins, &move_cost, ruins_sources);
         propagate_distance_sources(&mut map.good_towncenter_spot, &move_cost, tc_spot);
 
         map
     }
+
+    /// Move cost of the shortest walk from `from` to every tile
+    pub fn distance_from(
+        bp: &Blueprints,
+        board: &Board,
+        player_id: &PlayerId,
+        from: IVec2,
+    ) -> Grid<i32> {
+        let mut distance = Grid::fill(board.grid.size, MAX_DISTANCE_NEG);
+        distance.set_at(&from, 0);
+        propagate_distance_sources(
+            &mut distance,
+            &land_move_cost(bp, board, player_id),
+            vec![from],
+        );
+        for xy in iter_area(distance.size) {
+            *distance.get_at_mut(&xy) *= -1;
+        }
+        distance
+    }
+}
+
+/// Walls, and the gates of hostile players, can't be walked through
+fn land_move_cost(bp: &Blueprints, board: &Board, player_id: &PlayerId) -> Grid<i32> {
+    let mut move_cost = Grid::fill(board.grid.size, MAX_DISTANCE_NEG);
+    for xy in iter_area(board.grid.size) {
+        let tile = board.grid.get_at(&xy);
+        let walled = tile.building.as_ref().is_some_and(|building| {
+            match bp.get_unit(&building.blueprint_id).barrier {
+                Barrier::None => false,
+                Barrier::Wall => true,
+                Barrier::Gate => board.is_hostile(&building.owner, player_id),
+            }
+        });
+        move_cost.set_at(
+            &xy,
+            if walled {
+                100
+            } else {
+                tile.get_movement_cost(bp, &MovementDomain::Land)
+            },
+        )
+    }
+    move_cost
 }
 
 const MAX_DISTANCE_NEG: i32 = -10000;
@@ -0,0 +1,44 @@
+use crate::{actions::player_action::Pre, prelude::*};
+
+use super::Machine;
+
+#[derive(Clone, Debug, Default)]
+pub struct Gaia;
+
+// Wildlife and guardians do not plan: every unit attacks the first hostile
+// it can reach and otherwise stays where it is
+impl Machine for Gaia {
+    fn turn_actions(&self, _bp: &Blueprints, board: &mut Board) -> Vec<PlayerAction> {
+        let mut player_actions = vec![];
+
+        let positions: Vec<IVec2> = board
+            .get_player_units_pos(&board.current_player_turn)
+            .map(|(_, xy)| xy)
+            .collect();
+        for pos in positions {
+            let attack = PlayerAction::generate(&Pre::Tile(pos), board)
+                .into_iter()
+                .find(|act| {
+                    matches!(
+                        act,
+                        PlayerAction::Unit {
+                            action: UnitAction::Attack(_),
+                            ..
+                        }
+                    )
+                });
+            if let Some(act) = attack {
+                act.apply(board);
+                player_actions.push(act);
+            }
+        }
+
+        for act in player_actions.iter().rev() {
+            act.undo(board)
+        }
+
+        player_actions.push(PlayerAction::PassTurn);
+
+        player_actions
+    }
+}
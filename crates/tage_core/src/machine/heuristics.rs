@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 
+use crate::actions::trade::{ActTrade, BASE_TRADE_RATE};
+
 use super::*;
 
 pub fn action_value_heuristic(
@@ -91,6 +93,8 @@ pub fn action_value_heuristic(
                 UnitAction::Merge(_) => -10,
                 UnitAction::Repair(_) => 70,
                 UnitAction::Power(_, _) => 20,
+                UnitAction::Embark(_) => 10,
+                UnitAction::Unload(_, _) => 30,
                 UnitAction::Done => 0,
             };
             pickup_value + heal_value + action_value
@@ -105,7 +109,10 @@ pub fn action_value_heuristic(
             }
             BuildingAction::Trade(resource) => {
                 let res = &board.get_current_player().resources;
-                (res.get_res(&resource) - res.get_res(&resource.other())) / 100 - 40
+                let rate = ActTrade::get_rate(&board.current_player_turn, resource, board);
+                (res.get_res(&resource) - res.get_res(&resource.other())) / 100
+                    - 40
+                    - (rate - BASE_TRADE_RATE) / 5
             }
             BuildingAction::AgeUp => 10000,
             BuildingAction::Done => 0,
@@ -140,6 +147,7 @@ pub fn unit_value_heuristic(bp: &Blueprints) -> UnitValueTable {
                     UnitClass::Cav => 50,
                     UnitClass::Sie => 25,
                     UnitClass::Ran => 35,
+                    UnitClass::Nav => 35,
                 },
         );
     }
@@ -1,13 +1,15 @@
-mod distance_travel_map;
+pub(crate) mod distance_travel_map;
 mod eval;
 mod heuristics;
 mod weighted;
 
 pub mod boulder;
+pub mod gaia;
 //pub mod bruteforce;
 pub mod peak;
 
 use boulder::*;
+use gaia::*;
 use peak::*;
 
 use crate::prelude::*;
@@ -31,6 +33,9 @@ pub enum MachineOpponent {
     WeakPeak,
     AveragePeak,
     StrongPeak,
+
+    /// Plays the neutral player
+    Gaia,
 }
 
 trait Machine {
@@ -42,7 +47,11 @@ pub fn get_machine_turn(
     board: &mut Board,
     machine: &MachineOpponent,
 ) -> Vec<PlayerAction> {
-    match machine {
+    // machines apply and undo a lot of actions while searching, none of them happened
+    // and undo can't take back the triggers
+    let events = std::mem::take(&mut board.events);
+    let scenario = std::mem::take(&mut board.scenario);
+    let actions = match machine {
         MachineOpponent::Boulder(boulder) => boulder.turn_actions(bp, board),
         MachineOpponent::Peak(peak) => peak.turn_actions(bp, board),
         MachineOpponent::WeakBoulder => Boulder { variance: 100 }.turn_actions(bp, board),
@@ -72,7 +81,11 @@ pub fn get_machine_turn(
             playout_brances: 1,
         }
         .turn_actions(bp, board),
-    }
+        MachineOpponent::Gaia => Gaia.turn_actions(bp, board),
+    };
+    board.events = events;
+    board.scenario = scenario;
+    actions
 }
 
 /// A node in the game tree with the branch that it took to get there
@@ -81,3 +94,32 @@ struct Ply {
     final_board: Board,
     actions: Vec<PlayerAction>,
 }
+
+/// Strength of each player as the machines see it, in `board.player_turn_order` order.
+/// Used by the score victory.
+pub fn player_scores(bp: &Blueprints, board: &Board) -> Vec<i32> {
+    let unit_value_table = heuristics::unit_value_heuristic(bp);
+    let pack = Pack {
+        bp,
+        branches: 0,
+        unit_value_table: &unit_value_table,
+    };
+    eval::Eval::from_board(board, &pack).scores
+}
+
+/// Value of the units of each player that aren't buildings, scaled by their health.
+/// In `board.players` order, used by the stats.
+pub fn army_values(bp: &Blueprints, board: &Board) -> Vec<i32> {
+    let unit_value_table = heuristics::unit_value_heuristic(bp);
+    board
+        .players
+        .iter()
+        .map(|player| {
+            board
+                .get_player_units(&player.id)
+                .filter(|unit| board.unit_loc(unit) == UnitLocation::Top)
+                .map(|unit| (unit_value_table.get(&unit.blueprint_id) * unit.health) / 100)
+                .sum()
+        })
+        .collect()
+}
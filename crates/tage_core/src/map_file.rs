@@ -0,0 +1,162 @@
+use std::path::Path;
+
+use crate::{game::split_map_lines, is_default, prelude::*};
+
+/// Bumped every time the map file format changes.
+/// Maps of another version are refused instead of being loaded wrong.
+pub const MAP_VERSION: u32 = 1;
+
+/// A map with its metadata, saved as ron wrapping the grid of a `.txt` map
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct MapFile {
+    pub version: u32,
+    pub name: String,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub author: String,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub description: String,
+
+    /// Players the map is made for, the spawn points may allow more
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub recommended_players: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub preview: MapPreview,
+
+    /// Rule variants the map can be played with, any if empty.
+    /// The first one is picked when the map is chosen in the lobby
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub rules: Vec<Rules>,
+
+    /// The grid and the lines after it, in the format of the `.txt` maps
+    pub grid: String,
+}
+
+/// How the map is shown before the game
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct MapPreview {
+    /// Tile in the middle of the preview, the center of the map if not set
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub center: Option<IVec2>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub zoom: Option<i32>,
+}
+
+impl MapFile {
+    /// Wraps the grid of a `.txt` map, without metadata
+    pub fn from_grid(name: &str, grid: &str) -> Self {
+        Self {
+            version: MAP_VERSION,
+            name: name.to_string(),
+            grid: grid.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn from_string(map_str: &str) -> Result<Self, ParseMapError> {
+        let map: MapFile = ron::from_str(map_str)
+            .map_err(|err| ParseMapError::MapFileNotRecognized(err.to_string()))?;
+        if map.version != MAP_VERSION {
+            return Err(ParseMapError::UnsupportedVersion(map.version));
+        }
+        Ok(map)
+    }
+
+    /// Loads `.ron` map files, any other file is read as the grid of a `.txt` map
+    pub fn load(path: &str) -> Result<Self, ParseMapError> {
+        let raw = std::fs::read_to_string(path).map_err(|_| ParseMapError::FileReadFailure)?;
+        let path = Path::new(path);
+        if path.extension().is_some_and(|ext| ext == "ron") {
+            Self::from_string(&raw)
+        } else {
+            let name = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default();
+            Ok(Self::from_grid(name, &raw))
+        }
+    }
+
+    pub fn to_string_pretty(&self) -> String {
+        let config = ron::ser::PrettyConfig::default().escape_strings(false);
+        ron::ser::to_string_pretty(self, config).unwrap()
+    }
+
+    /// Number of spawn points in the grid, the most players the map can hold
+    pub fn spawn_count(&self) -> usize {
+        let (_, lines) = split_map_lines(&self.grid);
+        lines
+            .iter()
+            .flat_map(|line| line.split_whitespace())
+            .filter(|tile| tile.chars().nth(1).is_some_and(|c| c.is_ascii_hexdigit()))
+            .count()
+    }
+
+    pub fn allows(&self, rules: &Rules) -> bool {
+        self.rules.is_empty() || self.rules.contains(rules)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::{temp_path, test_bp};
+
+    use super::*;
+
+    #[test]
+    fn txt_maps_load_without_metadata() {
+        let map = MapFile::load("../../assets/maps/oasis.txt").unwrap();
+        assert_eq!(map.name, "oasis");
+        assert_eq!(map.version, MAP_VERSION);
+        assert_eq!(map.spawn_count(), 4);
+        assert!(map.allows(&Rules::default()));
+    }
+
+    #[test]
+    fn ron_maps_load_with_metadata() {
+        let map = MapFile::load("../../assets/maps/hastings.ron").unwrap();
+        assert_eq!(map.name, "Hastings");
+        assert_eq!(map.recommended_players, Some(2));
+        assert_eq!(map.spawn_count(), 2);
+        assert_eq!(MapFile::from_string(&map.to_string_pretty()).unwrap(), map);
+
+        let old = MapFile {
+            version: MAP_VERSION + 1,
+            ..map
+        };
+        assert!(matches!(
+            MapFile::from_string(&old.to_string_pretty()),
+            Err(ParseMapError::UnsupportedVersion(_))
+        ));
+    }
+
+    #[test]
+    fn maps_refuse_rules_not_allowed() {
+        let bp = test_bp();
+        let no_rng = Rules {
+            no_rng: true,
+            ..Default::default()
+        };
+        let map = MapFile {
+            rules: vec![no_rng.clone()],
+            ..MapFile::from_grid("test", "-0- ---\n--- ---")
+        };
+        let path = temp_path("tage_map_rules.ron");
+        std::fs::write(&path, map.to_string_pretty()).unwrap();
+        let settings = MapSettings::default().with_path(path);
+        assert!(matches!(
+            load_map(&bp, &settings),
+            Err(ParseMapError::RulesNotAllowed)
+        ));
+        let settings = MapSettings {
+            rules: no_rng,
+            ..settings
+        };
+        assert!(load_map(&bp, &settings).is_ok());
+    }
+}
@@ -0,0 +1,519 @@
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    is_default,
+    map_report::{survey_board, walking_turns},
+    prelude::*,
+    v,
+};
+
+/// Smallest side of a generated map, room for the spawns and their resources
+pub const MAP_GEN_MIN_SIZE: i32 = 12;
+
+/// Most food or gold tiles placed around each spawn point
+pub const MAP_GEN_MAX_RESOURCES: i32 = 8;
+
+/// Resources are placed within this distance from the spawn points
+pub const MAP_GEN_RESOURCE_RADIUS: i32 = 4;
+
+/// Parameters of a generated map, the same settings always generate the same map
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    bincode::Encode,
+    bincode::Decode,
+)]
+#[serde(default)]
+pub struct MapGenSettings {
+    pub seed: u64,
+    pub size: IVec2,
+    pub players: u32,
+
+    /// Percent of the tiles covered by water
+    pub water: i32,
+
+    /// Percent of the land covered by forest
+    pub forest: i32,
+
+    /// Food tiles near each spawn point
+    pub food: i32,
+
+    /// Gold tiles near each spawn point
+    pub gold: i32,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub symmetry: Symmetry,
+}
+
+impl Default for MapGenSettings {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            size: IVec2::splat(20),
+            players: 2,
+            water: 15,
+            forest: 20,
+            food: 3,
+            gold: 2,
+            symmetry: Symmetry::default(),
+        }
+    }
+}
+
+/// How the parts of a generated map repeat each other.
+/// Maps for more than four players are never symmetric
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    bincode::Encode,
+    bincode::Decode,
+)]
+pub enum Symmetry {
+    /// The spawn points are spread on a ring, the terrain doesn't repeat
+    None,
+
+    /// Mirrored left to right, also top to bottom with more than two players
+    #[default]
+    Mirror,
+
+    /// Rotated around the center, also mirrored with more than two players
+    Rotation,
+}
+
+/// The tiles repeating each other in a symmetric map
+struct Images {
+    size: IVec2,
+    symmetry: Symmetry,
+    quarters: bool,
+}
+
+impl Images {
+    /// The tile itself first, then the tiles repeating it
+    fn of(&self, xy: IVec2) -> Vec<IVec2> {
+        let flip_x = v!(self.size.x - 1 - xy.x, xy.y);
+        let flip_y = v!(xy.x, self.size.y - 1 - xy.y);
+        let flip = v!(self.size.x - 1 - xy.x, self.size.y - 1 - xy.y);
+        match (&self.symmetry, self.quarters) {
+            (Symmetry::None, _) => vec![xy],
+            (_, true) => vec![xy, flip, flip_x, flip_y],
+            (Symmetry::Mirror, false) => vec![xy, flip_x],
+            (Symmetry::Rotation, false) => vec![xy, flip],
+        }
+    }
+
+    /// Tiles whose terrain is picked, every other one copies one of them
+    fn is_original(&self, xy: IVec2) -> bool {
+        self.of(xy).iter().all(|i| (xy.y, xy.x) <= (i.y, i.x))
+    }
+
+    fn paint(&self, grid: &mut Grid<BoardTile>, xy: IVec2, f: impl Fn(&mut BoardTile)) {
+        for image in self.of(xy) {
+            f(grid.get_at_mut(&image));
+        }
+    }
+}
+
+/// Generates a map of `settings.players` spawn points, without units.
+/// Every spawn point can be reached walking from the others and has the same food
+/// and gold tiles near it.
+pub fn generate_map(bp: &Blueprints, settings: &MapGenSettings) -> Grid<BoardTile> {
+    let mut rng = BoardRng::new(settings.seed);
+    let players = settings.players.max(1);
+    // the ring of spawn points has room for each player
+    let min_size = MAP_GEN_MIN_SIZE.max(players as i32 * 3 / 2);
+    let size = settings.size.max(IVec2::splat(min_size));
+    let images = Images {
+        size,
+        symmetry: if players > 4 {
+            Symmetry::None
+        } else {
+            settings.symmetry.clone()
+        },
+        quarters: players > 2,
+    };
+    let terrain = |name| bp.get_terrain_from_name(name).unwrap();
+    let plains = TerrainTile {
+        blueprint_id: terrain("Plains"),
+        ..Default::default()
+    };
+
+    let mut grid = Grid::fill(
+        size,
+        BoardTile {
+            terrain: plains.clone(),
+            ..Default::default()
+        },
+    );
+
+    // smooth noise grouped in lakes and woods, the lowest tiles get the terrain
+    let originals: Vec<IVec2> = iter_area(size)
+        .filter(|xy| images.is_original(*xy))
+        .collect();
+    let mut lowest = |percent: i32, among: &[IVec2]| -> (Vec<IVec2>, Vec<IVec2>) {
+        let noise = smooth_noise(&mut rng, size);
+        let mut tiles = among.to_vec();
+        tiles.sort_by_key(|xy| *noise.get_at(xy));
+        let count = (tiles.len() as i32 * percent.clamp(0, 100) / 100) as usize;
+        let rest = tiles.split_off(count);
+        (tiles, rest)
+    };
+    let (water, land) = lowest(settings.water, &originals);
+    let (forest, land) = lowest(settings.forest, &land);
+    let (relief, _) = lowest(10, &land);
+    let mut painted = vec![];
+    painted.extend(water.iter().map(|xy| (*xy, "Water")));
+    painted.extend(forest.iter().map(|xy| (*xy, "Forest")));
+    for (i, xy) in relief.iter().enumerate() {
+        painted.push((*xy, if i % 3 == 0 { "Mountain" } else { "Hills" }));
+    }
+    for (xy, name) in painted {
+        let id = terrain(name);
+        images.paint(&mut grid, xy, |tile| tile.terrain.blueprint_id = id.clone());
+    }
+
+    let spawns = place_spawns(&mut rng, &images, players);
+    let mut offsets: Vec<IVec2> = iter_area(IVec2::splat(MAP_GEN_RESOURCE_RADIUS * 2 + 1))
+        .map(|xy| xy - IVec2::splat(MAP_GEN_RESOURCE_RADIUS))
+        .filter(|off| (2..=MAP_GEN_RESOURCE_RADIUS).contains(&off.length()))
+        .collect();
+    offsets.shuffle(&mut rng);
+
+    // the area of the starting units is left as plain land
+    let placed = match images.symmetry {
+        Symmetry::None => spawns.clone(),
+        _ => vec![spawns[0]],
+    };
+    for spawn in spawns.iter() {
+        for xy in starting_area(*spawn) {
+            images.paint(&mut grid, xy, |tile| tile.terrain = plains.clone());
+        }
+    }
+    for (i, spawn) in spawns.iter().enumerate() {
+        grid.get_at_mut(spawn).spawn_point = Some(PlayerId::new(i as u32));
+    }
+
+    for spawn in placed.iter() {
+        let resources = [
+            (Resource::Food, terrain("Plains"), settings.food),
+            (Resource::Gold, terrain("Hills"), settings.gold),
+        ];
+        let mut candidates = offsets.iter().map(|off| *spawn + *off).filter(|xy| {
+            images.of(*xy).iter().all(|image| {
+                rect_contains(&size, image) && spawns.iter().all(|s| (*s - *image).length() >= 2)
+            })
+        });
+        for (resource, terrain_id, count) in resources {
+            for _ in 0..count.clamp(0, MAP_GEN_MAX_RESOURCES) {
+                let Some(xy) = candidates.find(|xy| grid.get_at(xy).terrain.resource.is_none())
+                else {
+                    break;
+                };
+                images.paint(&mut grid, xy, |tile| {
+                    tile.terrain = TerrainTile {
+                        blueprint_id: terrain_id.clone(),
+                        resource: Some(resource.clone()),
+                        ..Default::default()
+                    }
+                });
+            }
+        }
+    }
+
+    // water between the spawn points is crossed by fords
+    let (water, ford) = (terrain("Water"), terrain("Ford"));
+    for target in unreachable_spawns(bp, &grid, &spawns) {
+        for xy in straight_path(spawns[0], target) {
+            if grid.get_at(&xy).terrain.blueprint_id == water {
+                images.paint(&mut grid, xy, |tile| {
+                    tile.terrain.blueprint_id = ford.clone()
+                });
+            }
+        }
+    }
+
+    // anything else in the way is cleared
+    for target in unreachable_spawns(bp, &grid, &spawns) {
+        for xy in straight_path(spawns[0], target) {
+            if grid
+                .get_at(&xy)
+                .get_movement_cost(bp, &MovementDomain::Land)
+                >= IMPASSABLE_MOVE_COST
+            {
+                images.paint(&mut grid, xy, |tile| tile.terrain = plains.clone());
+            }
+        }
+    }
+
+    grid
+}
+
+/// Tiles of the starting units of a spawn point, see `load_map_with_starts`
+fn starting_area(spawn: IVec2) -> [IVec2; 3] {
+    [spawn, spawn - IVec2::X, spawn + IVec2::Y]
+}
+
+/// Tiles from `from` to `to` moving along x first, without `from`
+fn straight_path(from: IVec2, to: IVec2) -> Vec<IVec2> {
+    let mut path = vec![];
+    let mut xy = from;
+    while xy != to {
+        if xy.x != to.x {
+            xy.x += (to.x - xy.x).signum();
+        } else {
+            xy.y += (to.y - xy.y).signum();
+        }
+        path.push(xy);
+    }
+    path
+}
+
+/// Random values averaged with the neighbors, so that low and high values form patches
+fn smooth_noise(rng: &mut BoardRng, size: IVec2) -> Grid<i32> {
+    let mut noise = Grid::default(size);
+    for xy in iter_area(size) {
+        noise.set_at(&xy, rng.gen_range(0..1000));
+    }
+    for _ in 0..3 {
+        let mut smooth = Grid::default(size);
+        for xy in iter_area(size) {
+            let adjacent = noise.get_adjacent(&xy);
+            let sum: i32 = adjacent.iter().map(|(_, v)| **v).sum::<i32>() + noise.get_at(&xy);
+            smooth.set_at(&xy, sum / (adjacent.len() as i32 + 1));
+        }
+        noise = smooth;
+    }
+    noise
+}
+
+/// The spawn point of each player, in order, far from the edges and from each other
+fn place_spawns(rng: &mut BoardRng, images: &Images, players: u32) -> Vec<IVec2> {
+    let size = images.size;
+    let margin = v!(2, 2);
+    let jitter = |rng: &mut BoardRng, v: i32| v + rng.gen_range(-1..=1);
+    let spawns = match (&images.symmetry, images.quarters) {
+        (Symmetry::None, _) => {
+            let offset = rng.gen_range(0.0..std::f32::consts::TAU);
+            let radius = size.x.min(size.y) as f32 * 0.35;
+            (0..players)
+                .map(|i| {
+                    let angle = offset + std::f32::consts::TAU * i as f32 / players as f32;
+                    v!(
+                        size.x / 2 + (angle.cos() * radius).round() as i32,
+                        size.y / 2 + (angle.sin() * radius).round() as i32
+                    )
+                })
+                .collect()
+        }
+        (_, true) => {
+            let first = v!(jitter(rng, size.x / 4), jitter(rng, size.y / 4));
+            images.of(first)
+        }
+        (_, false) => {
+            let first = v!(
+                jitter(rng, size.x / 5),
+                rng.gen_range(size.y / 3..=size.y * 2 / 3)
+            );
+            images.of(first)
+        }
+    };
+    let spawns: Vec<IVec2> = spawns
+        .into_iter()
+        .take(players as usize)
+        .map(|xy| xy.clamp(margin, size - margin - IVec2::ONE))
+        .collect();
+    if images.symmetry != Symmetry::None {
+        return spawns;
+    }
+
+    // rounded on a small ring the spawn points can be too close, each one
+    // moves to the nearest tile where its starting units are apart from the others
+    let mut inner: Vec<IVec2> = iter_area(size - margin * 2).map(|xy| xy + margin).collect();
+    let mut placed: Vec<IVec2> = vec![];
+    for spawn in spawns {
+        inner.sort_by_key(|xy| ((*xy - spawn).length(), xy.y, xy.x));
+        let apart = |xy: &IVec2| {
+            placed.iter().all(|other| {
+                starting_area(*xy).iter().all(|a| {
+                    starting_area(*other)
+                        .iter()
+                        .all(|b| (*a - *b).length() >= 2)
+                })
+            })
+        };
+        if let Some(xy) = inner.iter().find(|xy| apart(xy)) {
+            placed.push(*xy);
+        }
+    }
+    placed
+}
+
+/// Spawn points a Villager can't walk to from the first spawn point, in any number of turns
+fn unreachable_spawns(bp: &Blueprints, grid: &Grid<BoardTile>, spawns: &[IVec2]) -> Vec<IVec2> {
+    let reached = walking_turns(&survey_board(bp, grid), spawns[0], None);
+    spawns
+        .iter()
+        .filter(|xy| !reached.contains_key(xy))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::test_bp;
+
+    use super::*;
+
+    fn spawns(grid: &Grid<BoardTile>) -> Vec<IVec2> {
+        let mut spawns: Vec<(PlayerId, IVec2)> = grid
+            .iter()
+            .filter_map(|(xy, tile)| tile.spawn_point.clone().map(|id| (id, xy)))
+            .collect();
+        spawns.sort_by_key(|(id, _)| id.get());
+        spawns.into_iter().map(|(_, xy)| xy).collect()
+    }
+
+    fn near(grid: &Grid<BoardTile>, spawn: IVec2, resource: Resource) -> usize {
+        grid.iter()
+            .filter(|(xy, tile)| {
+                (*xy - spawn).length() <= MAP_GEN_RESOURCE_RADIUS
+                    && tile.terrain.resource == Some(resource.clone())
+            })
+            .count()
+    }
+
+    #[test]
+    fn same_seed_same_map() {
+        let bp = test_bp();
+        let settings = MapGenSettings {
+            seed: 7,
+            ..Default::default()
+        };
+        assert_eq!(generate_map(&bp, &settings), generate_map(&bp, &settings));
+        let other = MapGenSettings {
+            seed: 8,
+            ..settings.clone()
+        };
+        assert_ne!(generate_map(&bp, &settings), generate_map(&bp, &other));
+    }
+
+    #[test]
+    fn spawns_reach_each_other() {
+        let bp = test_bp();
+        for (seed, symmetry, players) in [
+            (1, Symmetry::Mirror, 2),
+            (2, Symmetry::Rotation, 4),
+            (3, Symmetry::None, 3),
+            (4, Symmetry::None, 6),
+        ] {
+            let settings = MapGenSettings {
+                seed,
+                players,
+                water: 65,
+                symmetry,
+                ..Default::default()
+            };
+            let grid = generate_map(&bp, &settings);
+            let spawns = spawns(&grid);
+            assert_eq!(spawns.len(), players as usize);
+            assert!(unreachable_spawns(&bp, &grid, &spawns).is_empty());
+
+            // the generated map is a valid map with its starting units
+            let map = write_map(&bp, &grid);
+            assert_eq!(parse_map(&bp, &map).unwrap().grid, grid);
+        }
+    }
+
+    #[test]
+    fn crowded_spawns_stay_apart() {
+        let bp = test_bp();
+        for (seed, players) in [(1, 8), (2, 12), (3, 16)] {
+            let settings = MapGenSettings {
+                seed,
+                size: IVec2::splat(MAP_GEN_MIN_SIZE),
+                players,
+                water: 40,
+                symmetry: Symmetry::None,
+                ..Default::default()
+            };
+            let grid = generate_map(&bp, &settings);
+            let spawns = spawns(&grid);
+            assert_eq!(spawns.len(), players as usize);
+            let areas: Vec<IVec2> = spawns.iter().flat_map(|s| starting_area(*s)).collect();
+            for (i, a) in areas.iter().enumerate() {
+                assert!(grid.contains(a));
+                assert!(!areas[i + 1..].contains(a), "{} taken twice", a);
+            }
+            assert!(unreachable_spawns(&bp, &grid, &spawns).is_empty());
+        }
+    }
+
+    #[test]
+    fn spawns_have_the_same_resources() {
+        let bp = test_bp();
+        for symmetry in [Symmetry::Mirror, Symmetry::Rotation, Symmetry::None] {
+            let settings = MapGenSettings {
+                seed: 11,
+                players: 4,
+                food: 4,
+                gold: 3,
+                symmetry: symmetry.clone(),
+                ..Default::default()
+            };
+            let grid = generate_map(&bp, &settings);
+            let spawns = spawns(&grid);
+            for spawn in spawns.iter() {
+                assert!(near(&grid, *spawn, Resource::Food) >= 4);
+                assert!(near(&grid, *spawn, Resource::Gold) >= 3);
+            }
+            if symmetry != Symmetry::None {
+                let food: Vec<usize> = spawns
+                    .iter()
+                    .map(|s| near(&grid, *s, Resource::Food))
+                    .collect();
+                assert!(food.iter().all(|f| *f == food[0]));
+            }
+        }
+    }
+
+    #[test]
+    fn map_settings_generate_the_map() {
+        let bp = test_bp();
+        let generator = MapGenSettings {
+            seed: 5,
+            players: 3,
+            ..Default::default()
+        };
+        let settings = MapSettings {
+            players: (0..3)
+                .map(|id| MapPlayerSettings {
+                    id: PlayerId::new(id),
+                    ..Default::default()
+                })
+                .collect(),
+            generator: Some(generator.clone()),
+            ..Default::default()
+        };
+        let grid = load_map(&bp, &settings).unwrap();
+        assert_eq!(grid.size, generator.size);
+        for id in 0..3 {
+            assert_eq!(
+                grid.iter()
+                    .filter(|(_, tile)| tile
+                        .unit
+                        .as_ref()
+                        .is_some_and(|unit| unit.owner == PlayerId::new(id)))
+                    .count(),
+                3
+            );
+        }
+    }
+}
@@ -0,0 +1,422 @@
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    fmt::Display,
+};
+
+use crate::{machine::distance_travel_map::DistanceTravelMap, prelude::*};
+
+use self::travel::ActTravel;
+
+/// What the fairness of a map is measured on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapReportSettings {
+    /// Turns of walking in which the resources near a spawn point are counted
+    pub turns: i32,
+
+    /// Percent of the largest value the spawn points can differ by before being flagged
+    pub threshold: i32,
+}
+
+impl Default for MapReportSettings {
+    fn default() -> Self {
+        Self {
+            turns: 2,
+            threshold: 20,
+        }
+    }
+}
+
+/// Fairness and validity of a map, measured from each spawn point.
+/// Distances are in movement points, walking on land
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapReport {
+    pub settings: MapReportSettings,
+    pub spawns: Vec<SpawnReport>,
+    pub relics: usize,
+    pub ruins: usize,
+    pub warnings: Vec<MapWarning>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpawnReport {
+    pub num: u32,
+    pub at: IVec2,
+
+    /// Distance to the nearest spawn point that can be walked to
+    pub nearest_spawn: Option<i32>,
+
+    /// Food tiles a Villager walks to within the turns of the report
+    pub food: i32,
+
+    /// Gold tiles a Villager walks to within the turns of the report
+    pub gold: i32,
+
+    /// `None` if no relic can be walked to
+    pub nearest_relic: Option<i32>,
+
+    /// `None` if no ruins can be walked to
+    pub nearest_ruins: Option<i32>,
+
+    /// Spawn points that can't be walked to from this one
+    pub walled_off_from: Vec<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapWarning {
+    /// The spawn point can't walk to some other spawn point
+    WalledOff(u32),
+
+    /// The map has some of the collectable but the spawn point can't walk to any
+    OutOfReach(u32, Collectable),
+
+    /// The spawn points differ by more than the threshold
+    Unbalanced {
+        measure: MapMeasure,
+        min: i32,
+        max: i32,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapMeasure {
+    SpawnDistance,
+    Food,
+    Gold,
+    RelicDistance,
+    RuinsDistance,
+}
+
+impl MapMeasure {
+    const ALL: [MapMeasure; 5] = [
+        MapMeasure::SpawnDistance,
+        MapMeasure::Food,
+        MapMeasure::Gold,
+        MapMeasure::RelicDistance,
+        MapMeasure::RuinsDistance,
+    ];
+
+    fn of(&self, spawn: &SpawnReport) -> Option<i32> {
+        match self {
+            MapMeasure::SpawnDistance => spawn.nearest_spawn,
+            MapMeasure::Food => Some(spawn.food),
+            MapMeasure::Gold => Some(spawn.gold),
+            MapMeasure::RelicDistance => spawn.nearest_relic,
+            MapMeasure::RuinsDistance => spawn.nearest_ruins,
+        }
+    }
+}
+
+impl MapReport {
+    pub fn new(bp: &Blueprints, grid: &Grid<BoardTile>, settings: &MapReportSettings) -> Self {
+        let board = survey_board(bp, grid);
+        let player_id = board.players[0].id.clone();
+        let mut spawn_points: Vec<(u32, IVec2)> = grid
+            .iter()
+            .filter_map(|(xy, tile)| tile.spawn_point.as_ref().map(|id| (id.get(), xy)))
+            .collect();
+        spawn_points.sort_by_key(|(num, _)| *num);
+
+        let collectables = |kind: Collectable| -> Vec<IVec2> {
+            grid.iter()
+                .filter(|(_, tile)| tile.terrain.collectable == Some(kind.clone()))
+                .map(|(xy, _)| xy)
+                .collect()
+        };
+        let relics = collectables(Collectable::Relic);
+        let ruins = collectables(Collectable::Ruins);
+        let fields = DistanceTravelMap::from_board(bp, &board, &player_id);
+
+        // spawn points in the same group walk to each other
+        let mut groups: Vec<HashSet<IVec2>> = vec![];
+        for (_, at) in spawn_points.iter() {
+            if groups.iter().all(|group| !group.contains(at)) {
+                groups.push(walking_turns(&board, *at, None).into_keys().collect());
+            }
+        }
+
+        let spawns: Vec<SpawnReport> = spawn_points
+            .iter()
+            .map(|(num, at)| {
+                let group = groups.iter().find(|group| group.contains(at)).unwrap();
+                let walled_off_from = spawn_points
+                    .iter()
+                    .filter(|(_, other)| !group.contains(other))
+                    .map(|(n, _)| *n)
+                    .collect();
+                let distance = DistanceTravelMap::distance_from(bp, &board, &player_id, *at);
+                let nearest_spawn = spawn_points
+                    .iter()
+                    .filter(|(_, other)| other != at && group.contains(other))
+                    .map(|(_, other)| *distance.get_at(other))
+                    .min();
+
+                let in_reach = walking_turns(&board, *at, Some(settings.turns));
+                let count = |resource: Resource| {
+                    in_reach
+                        .keys()
+                        .filter(|xy| grid.get_at(xy).terrain.resource == Some(resource.clone()))
+                        .count() as i32
+                };
+                // the fields don't tell the unreachable ones apart
+                let nearest = |tiles: &Vec<IVec2>, field: &Grid<i32>| {
+                    tiles
+                        .iter()
+                        .any(|xy| group.contains(xy))
+                        .then(|| -field.get_at(at))
+                };
+                SpawnReport {
+                    num: *num,
+                    at: *at,
+                    nearest_spawn,
+                    food: count(Resource::Food),
+                    gold: count(Resource::Gold),
+                    nearest_relic: nearest(&relics, &fields.relic),
+                    nearest_ruins: nearest(&ruins, &fields.ruins),
+                    walled_off_from,
+                }
+            })
+            .collect();
+
+        let mut warnings: Vec<MapWarning> = spawns
+            .iter()
+            .filter(|spawn| !spawn.walled_off_from.is_empty())
+            .map(|spawn| MapWarning::WalledOff(spawn.num))
+            .collect();
+        for spawn in spawns.iter() {
+            if !relics.is_empty() && spawn.nearest_relic.is_none() {
+                warnings.push(MapWarning::OutOfReach(spawn.num, Collectable::Relic));
+            }
+            if !ruins.is_empty() && spawn.nearest_ruins.is_none() {
+                warnings.push(MapWarning::OutOfReach(spawn.num, Collectable::Ruins));
+            }
+        }
+        for measure in MapMeasure::ALL {
+            let values: Vec<i32> = spawns.iter().filter_map(|s| measure.of(s)).collect();
+            let (Some(min), Some(max)) = (values.iter().min(), values.iter().max()) else {
+                continue;
+            };
+            if (max - min) * 100 > max * settings.threshold {
+                warnings.push(MapWarning::Unbalanced {
+                    measure,
+                    min: *min,
+                    max: *max,
+                });
+            }
+        }
+
+        Self {
+            settings: settings.clone(),
+            spawns,
+            relics: relics.len(),
+            ruins: ruins.len(),
+            warnings,
+        }
+    }
+
+    pub fn from_map_file(
+        bp: &Blueprints,
+        file: &MapFile,
+        settings: &MapReportSettings,
+    ) -> Result<Self, ParseMapError> {
+        Ok(Self::new(bp, &parse_map(bp, &file.grid)?.grid, settings))
+    }
+
+    pub fn is_fair(&self) -> bool {
+        self.warnings.is_empty()
+    }
+}
+
+impl Display for MapReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = |v: Option<i32>| v.map_or("-".to_string(), |v| v.to_string());
+        writeln!(
+            f,
+            "{:<6}{:<10}{:<8}{:<6}{:<6}{:<7}{:<7}",
+            "spawn", "at", "spawn", "food", "gold", "relic", "ruins"
+        )?;
+        for spawn in self.spawns.iter() {
+            writeln!(
+                f,
+                "{:<6}{:<10}{:<8}{:<6}{:<6}{:<7}{:<7}",
+                spawn.num,
+                spawn.at.to_string(),
+                value(spawn.nearest_spawn),
+                spawn.food,
+                spawn.gold,
+                value(spawn.nearest_relic),
+                value(spawn.nearest_ruins),
+            )?;
+        }
+        writeln!(
+            f,
+            "{} relics, {} ruins, food and gold within {} turns",
+            self.relics, self.ruins, self.settings.turns
+        )?;
+        if self.is_fair() {
+            writeln!(f, "No difference above {}%", self.settings.threshold)?;
+        }
+        for warning in self.warnings.iter() {
+            writeln!(f, "{}", warning)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for MapWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MapWarning::WalledOff(num) => write!(f, "Spawn {} is walled off", num),
+            MapWarning::OutOfReach(num, collectable) => {
+                write!(f, "Spawn {} can't walk to any {:?}", num, collectable)
+            }
+            MapWarning::Unbalanced { measure, min, max } => {
+                let measure = match measure {
+                    MapMeasure::SpawnDistance => "Distance to the nearest spawn",
+                    MapMeasure::Food => "Food in reach",
+                    MapMeasure::Gold => "Gold in reach",
+                    MapMeasure::RelicDistance => "Distance to the nearest relic",
+                    MapMeasure::RuinsDistance => "Distance to the nearest ruins",
+                };
+                write!(f, "{} goes from {} to {}", measure, min, max)
+            }
+        }
+    }
+}
+
+/// A board of the map without units and with a single player, to walk on
+pub(crate) fn survey_board(bp: &Blueprints, grid: &Grid<BoardTile>) -> Board {
+    let player = MapPlayerSettings::default().to_player(bp);
+    Board::new(bp, grid.clone(), vec![player])
+}
+
+/// Turns a Villager of the first player takes to walk from `from` to each tile it reaches,
+/// in at most `max_turns` if set
+pub(crate) fn walking_turns(
+    board: &Board,
+    from: IVec2,
+    max_turns: Option<i32>,
+) -> HashMap<IVec2, i32> {
+    let walker = Unit {
+        blueprint_id: board.bp.get_unit_from_name("Villager").unwrap(),
+        owner: board.players[0].id.clone(),
+        ..Default::default()
+    };
+    let mut board = board.clone();
+    let mut turns = HashMap::from([(from, 0)]);
+    let mut reached = vec![from];
+    let mut turn = 0;
+    while !reached.is_empty() && max_turns.is_none_or(|max| turn < max) {
+        turn += 1;
+        let mut next = vec![];
+        for at in reached {
+            board.grid.get_at_mut(&at).unit = Some(walker.clone());
+            for reachable in ActTravel::get_reachable(&UnitTarget::new(walker.clone(), at), &board)
+            {
+                if let Entry::Vacant(entry) = turns.entry(reachable.destination) {
+                    entry.insert(turn);
+                    next.push(reachable.destination);
+                }
+            }
+            board.grid.get_at_mut(&at).unit = None;
+        }
+        reached = next;
+    }
+    turns
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{test::test_bp, v};
+
+    use super::*;
+
+    fn report(bp: &Blueprints, map: &str) -> MapReport {
+        MapReport::new(
+            bp,
+            &parse_map(bp, map).unwrap().grid,
+            &MapReportSettings::default(),
+        )
+    }
+
+    #[test]
+    fn mirrored_map_is_fair() {
+        let bp = test_bp();
+        let report = report(
+            &bp,
+            "\
++-- --- --- -r- --- --- +--
+-0- --- ### ### ### --- -1-
+$)) --- --- -?- --- --- $))",
+        );
+        assert!(report.is_fair(), "{}", report);
+        assert_eq!(report.spawns.len(), 2);
+        let spawn = &report.spawns[0];
+        assert_eq!(spawn.at, v!(0, 1));
+        assert_eq!((spawn.food, spawn.gold), (1, 1));
+        assert_eq!(spawn.nearest_spawn, Some(15));
+        assert_eq!(spawn.nearest_relic, Some(8));
+        assert_eq!(spawn.nearest_ruins, Some(8));
+        assert_eq!(report.spawns[1].nearest_spawn, Some(15));
+    }
+
+    #[test]
+    fn walled_off_and_unbalanced_spawns_are_flagged() {
+        let bp = test_bp();
+        let report = report(
+            &bp,
+            "\
++-- -0- --- ... -1- ---
++-- --- --- ... --- -2-",
+        );
+        assert!(report.warnings.contains(&MapWarning::WalledOff(0)));
+        assert!(report.warnings.contains(&MapWarning::WalledOff(1)));
+        assert_eq!(report.spawns[0].walled_off_from, vec![1, 2]);
+        assert_eq!(report.spawns[1].nearest_spawn, Some(4));
+        assert!(report.warnings.contains(&MapWarning::Unbalanced {
+            measure: MapMeasure::Food,
+            min: 0,
+            max: 2
+        }));
+    }
+
+    #[test]
+    fn unreachable_relics_are_not_near() {
+        let bp = test_bp();
+        let report = report(
+            &bp,
+            "\
+-0- --- ... -r- ... -1-",
+        );
+        assert_eq!(report.spawns[0].nearest_relic, None);
+        assert_eq!(report.spawns[1].nearest_relic, None);
+        assert_eq!(report.spawns[0].nearest_ruins, None);
+        assert!(report
+            .warnings
+            .contains(&MapWarning::OutOfReach(0, Collectable::Relic)));
+        assert!(!report
+            .warnings
+            .contains(&MapWarning::OutOfReach(0, Collectable::Ruins)));
+    }
+
+    #[test]
+    fn shipped_maps_have_no_walled_off_spawns() {
+        let bp = test_bp();
+        for path in std::fs::read_dir("../../assets/maps").unwrap() {
+            let path = path.unwrap().path();
+            let Ok(file) = MapFile::load(path.to_str().unwrap()) else {
+                continue;
+            };
+            let report =
+                MapReport::from_map_file(&bp, &file, &MapReportSettings::default()).unwrap();
+            assert!(
+                report
+                    .warnings
+                    .iter()
+                    .all(|w| !matches!(w, MapWarning::WalledOff(_))),
+                "{:?}\n{}",
+                path,
+                report
+            );
+        }
+    }
+}
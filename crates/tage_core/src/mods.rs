@@ -0,0 +1,432 @@
+//! Mod packs laid over the base blueprints and maps.
+//!
+//! A mod is a directory in the mods directory with a `mod.ron` describing it,
+//! any of the blueprint files in `blueprints/` and maps in `maps/`.
+//! Only the mods listed in `load_order.ron` are loaded, the later ones win.
+
+use std::{fmt::Display, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::blueprints::*;
+
+#[cfg(test)]
+use crate::map_file::MapFile;
+
+/// List of the directories of the mods to load, in order
+pub const MOD_LOAD_ORDER: &str = "load_order.ron";
+
+/// Name, version and description of a mod, in its directory
+pub const MOD_INFO: &str = "mod.ron";
+
+/// Paths of the maps of the mods start with this, so they are the same on every machine
+pub const MOD_MAPS_PREFIX: &str = "mods/";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModInfo {
+    pub name: String,
+    pub version: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModPack {
+    /// Name of the directory of the mod
+    pub dir: String,
+    pub info: ModInfo,
+
+    /// The blueprint files of the mod, named `<dir>/<file>`
+    pub sources: BlueprintSources,
+
+    /// File names of the maps of the mod
+    pub maps: Vec<String>,
+}
+
+/// The same blueprint or map changed by more than one mod, the last one wins
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModConflict {
+    /// Blueprint file without the extension, or "maps"
+    pub kind: String,
+    pub name: String,
+
+    /// Directories of the mods, in load order
+    pub mods: Vec<String>,
+}
+
+impl Display for ModConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "\"{}\" in {} is changed by {}, {} wins",
+            self.name,
+            self.kind,
+            self.mods.join(", "),
+            self.mods.last().unwrap()
+        )
+    }
+}
+
+/// The mods to load, in load order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModSet {
+    /// Directory of the mods
+    pub dir: String,
+    pub mods: Vec<ModPack>,
+    pub conflicts: Vec<ModConflict>,
+
+    /// Hash of the blueprints and maps played, the base ones included.
+    /// The same on every machine with the same files, set with `fingerprint_files`
+    pub fingerprint: u64,
+}
+
+impl ModSet {
+    /// Loads the mods listed in the load order, no mods if there is no load order
+    pub fn load(dir: &str) -> Result<Self, BlueprintLoadError> {
+        let load_order_path = format!("{}/{}", dir, MOD_LOAD_ORDER);
+        if !Path::new(&load_order_path).exists() {
+            return Ok(Self {
+                dir: dir.to_string(),
+                ..Default::default()
+            });
+        }
+        let load_order: Vec<String> = parse_file(MOD_LOAD_ORDER, &read_file(&load_order_path)?)?;
+
+        let mut mods = vec![];
+        for mod_dir in load_order {
+            let path = format!("{}/{}", dir, mod_dir);
+            let info_path = format!("{}/{}", path, MOD_INFO);
+            if !Path::new(&info_path).exists() {
+                return Err(BlueprintLoadError::Invalid(vec![BlueprintDiagnostic {
+                    file: MOD_LOAD_ORDER.to_string(),
+                    position: None,
+                    message: format!("no mod in \"{}\", it needs a {}", path, MOD_INFO),
+                }]));
+            }
+            let info_text = read_file(&info_path)?;
+            let info: ModInfo = parse_file(&format!("{}/{}", mod_dir, MOD_INFO), &info_text)?;
+
+            let mut sources = BlueprintSources::default();
+            for file in BLUEPRINT_FILES {
+                let file_path = format!("{}/blueprints/{}", path, file);
+                if Path::new(&file_path).exists() {
+                    let text = read_file(&file_path)?;
+                    sources.files.push((format!("{}/{}", mod_dir, file), text));
+                }
+            }
+
+            let mut maps: Vec<String> = fs::read_dir(format!("{}/maps", path))
+                .map(|entries| {
+                    entries
+                        .filter_map(|entry| entry.ok()?.file_name().to_str().map(String::from))
+                        .collect()
+                })
+                .unwrap_or_default();
+            maps.sort();
+
+            mods.push(ModPack {
+                dir: mod_dir,
+                info,
+                sources,
+                maps,
+            });
+        }
+
+        let conflicts = find_conflicts(&mods)?;
+        Ok(Self {
+            dir: dir.to_string(),
+            mods,
+            conflicts,
+            ..Default::default()
+        })
+    }
+
+    /// Hashes the blueprint files and the maps played with these mods into the fingerprint
+    pub fn fingerprint_files(
+        &mut self,
+        blueprints_dir: &str,
+        assets_dir: &str,
+    ) -> Result<(), BlueprintLoadError> {
+        let mut hash = Fnv::default();
+        for (label, text) in self.blueprint_sources(blueprints_dir)?.files {
+            hash.write(label.as_bytes());
+            hash.write(text.as_bytes());
+        }
+        for path in self.map_paths(assets_dir) {
+            let located = self.locate(&path);
+            if !Path::new(&located).is_file() {
+                continue;
+            }
+            // the assets may be somewhere else on the other machines
+            hash.write(path.strip_prefix(assets_dir).unwrap_or(&path).as_bytes());
+            hash.write(read_file(&located)?.as_bytes());
+        }
+        self.fingerprint = hash.0;
+        Ok(())
+    }
+
+    /// The base blueprint files followed by the ones of the mods, in load order
+    pub fn blueprint_sources(
+        &self,
+        base_path: &str,
+    ) -> Result<BlueprintSources, BlueprintLoadError> {
+        let mut sources = BlueprintSources::read(base_path)?;
+        for pack in self.mods.iter() {
+            sources.files.extend(pack.sources.files.iter().cloned());
+        }
+        Ok(sources)
+    }
+
+    /// Names and versions of the mods, in load order
+    pub fn names(&self) -> Vec<String> {
+        self.mods
+            .iter()
+            .map(|pack| format!("{} {}", pack.info.name, pack.info.version))
+            .collect()
+    }
+
+    /// Paths of the maps in the assets and in the mods.
+    /// A map of a mod replaces the one with the same name without the extension
+    pub fn map_paths(&self, assets_dir: &str) -> Vec<String> {
+        let mut paths: Vec<String> = fs::read_dir(format!("{}/maps/", assets_dir))
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok()?.path().to_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default();
+        for pack in self.mods.iter() {
+            for map in pack.maps.iter() {
+                paths.retain(|path| map_name(path) != map_name(map));
+                paths.push(format!("{}{}/maps/{}", MOD_MAPS_PREFIX, pack.dir, map));
+            }
+        }
+        paths.sort();
+        paths
+    }
+
+    /// Path on this machine of a map listed by `map_paths`
+    pub fn locate(&self, path: &str) -> String {
+        match path.strip_prefix(MOD_MAPS_PREFIX) {
+            Some(rest) => format!("{}/{}", self.dir, rest),
+            None => path.to_string(),
+        }
+    }
+}
+
+/// File name without the directories and the extension
+fn map_name(path: &str) -> &str {
+    let file = path.rsplit('/').next().unwrap_or(path);
+    file.split('.').next().unwrap_or(file)
+}
+
+fn find_conflicts(mods: &[ModPack]) -> Result<Vec<ModConflict>, BlueprintLoadError> {
+    let mut changed: Vec<(String, String, Vec<String>)> = vec![];
+    let mut change = |kind: &str, name: &str, mod_dir: &str| match changed
+        .iter_mut()
+        .find(|(k, n, _)| k == kind && n == name)
+    {
+        Some((_, _, dirs)) => dirs.push(mod_dir.to_string()),
+        None => changed.push((
+            kind.to_string(),
+            name.to_string(),
+            vec![mod_dir.to_string()],
+        )),
+    };
+    for pack in mods {
+        for (label, text) in pack.sources.files.iter() {
+            let file = label.rsplit('/').next().unwrap();
+            let kind = file.trim_end_matches(".ron");
+            for name in declared_names(label, file, text)? {
+                change(kind, &name, &pack.dir);
+            }
+        }
+        for map in pack.maps.iter() {
+            change("maps", map_name(map), &pack.dir);
+        }
+    }
+    Ok(changed
+        .into_iter()
+        .filter(|(_, _, dirs)| dirs.len() > 1)
+        .map(|(kind, name, mods)| ModConflict { kind, name, mods })
+        .collect())
+}
+
+/// Names of the blueprints in a file, battle bonuses have none
+fn declared_names(label: &str, file: &str, text: &str) -> Result<Vec<String>, BlueprintLoadError> {
+    fn names<T: NamedBlueprint>(
+        label: &str,
+        text: &str,
+    ) -> Result<Vec<String>, BlueprintLoadError> {
+        let blueprints: Vec<T> = parse_file(label, text)?;
+        Ok(blueprints.iter().map(|b| b.name().to_string()).collect())
+    }
+    match file {
+        "terrains.ron" => names::<TerrainBlueprint>(label, text),
+        "units.ron" => names::<UnitBlueprint>(label, text),
+        "techs.ron" => names::<TechBlueprint>(label, text),
+        "abilities.ron" => names::<AbilityBlueprint>(label, text),
+        "powers.ron" => names::<PowerBlueprint>(label, text),
+        "civilizations.ron" => names::<CivilizationBlueprint>(label, text),
+        _ => Ok(vec![]),
+    }
+}
+
+/// FNV-1a, the std hashers aren't guaranteed to be the same across versions
+struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Fnv {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::{temp_path, test_bp};
+
+    use super::*;
+
+    const KNIGHT: &str = "Knights";
+
+    /// Two mods in a new directory, both changing the knight
+    fn write_mods(name: &str) -> String {
+        let dir = temp_path(name);
+        let _ = fs::remove_dir_all(&dir);
+        let base = test_bp();
+        let knight = base.get_unit(&base.get_unit_from_name(KNIGHT).unwrap());
+
+        let unit = |name: &str, attack: i32| {
+            let mut unit = knight.clone();
+            unit.header.name = name.to_string();
+            unit.stats.attack = attack;
+            unit.header.id = UnitId(0);
+            unit
+        };
+        let mods = [
+            ("strong", vec![unit(KNIGHT, 99)]),
+            ("paladins", vec![unit(KNIGHT, 42), unit("Lancers", 50)]),
+        ];
+        for (mod_dir, units) in mods.iter() {
+            let path = format!("{}/{}", dir, mod_dir);
+            fs::create_dir_all(format!("{}/blueprints", path)).unwrap();
+            fs::create_dir_all(format!("{}/maps", path)).unwrap();
+            let info = ModInfo {
+                name: mod_dir.to_string(),
+                version: "1.0".to_string(),
+                description: String::new(),
+            };
+            fs::write(
+                format!("{}/{}", path, MOD_INFO),
+                ron::to_string(&info).unwrap(),
+            )
+            .unwrap();
+            let units = ron::ser::to_string_pretty(units, Default::default()).unwrap();
+            fs::write(format!("{}/blueprints/units.ron", path), units).unwrap();
+        }
+        let oasis = fs::read_to_string("../../assets/maps/oasis.txt").unwrap();
+        fs::write(format!("{}/paladins/maps/oasis.txt", dir), oasis).unwrap();
+        fs::write(
+            format!("{}/{}", dir, MOD_LOAD_ORDER),
+            "[\"strong\", \"paladins\"]",
+        )
+        .unwrap();
+        dir
+    }
+
+    fn load(mods: &ModSet) -> Result<Blueprints, BlueprintLoadError> {
+        Blueprints::from_sources(
+            &mods.blueprint_sources(&("../../".to_string() + BLUEPRINTS_PATH))?,
+        )
+    }
+
+    #[test]
+    fn mods_override_by_name_in_load_order() {
+        let dir = write_mods("tage_mods_override");
+        let mods = ModSet::load(&dir).unwrap();
+        assert_eq!(mods.names(), vec!["strong 1.0", "paladins 1.0"]);
+        assert_eq!(
+            mods.conflicts
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>(),
+            vec!["\"Knights\" in units is changed by strong, paladins, paladins wins"]
+        );
+
+        let base = load(&ModSet::default()).unwrap();
+        let bp = load(&mods).unwrap();
+        let knight = base.get_unit_from_name(KNIGHT).unwrap();
+        assert_eq!(bp.get_unit_from_name(KNIGHT), Some(knight.clone()));
+        assert_eq!(bp.get_unit(&knight).stats.attack, 42);
+        let lancers = bp.get_unit_from_name("Lancers").unwrap();
+        assert_eq!(
+            lancers.0,
+            base.units.keys().map(|id| id.0).max().unwrap() + 1
+        );
+
+        let oasis = mods
+            .map_paths("../../assets")
+            .into_iter()
+            .find(|path| path.contains("oasis"))
+            .unwrap();
+        assert_eq!(oasis, "mods/paladins/maps/oasis.txt");
+        assert!(MapFile::load(&mods.locate(&oasis)).is_ok());
+    }
+
+    fn fingerprint(dir: &str, blueprints_dir: &str) -> u64 {
+        let mut mods = ModSet::load(dir).unwrap();
+        mods.fingerprint_files(blueprints_dir, "../../assets")
+            .unwrap();
+        mods.fingerprint
+    }
+
+    #[test]
+    fn fingerprint_follows_the_files() {
+        let dir = write_mods("tage_mods_fingerprint");
+        let base = "../../".to_string() + BLUEPRINTS_PATH;
+        let modded = fingerprint(&dir, &base);
+        assert_eq!(fingerprint(&dir, &base), modded);
+
+        let path = format!("{}/strong/blueprints/units.ron", dir);
+        let units = fs::read_to_string(&path).unwrap();
+        fs::write(&path, units.replace("99", "98")).unwrap();
+        assert_ne!(fingerprint(&dir, &base), modded);
+
+        fs::remove_file(format!("{}/{}", dir, MOD_LOAD_ORDER)).unwrap();
+        let unmodded = fingerprint(&dir, &base);
+        assert_ne!(unmodded, modded);
+
+        // a randomized base is told apart even without mods
+        let randomized = &temp_path("tage_mods_fingerprint_base");
+        let bp = Blueprints::from_assets_location(&base).unwrap();
+        let settings = RandomizerSettings {
+            seed: 3,
+            ..Default::default()
+        };
+        bp.randomized(&settings)
+            .to_assets_location(randomized)
+            .unwrap();
+        assert_ne!(fingerprint(&dir, randomized), unmodded);
+    }
+
+    #[test]
+    fn mistakes_in_mods_point_to_the_mod() {
+        let dir = write_mods("tage_mods_mistakes");
+        fs::write(format!("{}/strong/blueprints/techs.ron", dir), "[(name: ").unwrap();
+        match ModSet::load(&dir) {
+            Err(BlueprintLoadError::Invalid(diagnostics)) => {
+                assert_eq!(diagnostics[0].file, "strong/techs.ron")
+            }
+            other => panic!("expected diagnostics, got {:?}", other.map(|_| ())),
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use std::ops::Deref;
 
-use crate::{blueprints::*, prelude::MachineOpponent};
+use crate::{blueprints::*, is_default, prelude::MachineOpponent};
 
 #[derive(
     Default,
@@ -40,14 +40,24 @@ impl TeamId {
 }
 
 impl PlayerId {
+    /// The neutral player owning the units placed by the map
+    pub const GAIA: PlayerId = PlayerId(u32::MAX);
+
     pub fn new(id: u32) -> Self {
         Self(id)
     }
     pub fn get(&self) -> u32 {
         self.0
     }
+    pub fn is_gaia(&self) -> bool {
+        *self == Self::GAIA
+    }
     pub fn view(&self) -> String {
-        format!("{}", self.0)
+        if self.is_gaia() {
+            "Gaia".to_string()
+        } else {
+            format!("{}", self.0)
+        }
     }
 }
 
@@ -74,6 +84,15 @@ pub enum QueuedResearch {
     AgeUp,
 }
 
+impl QueuedResearch {
+    pub fn view(&self, bp: &Blueprints) -> String {
+        match self {
+            QueuedResearch::Tech(id) => bp.get_tech(id).name.clone(),
+            QueuedResearch::AgeUp => "Age Up".to_string(),
+        }
+    }
+}
+
 #[derive(
     Clone,
     Debug,
@@ -92,9 +111,17 @@ pub struct Player {
     pub symbol: String,
     pub level: i32,
     pub researched_technologies: Vec<TechId>,
-    pub research_queued: Option<QueuedResearch>,
+    /// Paid research in order with the resources paid for it, the first one completes
+    /// at the start of the next turn and each of the others on the turn after the previous one.
+    /// The resources are refunded when the research is cancelled
+    pub research_queue: Vec<(QueuedResearch, Resources)>,
     pub tech_discount: Resources,
     pub train_discount: Resources,
+
+    /// Food sold minus gold sold in the last days, moves the trade rates.
+    /// Recovers by one towards zero at the start of each turn of the player
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub trade_pressure: i32,
     pub civilization: CivilizationId,
     pub team: Option<TeamId>,
     pub controller: Controller,
@@ -102,6 +129,18 @@ pub struct Player {
 }
 
 impl Player {
+    /// The neutral player, hostile to everyone and played by a minimal machine
+    pub fn gaia() -> Self {
+        Player {
+            id: PlayerId::GAIA,
+            color: 0x808080,
+            symbol: "g".to_string(),
+            name: "Gaia".to_string(),
+            controller: Controller::Machine(MachineOpponent::Gaia),
+            ..Default::default()
+        }
+    }
+
     pub fn get_resource(&self, resource: &Resource) -> i32 {
         match resource {
             Resource::Food => self.resources.food,
@@ -141,6 +180,20 @@ impl Player {
                 >= Self::get_age_up_tech_count(self.level)
     }
 
+    /// The research is in the queue
+    pub fn is_researching(&self, research: &QueuedResearch) -> bool {
+        self.research_queue
+            .iter()
+            .any(|(queued, _)| queued == research)
+    }
+
+    pub fn get_research_cost(&self, bp: &Blueprints, research: &QueuedResearch) -> Resources {
+        match research {
+            QueuedResearch::Tech(id) => bp.get_tech(id).cost.clone() - self.tech_discount.clone(),
+            QueuedResearch::AgeUp => self.get_age_up_cost(),
+        }
+    }
+
     pub fn get_age_up_cost(&self) -> Resources {
         let val = (self.level + 1) * 500;
         Resources {
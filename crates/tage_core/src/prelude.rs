@@ -1,8 +1,21 @@
 pub use crate::actions::*;
 pub use crate::blueprints::*;
+pub use crate::campaign::*;
+pub use crate::diplomacy::*;
+pub use crate::events::*;
 pub use crate::game::*;
 pub use crate::grid::*;
 pub use crate::machine::*;
+pub use crate::map_file::*;
+pub use crate::map_gen::*;
+pub use crate::map_report::*;
+pub use crate::mods::*;
 pub use crate::player::*;
+pub use crate::replay::*;
+pub use crate::rng::*;
+pub use crate::rules::*;
+pub use crate::scenario::*;
+pub use crate::stats::*;
 pub use crate::unit::*;
 pub use crate::vec2::*;
+pub use crate::victory::*;
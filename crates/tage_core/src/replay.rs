@@ -0,0 +1,190 @@
+use std::io;
+
+use ron::de::SpannedError;
+
+use crate::prelude::*;
+
+/// Bumped every time the replay format or the action semantics change.
+/// Replays of another version are refused instead of being replayed wrong.
+pub const REPLAY_VERSION: u32 = 5;
+
+/// A recorded game: the starting board and every action applied to it.
+/// The board rng is saved with the starting board, so playing back the
+/// actions rebuilds the exact same game.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Replay {
+    pub version: u32,
+    pub settings: MapSettings,
+    pub seed: u64,
+    pub start: BoardView,
+    pub actions: Vec<PlayerAction>,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    ReadingFile(io::Error),
+    Parsing(SpannedError),
+    UnsupportedVersion(u32),
+}
+
+impl From<SpannedError> for ReplayError {
+    fn from(value: SpannedError) -> Self {
+        Self::Parsing(value)
+    }
+}
+
+impl From<io::Error> for ReplayError {
+    fn from(value: io::Error) -> Self {
+        Self::ReadingFile(value)
+    }
+}
+
+impl Replay {
+    /// Starts recording from the current state of the board
+    pub fn new(settings: &MapSettings, board: &Board) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            settings: settings.clone(),
+            seed: board.rng.seed(),
+            start: BoardView::from(board.bp(), board),
+            actions: vec![],
+        }
+    }
+
+    /// Record an action just applied to the board, as it was applied after `fog_bonk`
+    pub fn record(&mut self, action: &PlayerAction) {
+        self.actions.push(action.clone());
+    }
+
+    /// Forget the last recorded action, call it when the action is undone
+    pub fn undo(&mut self) -> Option<PlayerAction> {
+        self.actions.pop()
+    }
+
+    pub fn start_board(&self, bp: &Blueprints) -> Board {
+        self.start.clone().to(bp)
+    }
+
+    /// Rebuilds the board after the first `index` actions
+    pub fn board_at(&self, bp: &Blueprints, index: usize) -> Board {
+        let mut board = self.start_board(bp);
+        for action in self.actions.iter().take(index) {
+            action.apply(&mut board);
+        }
+        board
+    }
+
+    /// Rebuilds the board at the start of `day`, the last board if the game ended before
+    pub fn board_at_day(&self, bp: &Blueprints, day: u32) -> Board {
+        let index = self.day_start(bp, day);
+        self.board_at(bp, index)
+    }
+
+    /// Index of the first action of `day`, the number of actions if the game ended before
+    pub fn day_start(&self, bp: &Blueprints, day: u32) -> usize {
+        self.days(bp)
+            .iter()
+            .position(|d| *d >= day)
+            .unwrap_or(self.actions.len())
+    }
+
+    /// The day in which each action was applied
+    pub fn days(&self, bp: &Blueprints) -> Vec<u32> {
+        let mut board = self.start_board(bp);
+        self.actions
+            .iter()
+            .map(|action| {
+                let day = board.day;
+                action.apply(&mut board);
+                day
+            })
+            .collect()
+    }
+
+    pub fn from_string(replay_str: &str) -> Result<Self, ReplayError> {
+        let replay: Replay = ron::from_str(replay_str)?;
+        if replay.version != REPLAY_VERSION {
+            return Err(ReplayError::UnsupportedVersion(replay.version));
+        }
+        Ok(replay)
+    }
+
+    pub fn to_string_pretty(&self) -> String {
+        let config = ron::ser::PrettyConfig::default()
+            .escape_strings(false)
+            .compact_arrays(true)
+            .depth_limit(2);
+        ron::ser::to_string_pretty(self, config).unwrap()
+    }
+
+    pub fn load(path: &str) -> Result<Self, ReplayError> {
+        let raw = std::fs::read_to_string(path)?;
+        Self::from_string(&raw)
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        std::fs::write(path, self.to_string_pretty())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        test::{players_board, test_bp},
+        v,
+    };
+
+    use super::*;
+
+    fn test_board(bp: &Blueprints) -> Board {
+        let map = "--- --- --- ---\n--- --- --- ---\n--- --- --- ---";
+        let mut board = Board {
+            rng: BoardRng::new(3),
+            ..players_board(bp, map, 2, Resources::default())
+        };
+        let militia = bp.get_unit_from_name("Militia").unwrap();
+        for x in 0..4 {
+            board.grid.get_at_mut(&v!(x, 1)).unit = Some(Unit {
+                blueprint_id: militia.clone(),
+                owner: PlayerId::new(x as u32 % 2),
+                holding_collectable: Some(Collectable::Ruins),
+                ..Default::default()
+            });
+        }
+        board
+    }
+
+    #[test]
+    fn playback_rebuilds_the_game() {
+        let bp = test_bp();
+        let mut board = test_board(&bp);
+        let mut replay = Replay::new(&MapSettings::default(), &board);
+        let mut history = vec![board.clone()];
+        for _ in 0..6 {
+            PlayerAction::PassTurn.apply(&mut board);
+            replay.record(&PlayerAction::PassTurn);
+            history.push(board.clone());
+        }
+
+        let loaded = Replay::from_string(&replay.to_string_pretty()).unwrap();
+        assert_eq!(loaded.seed, 3);
+        for (i, expected) in history.iter().enumerate() {
+            assert_eq!(&loaded.board_at(&bp, i), expected, "action {}", i);
+        }
+        assert_eq!(loaded.board_at_day(&bp, 2), history[4]);
+        assert_eq!(loaded.board_at_day(&bp, 100), board);
+    }
+
+    #[test]
+    fn refuse_other_versions() {
+        let replay = Replay {
+            version: REPLAY_VERSION + 1,
+            ..Default::default()
+        };
+        assert!(matches!(
+            Replay::from_string(&replay.to_string_pretty()),
+            Err(ReplayError::UnsupportedVersion(_))
+        ));
+    }
+}
@@ -0,0 +1,91 @@
+use rand::{thread_rng, Error, Rng, RngCore};
+
+/// Deterministic random number generator owned by the `Board`.
+/// Every random roll of the game (conversions, ruins, markets) goes through it,
+/// so that the same seed and the same actions always produce the same game.
+///
+/// The generator is a SplitMix64, small enough to be saved alongside the board.
+#[derive(
+    Default,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    bincode::Encode,
+    bincode::Decode,
+)]
+pub struct BoardRng {
+    /// The seed the generator was created with, kept to reproduce the game
+    seed: u64,
+
+    /// Current state, advanced by every roll
+    state: u64,
+}
+
+impl BoardRng {
+    pub fn new(seed: u64) -> Self {
+        Self { seed, state: seed }
+    }
+
+    /// Pick a random seed, used when the map settings don't specify one
+    pub fn from_entropy() -> Self {
+        Self::new(thread_rng().gen())
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl RngCore for BoardRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_rolls() {
+        let mut a = BoardRng::new(42);
+        let mut b = BoardRng::new(42);
+        for _ in 0..100 {
+            assert_eq!(a.gen_range(0..=4), b.gen_range(0..=4));
+        }
+        assert_eq!(a, b);
+        assert_eq!(a.seed(), 42);
+    }
+
+    #[test]
+    fn different_seed_different_rolls() {
+        let mut a = BoardRng::new(1);
+        let mut b = BoardRng::new(2);
+        let rolls_a: Vec<u64> = (0..8).map(|_| a.next_u64()).collect();
+        let rolls_b: Vec<u64> = (0..8).map(|_| b.next_u64()).collect();
+        assert_ne!(rolls_a, rolls_b);
+    }
+}
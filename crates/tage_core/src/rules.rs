@@ -0,0 +1,85 @@
+use crate::is_default;
+
+/// Rule variants picked in the map settings
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    bincode::Encode,
+    bincode::Decode,
+)]
+#[serde(default)]
+pub struct Rules {
+    /// Removes every roll of the board rng for competitive games:
+    /// Monks deal conversion damage, ruins give `ruins_reward` and Markets offer every unit
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub no_rng: bool,
+
+    /// What ruins give when there is no rng
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub ruins_reward: RuinsReward,
+
+    /// Whose trades move the trade rates of a player
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub market: MarketModel,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    bincode::Encode,
+    bincode::Decode,
+)]
+pub enum MarketModel {
+    /// Only the trades of the player
+    #[default]
+    PerPlayer,
+
+    /// The trades of every player
+    Shared,
+}
+
+/// The outcomes of picking up ruins, apart from losing the unit
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    bincode::Encode,
+    bincode::Decode,
+)]
+pub enum RuinsReward {
+    Food,
+    #[default]
+    Gold,
+    Tech,
+    Militia,
+}
+
+impl RuinsReward {
+    /// Food and gold given by the ruins
+    pub const RESOURCES: i32 = 200;
+}
+
+/// Percent chance of a conversion of this strength to succeed.
+/// Without rng it is the health lost by the target instead
+pub fn conversion_chance(strength: i32) -> i32 {
+    match strength {
+        -1 => 10,
+        0 => 25,
+        1 => 33,
+        _ => 50,
+    }
+}
@@ -0,0 +1,397 @@
+use crate::{is_default, prelude::*};
+
+/// Scripted event of a scenario: when the condition is met the actions run, once.
+/// A scenario is a map settings file with triggers.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    bincode::Encode,
+    bincode::Decode,
+)]
+pub struct Trigger {
+    pub condition: TriggerCondition,
+    pub actions: Vec<TriggerAction>,
+}
+
+/// Players set to `None` match any player, units are matched by name.
+/// Areas are rectangles with both corners included.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    bincode::Encode,
+    bincode::Decode,
+)]
+pub enum TriggerCondition {
+    DayReached(u32),
+    UnitEntersArea {
+        player: Option<PlayerId>,
+        from: IVec2,
+        to: IVec2,
+    },
+    UnitKilled {
+        player: Option<PlayerId>,
+        unit: Option<String>,
+    },
+    BuildingDestroyed {
+        player: Option<PlayerId>,
+        building: Option<String>,
+    },
+}
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    bincode::Encode,
+    bincode::Decode,
+)]
+pub enum TriggerAction {
+    /// Places a unit or a building of a single tile, nothing happens if the slot is taken
+    Spawn {
+        player: PlayerId,
+        unit: String,
+        at: IVec2,
+    },
+    GrantResources {
+        player: PlayerId,
+        resources: Resources,
+    },
+
+    /// Hidden tiles of the area become explored for the player
+    RevealFog {
+        player: PlayerId,
+        from: IVec2,
+        to: IVec2,
+    },
+    Message(String),
+
+    /// The alliance of the player wins
+    Win(PlayerId),
+
+    /// The player is defeated, its units stay on the map
+    Lose(PlayerId),
+}
+
+/// Triggers of the scenario being played and what they did so far
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    bincode::Encode,
+    bincode::Decode,
+)]
+#[serde(default)]
+pub struct ScenarioState {
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub triggers: Vec<Trigger>,
+
+    /// Indexes of the triggers that already fired
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub fired: Vec<usize>,
+
+    /// Shown messages, in order
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub messages: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub winner: Option<PlayerId>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub defeated: Vec<PlayerId>,
+}
+
+impl ScenarioState {
+    pub fn new(triggers: Vec<Trigger>) -> Self {
+        Self {
+            triggers,
+            ..Default::default()
+        }
+    }
+
+    /// Some trigger can still fire
+    pub fn is_active(&self) -> bool {
+        self.fired.len() < self.triggers.len()
+    }
+
+    /// Owners of the units spawned by the triggers
+    pub fn spawned_players(&self) -> impl Iterator<Item = &PlayerId> {
+        self.triggers
+            .iter()
+            .flat_map(|trigger| &trigger.actions)
+            .filter_map(|action| match action {
+                TriggerAction::Spawn { player, .. } => Some(player),
+                _ => None,
+            })
+    }
+}
+
+/// Applies `f` to the board, then fires the triggers it satisfied.
+/// Firing is not undone: the caller has to treat the action as not undoable.
+pub fn apply_with_triggers(board: &mut Board, f: impl FnOnce(&mut Board)) {
+    if !board.scenario.is_active() {
+        f(board);
+        return;
+    }
+
+    // the triggers need the events even if nobody else is collecting them
+    let mut outer = std::mem::take(&mut board.events);
+    board.events.collect();
+    f(board);
+    let events = board.events.take();
+    for event in events.iter() {
+        outer.emit(|| event.clone());
+    }
+    board.events = outer;
+
+    let ready: Vec<usize> = (0..board.scenario.triggers.len())
+        .filter(|i| !board.scenario.fired.contains(i))
+        .filter(|i| is_met(board, &board.scenario.triggers[*i].condition, &events))
+        .collect();
+    for i in ready {
+        board.scenario.fired.push(i);
+        for action in board.scenario.triggers[i].actions.clone() {
+            run(board, action);
+        }
+    }
+}
+
+fn is_met(board: &Board, condition: &TriggerCondition, events: &[GameEvent]) -> bool {
+    let is_player =
+        |player: &Option<PlayerId>, id: &PlayerId| player.as_ref().is_none_or(|p| p == id);
+    let is_named = |name: &Option<String>, unit: &Unit| {
+        name.as_ref()
+            .is_none_or(|name| &board.bp.get_unit(&unit.blueprint_id).header.name == name)
+    };
+    let killed = |player: &Option<PlayerId>, name: &Option<String>, loc: UnitLocation| {
+        events.iter().any(|event| match event {
+            GameEvent::UnitKilled { target, .. } => {
+                board.unit_loc(&target.unit) == loc
+                    && is_player(player, &target.unit.owner)
+                    && is_named(name, &target.unit)
+            }
+            _ => false,
+        })
+    };
+    match condition {
+        TriggerCondition::DayReached(day) => board.day >= *day,
+        TriggerCondition::UnitEntersArea { player, from, to } => {
+            board.get_units_pos().any(|(unit, xy)| {
+                board.unit_loc(unit) == UnitLocation::Top
+                    && is_player(player, &unit.owner)
+                    && in_area(xy, *from, *to)
+            })
+        }
+        TriggerCondition::UnitKilled { player, unit } => killed(player, unit, UnitLocation::Top),
+        TriggerCondition::BuildingDestroyed { player, building } => {
+            killed(player, building, UnitLocation::Bot)
+        }
+    }
+}
+
+fn run(board: &mut Board, action: TriggerAction) {
+    match action {
+        TriggerAction::Spawn { player, unit, at } => {
+            let Some(unit_id) = board.bp.get_unit_from_name(&unit) else {
+                return;
+            };
+            if !board.grid.contains(&at)
+                || board.bp.get_unit(&unit_id).unit_size.size != 1
+                || !board.players.iter().any(|p| p.id == player)
+            {
+                return;
+            }
+            let unit = Unit {
+                blueprint_id: unit_id,
+                owner: player,
+                ..Default::default()
+            };
+            let pos = UnitPos::new(at, board.unit_loc(&unit));
+            if board.get_unit(&pos).is_none() {
+                board.set_unit_target(UnitTarget::new(unit, at));
+            }
+        }
+        TriggerAction::GrantResources { player, resources } => {
+            if board.players.iter().any(|p| p.id == player) {
+                board.get_player_mut(&player).resources += resources;
+            }
+        }
+        TriggerAction::RevealFog { player, from, to } => {
+            if let Some(fog) = board.fog.get_mut(&player) {
+                for xy in iter_area(fog.size) {
+                    if in_area(xy, from, to) && fog.get_at(&xy) == &FogTile::Hidden {
+                        fog.set_at(&xy, FogTile::Explored);
+                    }
+                }
+            }
+        }
+        TriggerAction::Message(message) => board.scenario.messages.push(message),
+        TriggerAction::Win(player) => {
+            board.scenario.winner.get_or_insert(player);
+        }
+        TriggerAction::Lose(player) => {
+            if !board.scenario.defeated.contains(&player) {
+                board.scenario.defeated.push(player);
+            }
+        }
+    }
+}
+
+fn in_area(xy: IVec2, from: IVec2, to: IVec2) -> bool {
+    xy.x >= from.x.min(to.x)
+        && xy.x <= from.x.max(to.x)
+        && xy.y >= from.y.min(to.y)
+        && xy.y <= from.y.max(to.y)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        actions::player_action::Pre,
+        test::{place_unit, players_board, test_bp},
+        v,
+    };
+
+    use super::*;
+
+    fn test_board(triggers: Vec<Trigger>) -> Board {
+        let mut board = Board {
+            scenario: ScenarioState::new(triggers),
+            ..players_board(&test_bp(), "--- --- ---", 2, Resources::default())
+        };
+        place_unit(&mut board, v!(1, 0), "Militia", 0);
+        place_unit(&mut board, v!(2, 0), "Militia", 1);
+        board
+            .grid
+            .get_at_mut(&v!(2, 0))
+            .unit
+            .as_mut()
+            .unwrap()
+            .health = 10;
+        board
+    }
+
+    #[test]
+    fn scenario_assets_parse() {
+        let raw = std::fs::read_to_string("../../assets/scenarios/oasis_guardians.ron").unwrap();
+        let settings = MapSettings::from_string(&raw).unwrap();
+        assert_eq!(settings.triggers.len(), 6);
+    }
+
+    #[test]
+    fn spawn_for_missing_player_is_refused() {
+        let raw = std::fs::read_to_string("../../assets/scenarios/oasis_guardians.ron").unwrap();
+        let mut settings = MapSettings::from_string(&raw)
+            .unwrap()
+            .with_path("../../assets/maps/oasis.txt".to_string());
+        assert!(Board::from_settings(&test_bp(), &settings).is_ok());
+        settings.triggers.push(Trigger {
+            condition: TriggerCondition::DayReached(3),
+            actions: vec![TriggerAction::Spawn {
+                player: PlayerId::new(5),
+                unit: "Militia".to_string(),
+                at: v!(0, 0),
+            }],
+        });
+        assert!(matches!(
+            Board::from_settings(&test_bp(), &settings),
+            Err(ParseMapError::TriggerPlayerNotRecognized(player)) if player == PlayerId::new(5)
+        ));
+
+        let mut board = test_board(vec![Trigger {
+            condition: TriggerCondition::DayReached(0),
+            actions: vec![TriggerAction::Spawn {
+                player: PlayerId::GAIA,
+                unit: "Militia".to_string(),
+                at: v!(0, 0),
+            }],
+        }]);
+        apply_with_triggers(&mut board, |_| {});
+        assert_eq!(board.scenario.fired, vec![0]);
+        assert!(board.get_unit(&UnitPos::top(v!(0, 0))).is_none());
+    }
+
+    #[test]
+    fn kill_wins_the_scenario() {
+        let mut board = test_board(vec![Trigger {
+            condition: TriggerCondition::UnitKilled {
+                player: Some(PlayerId::new(1)),
+                unit: Some("Militia".to_string()),
+            },
+            actions: vec![
+                TriggerAction::Message("Victory".to_string()),
+                TriggerAction::Win(PlayerId::new(0)),
+            ],
+        }]);
+        let attack = PlayerAction::generate(&Pre::Target(UnitPos::top(v!(1, 0))), &mut board)
+            .into_iter()
+            .find(|act| {
+                matches!(
+                    act,
+                    PlayerAction::Unit {
+                        action: UnitAction::Attack(_),
+                        ..
+                    }
+                )
+            })
+            .unwrap();
+        let (_, undoable) = board.apply_fogged(attack);
+        assert!(!undoable);
+        assert_eq!(board.scenario.fired, vec![0]);
+        assert_eq!(board.scenario.messages, vec!["Victory".to_string()]);
+        assert_eq!(
+            board.get_outcome(),
+            Some(Outcome {
+                winners: vec![PlayerId::new(0)],
+                victory: Victory::Scenario,
+            })
+        );
+        assert!(!board.events.is_collecting());
+    }
+
+    #[test]
+    fn day_spawns_and_grants_once() {
+        let mut board = test_board(vec![Trigger {
+            condition: TriggerCondition::DayReached(1),
+            actions: vec![
+                TriggerAction::Spawn {
+                    player: PlayerId::new(0),
+                    unit: "Militia".to_string(),
+                    at: v!(0, 0),
+                },
+                TriggerAction::GrantResources {
+                    player: PlayerId::new(0),
+                    resources: Resources::new(100, 0),
+                },
+                TriggerAction::Lose(PlayerId::new(1)),
+            ],
+        }]);
+        PlayerAction::PassTurn.apply(&mut board);
+        assert!(board.scenario.fired.is_empty());
+        PlayerAction::PassTurn.apply(&mut board);
+        assert_eq!(board.scenario.fired, vec![0]);
+        assert!(board.get_unit(&UnitPos::top(v!(0, 0))).is_some());
+        let food = board.get_player(&PlayerId::new(0)).resources.food;
+        PlayerAction::PassTurn.apply(&mut board);
+        PlayerAction::PassTurn.apply(&mut board);
+        assert_eq!(board.get_player(&PlayerId::new(0)).resources.food, food);
+        assert_eq!(
+            board.get_outcome().map(|outcome| outcome.victory),
+            Some(Victory::Scenario)
+        );
+    }
+}
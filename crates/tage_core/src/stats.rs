@@ -0,0 +1,273 @@
+use crate::prelude::*;
+
+/// What a player did in a day
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    bincode::Encode,
+    bincode::Decode,
+)]
+pub struct DayStats {
+    pub produced: Resources,
+    pub spent: Resources,
+    pub trained: i32,
+
+    /// Units killed or converted
+    pub lost: i32,
+    pub kills: i32,
+    pub techs: i32,
+
+    /// Value of the army at the end of the day, see `army_values`
+    pub army_value: i32,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    bincode::Encode,
+    bincode::Decode,
+)]
+pub struct PlayerStats {
+    pub id: PlayerId,
+
+    /// Indexed by day
+    pub days: Vec<DayStats>,
+}
+
+impl PlayerStats {
+    /// One value per day, for charts
+    pub fn series(&self, f: impl Fn(&DayStats) -> i32) -> Vec<i32> {
+        self.days.iter().map(f).collect()
+    }
+
+    pub fn total(&self, f: impl Fn(&DayStats) -> i32) -> i32 {
+        self.days.iter().map(f).sum()
+    }
+}
+
+/// Per-day statistics of every player, in `board.players` order.
+/// Built from the events of the applied actions.
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    bincode::Encode,
+    bincode::Decode,
+)]
+pub struct Stats {
+    pub players: Vec<PlayerStats>,
+}
+
+impl Stats {
+    pub fn new(board: &Board) -> Self {
+        let mut stats = Self {
+            players: board
+                .players
+                .iter()
+                .map(|player| PlayerStats {
+                    id: player.id.clone(),
+                    days: vec![],
+                })
+                .collect(),
+        };
+        stats.update_army_values(board);
+        stats
+    }
+
+    /// Plays back the whole replay
+    pub fn from_replay(bp: &Blueprints, replay: &Replay) -> Self {
+        let mut board = replay.start_board(bp);
+        board.events.collect();
+        let mut stats = Self::new(&board);
+        for action in replay.actions.iter() {
+            let before = board.clone();
+            action.apply(&mut board);
+            let events = board.events.take();
+            stats.record(&before, &board, &events);
+        }
+        stats
+    }
+
+    /// Adds an action that took the board from `before` to `after` emitting `events`
+    pub fn record(&mut self, before: &Board, after: &Board, events: &[GameEvent]) {
+        let day = before.day;
+        for event in events {
+            match event {
+                GameEvent::Production { player, resources } => {
+                    self.get_day_mut(player, after.day).produced += resources.clone();
+                }
+                GameEvent::Spent { player, resources } => {
+                    self.get_day_mut(player, day).spent += resources.clone();
+                }
+                GameEvent::Refunded { player, resources } => {
+                    self.get_day_mut(player, day).spent -= resources.clone();
+                }
+                GameEvent::UnitTrained { target } => {
+                    self.get_day_mut(&target.unit.owner, day).trained += 1;
+                }
+                GameEvent::UnitKilled { target, by } => {
+                    self.get_day_mut(&target.unit.owner, day).lost += 1;
+                    self.get_day_mut(by, day).kills += 1;
+                }
+                GameEvent::UnitConverted { target, .. }
+                | GameEvent::Ruins {
+                    target,
+                    reward: None,
+                } => {
+                    self.get_day_mut(&target.unit.owner, day).lost += 1;
+                }
+                GameEvent::ResearchFinished { player, .. } => {
+                    self.get_day_mut(player, after.day).techs += 1;
+                }
+                _ => {}
+            }
+        }
+
+        self.update_army_values(after);
+    }
+
+    fn update_army_values(&mut self, board: &Board) {
+        let values = army_values(board.bp(), board);
+        for (player, value) in board.players.iter().zip(values) {
+            self.get_day_mut(&player.id, board.day).army_value = value;
+        }
+    }
+
+    fn get_day_mut(&mut self, player_id: &PlayerId, day: u32) -> &mut DayStats {
+        let index = match self.players.iter().position(|p| &p.id == player_id) {
+            Some(index) => index,
+            None => {
+                self.players.push(PlayerStats {
+                    id: player_id.clone(),
+                    days: vec![],
+                });
+                self.players.len() - 1
+            }
+        };
+        let days = &mut self.players[index].days;
+        if days.len() <= day as usize {
+            // a new day starts with the army of the last one
+            let army_value = days.last().map_or(0, |d| d.army_value);
+            days.resize(
+                day as usize + 1,
+                DayStats {
+                    army_value,
+                    ..Default::default()
+                },
+            );
+        }
+        &mut days[day as usize]
+    }
+
+    pub fn get(&self, player_id: &PlayerId) -> Option<&PlayerStats> {
+        self.players.iter().find(|p| &p.id == player_id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        actions::player_action::Pre,
+        test::{place_unit, players_board, test_bp},
+        v,
+    };
+
+    use super::*;
+
+    fn test_board(bp: &Blueprints) -> Board {
+        let mut board = players_board(bp, "--- --- ---", 2, Resources::new(1000, 1000));
+        place_unit(&mut board, v!(0, 0), "Town Center", 0);
+        place_unit(&mut board, v!(1, 0), "Militia", 0);
+        place_unit(&mut board, v!(2, 0), "Militia", 1);
+        board
+            .grid
+            .get_at_mut(&v!(2, 0))
+            .unit
+            .as_mut()
+            .unwrap()
+            .health = 10;
+        board
+    }
+
+    #[test]
+    fn stats_from_replay() {
+        let bp = test_bp();
+        let mut board = test_board(&bp);
+        let mut replay = Replay::new(&MapSettings::default(), &board);
+        let attack = PlayerAction::generate(&Pre::Target(UnitPos::top(v!(1, 0))), &mut board)
+            .into_iter()
+            .find(|act| {
+                matches!(
+                    act,
+                    PlayerAction::Unit {
+                        action: UnitAction::Attack(_),
+                        ..
+                    }
+                )
+            })
+            .unwrap();
+        let train = PlayerAction::generate(&Pre::Target(UnitPos::bot(v!(0, 0))), &mut board)
+            .into_iter()
+            .find(|act| {
+                matches!(
+                    act,
+                    PlayerAction::Building {
+                        action: BuildingAction::Train(_),
+                        ..
+                    }
+                )
+            })
+            .unwrap();
+        for action in [
+            attack,
+            train,
+            PlayerAction::PassTurn,
+            PlayerAction::PassTurn,
+        ] {
+            action.apply(&mut board);
+            replay.record(&action);
+        }
+
+        let stats = Stats::from_replay(&bp, &replay);
+        let first = stats.get(&PlayerId::new(0)).unwrap();
+        let second = stats.get(&PlayerId::new(1)).unwrap();
+        assert_eq!(first.days.len(), 2);
+        assert_eq!(first.total(|d| d.kills), 1);
+        assert_eq!(first.total(|d| d.trained), 1);
+        assert!(first.days[0].spent.food + first.days[0].spent.gold > 0);
+        assert!(first.days[1].produced.food + first.days[1].produced.gold > 0);
+        assert_eq!(second.total(|d| d.lost), 1);
+        assert_eq!(second.series(|d| d.army_value), vec![0, 0]);
+        assert!(first.days[0].army_value > 0);
+    }
+
+    #[test]
+    fn tributes_are_not_spending() {
+        let bp = test_bp();
+        let mut board = test_board(&bp);
+        let mut replay = Replay::new(&MapSettings::default(), &board);
+        let tribute =
+            PlayerAction::Diplomacy(DiplomacyAction::Tribute(PlayerId::new(1), Resource::Gold));
+        assert!(tribute.is_valid(&board));
+        tribute.apply(&mut board);
+        replay.record(&tribute);
+
+        let stats = Stats::from_replay(&bp, &replay);
+        let first = stats.get(&PlayerId::new(0)).unwrap();
+        assert_eq!(first.days[0].spent, Resources::default());
+    }
+}
@@ -1,2 +1,47 @@
 mod integration;
 mod win_condition;
+
+use crate::prelude::*;
+
+/// Blueprints of the assets, the tests run in the directory of the crate
+pub(crate) fn test_bp() -> Blueprints {
+    Blueprints::from_assets_location(&("../../".to_string() + BLUEPRINTS_PATH)).unwrap()
+}
+
+/// Board of the map with the players numbered from 0, all starting with the same resources
+pub(crate) fn players_board(
+    bp: &Blueprints,
+    map: &str,
+    players: u32,
+    resources: Resources,
+) -> Board {
+    Board::new(
+        bp,
+        parse_map(bp, map).unwrap().grid,
+        (0..players)
+            .map(|id| Player {
+                id: PlayerId::new(id),
+                resources: resources.clone(),
+                ..Default::default()
+            })
+            .collect(),
+    )
+}
+
+/// Path in the temp directory, unique to the process so that parallel runs don't collide
+pub(crate) fn temp_path(name: &str) -> String {
+    let path = std::env::temp_dir().join(format!("{}_{}", std::process::id(), name));
+    path.to_str().unwrap().to_string()
+}
+
+/// Places a new unit of the owner, buildings go to the bottom of the tile
+pub(crate) fn place_unit(board: &mut Board, xy: IVec2, name: &str, owner: u32) {
+    let unit_id = board.bp.get_unit_from_name(name).unwrap();
+    let unit = Unit {
+        blueprint_id: unit_id.clone(),
+        owner: PlayerId::new(owner),
+        ..Default::default()
+    };
+    let unit_bp = board.bp.get_unit(&unit_id).clone();
+    board.grid.get_at_mut(&xy).set_unit(Some(unit), &unit_bp);
+}
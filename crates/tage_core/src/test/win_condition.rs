@@ -1,7 +1,5 @@
 #[cfg(test)]
 mod test_win_condition {
-    use std::{collections::HashMap, sync::Arc};
-
     use crate::prelude::*;
 
     fn assert_eq_players(list_a_opt: Option<Vec<PlayerId>>, list_b_opt: Option<Vec<PlayerId>>) {
@@ -20,11 +18,10 @@ mod test_win_condition {
     }
 
     fn test_board(ids: Vec<(PlayerId, Option<TeamId>)>) -> Board {
-        Board {
-            bp: Arc::new(Blueprints::default()),
-            grid: Grid::default(IVec2::splat(3)),
-            players: ids
-                .clone()
+        Board::new(
+            &Blueprints::default(),
+            Grid::default(IVec2::splat(3)),
+            ids.clone()
                 .into_iter()
                 .map(|(player, team)| Player {
                     id: player,
@@ -32,12 +29,7 @@ mod test_win_condition {
                     ..Default::default()
                 })
                 .collect(),
-            day: 0,
-            current_player_turn: ids.first().unwrap().0.clone(),
-            player_turn_order: ids.into_iter().map(|(id, _)| id).collect(),
-            fog: HashMap::new(),
-            fog_base: FogTile::Visible,
-        }
+        )
     }
 
     #[test]
@@ -147,3 +139,142 @@ mod test_win_condition {
         assert_eq_players(board.get_winners(), None);
     }
 }
+
+#[cfg(test)]
+mod test_victory_conditions {
+    use crate::{
+        prelude::*,
+        test::{place_unit, players_board, test_bp},
+        v,
+    };
+
+    fn test_board(conditions: VictoryConditions) -> Board {
+        let map = "--- --- --- --- ---\n--- --- --- --- ---\n--- --- --- --- ---";
+        let mut board = Board {
+            victory: VictoryState::new(conditions),
+            ..players_board(&test_bp(), map, 2, Resources::default())
+        };
+        place_unit(&mut board, v!(0, 0), "Militia", 0);
+        place_unit(&mut board, v!(4, 2), "Militia", 1);
+        board
+    }
+
+    fn pass_turns(board: &mut Board, turns: u32) {
+        for _ in 0..turns {
+            PlayerAction::PassTurn.apply(board);
+        }
+    }
+
+    #[test]
+    fn regicide() {
+        let mut board = test_board(VictoryConditions {
+            regicide: true,
+            ..Default::default()
+        });
+        place_unit(&mut board, v!(1, 0), "Saladin I", 0);
+        place_unit(&mut board, v!(3, 2), "Saladin I", 1);
+        board.crown_heroes();
+        assert_eq!(board.get_outcome(), None);
+        board.grid.get_at_mut(&v!(3, 2)).unit = None;
+        assert_eq!(
+            board.get_outcome(),
+            Some(Outcome {
+                winners: vec![PlayerId::new(0)],
+                victory: Victory::Regicide
+            })
+        );
+    }
+
+    #[test]
+    fn regicide_spares_players_without_heroes() {
+        let mut board = test_board(VictoryConditions {
+            regicide: true,
+            ..Default::default()
+        });
+        board.crown_heroes();
+        assert_eq!(board.get_outcome(), None);
+
+        // only the players that started with a hero can lose it
+        place_unit(&mut board, v!(1, 0), "Saladin I", 0);
+        board.crown_heroes();
+        board.grid.get_at_mut(&v!(1, 0)).unit = None;
+        assert_eq!(
+            board.get_outcome(),
+            Some(Outcome {
+                winners: vec![PlayerId::new(1)],
+                victory: Victory::Regicide
+            })
+        );
+    }
+
+    #[test]
+    fn relic_held_for_days() {
+        let mut board = test_board(VictoryConditions {
+            relic: Some(2),
+            ..Default::default()
+        });
+        board.grid.get_at_mut(&v!(1, 1)).building = Some(Unit {
+            blueprint_id: board.bp.get_unit_from_name("Church").unwrap(),
+            owner: PlayerId::new(0),
+            holding_collectable: Some(Collectable::Relic),
+            ..Default::default()
+        });
+        pass_turns(&mut board, 2);
+        assert_eq!(board.get_outcome(), None);
+        pass_turns(&mut board, 4);
+        assert_eq!(
+            board.get_outcome(),
+            Some(Outcome {
+                winners: vec![PlayerId::new(0)],
+                victory: Victory::Relic
+            })
+        );
+    }
+
+    #[test]
+    fn wonders_held_once_per_owner() {
+        let mut board = test_board(VictoryConditions {
+            wonder: Some(5),
+            ..Default::default()
+        });
+        let wonder = board.bp.get_unit_from_name("Wonder").unwrap();
+        for (xy, owner) in [(v!(0, 1), 0), (v!(2, 1), 1), (v!(4, 1), 0)] {
+            board.grid.get_at_mut(&xy).building = Some(Unit {
+                blueprint_id: wonder.clone(),
+                owner: PlayerId::new(owner),
+                ..Default::default()
+            });
+        }
+        board.update_victory();
+        let mut owners: Vec<PlayerId> = board
+            .victory
+            .wonders_held
+            .iter()
+            .map(|(owner, _)| owner.clone())
+            .collect();
+        owners.sort_by_key(PlayerId::get);
+        assert_eq!(owners, vec![PlayerId::new(0), PlayerId::new(1)]);
+    }
+
+    #[test]
+    fn score_at_day_limit() {
+        let mut board = test_board(VictoryConditions {
+            score: Some(1),
+            ..Default::default()
+        });
+        board.grid.get_at_mut(&v!(4, 1)).unit = Some(Unit {
+            blueprint_id: board.bp.get_unit_from_name("Militia").unwrap(),
+            owner: PlayerId::new(1),
+            ..Default::default()
+        });
+        assert_eq!(board.get_outcome(), None);
+        pass_turns(&mut board, 2);
+        assert_eq!(
+            board.get_outcome(),
+            Some(Outcome {
+                winners: vec![PlayerId::new(1)],
+                victory: Victory::Score
+            })
+        );
+    }
+}
@@ -51,7 +51,7 @@ pub struct Unit {
     #[serde(default, skip_serializing_if = "is_default")]
     pub conversion_attempt: Option<(PlayerId, i32)>,
 
-    /// Used to refresh Markets at the start of day with three new units
+    /// Used to refresh Markets at the start of day with three new units, every unit without rng
     #[serde(default, skip_serializing_if = "is_default")]
     pub train_list_override: Vec<UnitId>,
 
@@ -62,6 +62,10 @@ pub struct Unit {
     /// Multi-tile units store pointers to all other pieces within this vec
     #[serde(default, skip_serializing_if = "is_default")]
     pub linked_units: Vec<IVec2>,
+
+    /// Land units carried by a transport ship
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub cargo: Vec<Unit>,
 }
 
 impl Default for Unit {
@@ -79,6 +83,7 @@ impl Default for Unit {
             conversion_attempt: None,
             train_list_override: vec![],
             linked_units: vec![],
+            cargo: vec![],
         }
     }
 }
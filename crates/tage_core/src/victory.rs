@@ -0,0 +1,104 @@
+use crate::{is_default, prelude::*};
+
+/// Ways to win the game on top of conquest, set in the map settings.
+/// Conquest is always active: the only alliance left wins.
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    bincode::Encode,
+    bincode::Decode,
+)]
+#[serde(default)]
+pub struct VictoryConditions {
+    /// An alliance wins by holding every relic in its Churches for this many days
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub relic: Option<u32>,
+
+    /// A player wins if one of its Wonders stands for this many days
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub wonder: Option<u32>,
+
+    /// A player is defeated when its hero dies
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub regicide: bool,
+
+    /// When this day starts the alliance with the highest score wins
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub score: Option<u32>,
+}
+
+/// Progress of the timed victory conditions, updated at the end of every turn
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    bincode::Encode,
+    bincode::Decode,
+)]
+#[serde(default)]
+pub struct VictoryState {
+    pub conditions: VictoryConditions,
+
+    /// A player of the alliance holding every relic and the day since when it does
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub relics_held: Option<(PlayerId, u32)>,
+
+    /// Players owning a finished Wonder and the day since when they do
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub wonders_held: Vec<(PlayerId, u32)>,
+
+    /// Players that started with a hero, regicide defeats only them
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub crowned: Vec<PlayerId>,
+}
+
+impl VictoryState {
+    pub fn new(conditions: VictoryConditions) -> Self {
+        Self {
+            conditions,
+            ..Default::default()
+        }
+    }
+}
+
+/// The condition that ended the game
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Victory {
+    Conquest,
+    Relic,
+    Wonder,
+    Regicide,
+    Score,
+
+    /// Decided by the triggers of the scenario
+    Scenario,
+}
+
+impl Victory {
+    pub fn view(&self) -> String {
+        match self {
+            Victory::Conquest => "Conquest".to_string(),
+            Victory::Relic => "Relic".to_string(),
+            Victory::Wonder => "Wonder".to_string(),
+            Victory::Regicide => "Regicide".to_string(),
+            Victory::Score => "Score".to_string(),
+            Victory::Scenario => "Scenario".to_string(),
+        }
+    }
+}
+
+/// How the game ended, returned by `Board::get_outcome`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    pub winners: Vec<PlayerId>,
+    pub victory: Victory,
+}
@@ -1,14 +1,27 @@
 //! Entry point that parses the command line argument
 //! and provides a shared way for interfaces to handle the start flow.
 
-use std::fs;
+use std::{env, fs, path::PathBuf, process};
 
-use clap::Parser;
-use tage_core::game::MapSettings;
+use clap::{Parser, Subcommand};
+use tage_core::{
+    blueprints::Blueprints, game::MapSettings, get_assets_dir, get_blueprints_dir, mods::ModSet,
+};
+
+mod map_tool;
+use map_tool::*;
+
+mod randomize_tool;
+use randomize_tool::*;
 
 pub enum StartFlow {
     Menu,
-    LocalNewMap { settings: MapSettings },
+    LocalNewMap {
+        settings: MapSettings,
+    },
+
+    /// A command ran from the command line, there is no game to start
+    Done,
 }
 
 impl StartFlow {
@@ -23,11 +36,51 @@ struct FlowArgs {
     /// Config path
     #[arg(short, long)]
     config: Option<String>,
+
+    #[command(subcommand)]
+    command: Option<FlowCommand>,
+}
+
+#[derive(Subcommand, Debug)]
+enum FlowCommand {
+    /// Tools to write and edit maps
+    #[command(subcommand)]
+    Map(MapCommand),
+
+    /// Loads the blueprints and the mods and lists the mistakes in them
+    CheckAssets,
+
+    /// Writes a copy of the blueprints with stats, costs and bonuses moved at random
+    Randomize(RandomizeArgs),
 }
 
 impl From<FlowArgs> for StartFlow {
     fn from(value: FlowArgs) -> Self {
-        if let Some(config_path) = value.config {
+        if let Some(FlowCommand::Map(command)) = value.command {
+            let (bp, _) = load_blueprints();
+            if let Err(err) = command.run(&bp) {
+                eprintln!("{}", err);
+                process::exit(1);
+            }
+            StartFlow::Done
+        } else if let Some(FlowCommand::CheckAssets) = value.command {
+            let (_, mods) = load_blueprints();
+            for name in mods.names() {
+                println!("Mod {}", name);
+            }
+            for conflict in mods.conflicts.iter() {
+                println!("Conflict: {}", conflict);
+            }
+            println!("The blueprints are valid");
+            StartFlow::Done
+        } else if let Some(FlowCommand::Randomize(args)) = value.command {
+            let (bp, _) = load_blueprints();
+            if let Err(err) = args.run(&bp) {
+                eprintln!("{}", err);
+                process::exit(1);
+            }
+            StartFlow::Done
+        } else if let Some(config_path) = value.config {
             let config_str = fs::read_to_string(&config_path).unwrap();
             StartFlow::LocalNewMap {
                 settings: MapSettings::from_string(&config_str).unwrap(),
@@ -37,3 +90,34 @@ impl From<FlowArgs> for StartFlow {
         }
     }
 }
+
+/// Loads the blueprints with the mods laid over them.
+/// Exits listing the mistakes if they don't load
+pub fn load_blueprints() -> (Blueprints, ModSet) {
+    let mods_dir = get_mods_dir().to_string_lossy().to_string();
+    ModSet::load(&mods_dir)
+        .and_then(|mut mods| {
+            mods.fingerprint_files(&get_blueprints_dir(), &get_assets_dir())?;
+            Ok((Blueprints::from_assets_with_mods(&mods)?, mods))
+        })
+        .unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            process::exit(1);
+        })
+}
+
+/// Directory of the user data, created if missing
+pub fn get_data_dir() -> Option<PathBuf> {
+    let mut path = dirs::data_dir()?;
+    path.push("tage/");
+    let _ = std::fs::create_dir_all(path.clone());
+    Some(path)
+}
+
+/// Mods directory can be specified at runtime with an env variable. Defaults to mods in the user data
+pub fn get_mods_dir() -> PathBuf {
+    match env::var("TAGE_MODS") {
+        Ok(path) => PathBuf::from(path),
+        Err(_) => get_data_dir().unwrap_or_default().join("mods"),
+    }
+}
@@ -0,0 +1,607 @@
+//! Map editing and checking commands, run as `tage map <command> <path>`.
+//! Maps are read and written with the parser of the game, so they are checked
+//! against the terrain and unit blueprints.
+
+use std::{collections::HashMap, f32::consts::TAU, fmt::Display, fs, io, path::Path, str::FromStr};
+
+use clap::{Args, Subcommand};
+use tage_core::{prelude::*, v};
+
+#[derive(Subcommand, Debug)]
+pub enum MapCommand {
+    /// Expands a map written with one character per tile
+    ///
+    /// `+` is food, `m` and `M` are gold on hills and mountains, `=` is a road,
+    /// digits are spawn points, any other character is the first of a terrain glyph
+    Expand {
+        #[command(flatten)]
+        file: MapFileArgs,
+    },
+
+    /// Compacts a map to one character per tile, the lines after the grid are lost
+    Compact {
+        #[command(flatten)]
+        file: MapFileArgs,
+    },
+
+    /// Repeats the map around its top left corner and renumbers the spawn points
+    ///
+    /// `C<n>` rotates the map `n` times, `D<n>` also mirrors it on `n` axes
+    Symmetry {
+        #[command(flatten)]
+        file: MapFileArgs,
+
+        symmetry: MapSymmetry,
+    },
+
+    /// Changes the size of the map keeping its top left corner
+    Resize {
+        #[command(flatten)]
+        file: MapFileArgs,
+
+        width: i32,
+        height: i32,
+
+        /// Tile placed where the map grows
+        #[arg(long, default_value = "---")]
+        fill: String,
+    },
+
+    /// Keeps only the area of `width` by `height` tiles from `x`, `y`
+    Crop {
+        #[command(flatten)]
+        file: MapFileArgs,
+
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+    },
+
+    /// Numbers the spawn points from 0 without gaps, keeping their order
+    Renumber {
+        #[command(flatten)]
+        file: MapFileArgs,
+    },
+
+    /// Reports the distances between spawn points, the resources and collectables near them
+    /// and the spawn points that are walled off
+    ///
+    /// Differences between spawn points above the threshold are flagged
+    Report {
+        #[command(flatten)]
+        file: MapFileArgs,
+
+        /// Turns of walking in which the food and gold are counted
+        #[arg(long, default_value_t = MapReportSettings::default().turns)]
+        turns: i32,
+
+        /// Percent of difference between spawn points that is flagged
+        #[arg(long, default_value_t = MapReportSettings::default().threshold)]
+        threshold: i32,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct MapFileArgs {
+    /// Map to read, `.ron` maps keep their metadata
+    path: String,
+
+    /// Where to write the map, printed if not set
+    #[arg(short, long)]
+    output: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum MapToolError {
+    File(io::Error),
+    Map(ParseMapError),
+    UnknownChar(char, IVec2),
+
+    /// More spawn points than a map can number
+    TooManySpawns(usize),
+
+    /// Two images of the symmetry put a spawn point on this tile
+    SpawnOverlap(IVec2),
+}
+
+impl Display for MapToolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::File(err) => write!(f, "{}", err),
+            Self::Map(err) => write!(f, "{:?}", err),
+            Self::UnknownChar(c, xy) => write!(f, "Unknown char {:?} at {}", c, xy),
+            Self::TooManySpawns(count) => write!(
+                f,
+                "{} spawn points, a map has at most {}",
+                count, MAX_SPAWN_POINTS
+            ),
+            Self::SpawnOverlap(xy) => write!(f, "Two spawn points at {}", xy),
+        }
+    }
+}
+
+impl From<io::Error> for MapToolError {
+    fn from(value: io::Error) -> Self {
+        Self::File(value)
+    }
+}
+
+impl From<ParseMapError> for MapToolError {
+    fn from(value: ParseMapError) -> Self {
+        Self::Map(value)
+    }
+}
+
+impl MapCommand {
+    pub fn run(self, bp: &Blueprints) -> Result<(), MapToolError> {
+        match self {
+            MapCommand::Expand { file } => {
+                let grid = expand(bp, &fs::read_to_string(&file.path)?)?;
+                file.write(&write_map(bp, &grid))
+            }
+            MapCommand::Compact { file } => {
+                let map = parse_map(bp, &MapFile::load(&file.path)?.grid)?;
+                file.write(&compact(bp, &map.grid))
+            }
+            MapCommand::Symmetry { file, symmetry } => {
+                file.edit(bp, |map| renumber(symmetric(bp, map, &symmetry)?))
+            }
+            MapCommand::Resize {
+                file,
+                width,
+                height,
+                fill,
+            } => {
+                let fill = parse_map(bp, &fill)?.grid.get_at(&IVec2::ZERO).clone();
+                file.edit(bp, |map| {
+                    Ok(reframe(map, IVec2::ZERO, v!(width, height), &fill))
+                })
+            }
+            MapCommand::Crop {
+                file,
+                x,
+                y,
+                width,
+                height,
+            } => file.edit(bp, |map| {
+                Ok(reframe(
+                    map,
+                    v!(x, y),
+                    v!(width, height),
+                    &BoardTile::default(),
+                ))
+            }),
+            MapCommand::Renumber { file } => file.edit(bp, renumber),
+            MapCommand::Report {
+                file,
+                turns,
+                threshold,
+            } => {
+                let settings = MapReportSettings { turns, threshold };
+                let report = MapReport::from_map_file(bp, &MapFile::load(&file.path)?, &settings)?;
+                file.write(&report.to_string())
+            }
+        }
+    }
+}
+
+impl MapFileArgs {
+    /// Writes the edited map in the format it was read, keeping the metadata of `.ron` maps
+    fn edit(
+        &self,
+        bp: &Blueprints,
+        f: impl FnOnce(ParseMapResult) -> Result<ParseMapResult, MapToolError>,
+    ) -> Result<(), MapToolError> {
+        let file = MapFile::load(&self.path)?;
+        let grid = write_parsed_map(bp, &f(parse_map(bp, &file.grid)?)?);
+        if Path::new(&self.path)
+            .extension()
+            .is_some_and(|ext| ext == "ron")
+        {
+            self.write(&MapFile { grid, ..file }.to_string_pretty())
+        } else {
+            self.write(grid.trim_start())
+        }
+    }
+
+    fn write(&self, map: &str) -> Result<(), MapToolError> {
+        match &self.output {
+            Some(output) => fs::write(output, map)?,
+            None => print!("{}", map),
+        }
+        Ok(())
+    }
+}
+
+fn expand(bp: &Blueprints, compact: &str) -> Result<Grid<BoardTile>, MapToolError> {
+    let lines: Vec<&str> = compact.lines().filter(|l| !l.trim().is_empty()).collect();
+    if lines.is_empty() {
+        return Err(ParseMapError::EmptyString.into());
+    }
+    let size = v!(lines[0].trim().chars().count() as i32, lines.len() as i32);
+    let terrain = |name| bp.get_terrain_from_name(name).unwrap();
+    let mut grid = Grid::default(size);
+    for (y, line) in lines.iter().enumerate() {
+        for (x, c) in line.trim().chars().enumerate() {
+            let xy = v!(x as i32, y as i32);
+            let mut tile = BoardTile::default();
+            match c {
+                '+' => {
+                    tile.terrain.blueprint_id = terrain("Plains");
+                    tile.terrain.resource = Some(Resource::Food);
+                }
+                'm' | 'M' => {
+                    let name = if c == 'm' { "Hills" } else { "Mountain" };
+                    tile.terrain.blueprint_id = terrain(name);
+                    tile.terrain.resource = Some(Resource::Gold);
+                }
+                '=' => {
+                    tile.terrain.blueprint_id = terrain("Plains");
+                    tile.terrain.has_road = true;
+                }
+                c if c.is_ascii_hexdigit() => {
+                    tile.terrain.blueprint_id = terrain("Plains");
+                    tile.spawn_point = c.to_digit(16).map(PlayerId::new);
+                }
+                c => {
+                    let Some((id, _)) = bp
+                        .terrain
+                        .iter()
+                        .find(|(_, terrain)| terrain.header.glyph.starts_with(c))
+                    else {
+                        return Err(MapToolError::UnknownChar(c, xy));
+                    };
+                    tile.terrain.blueprint_id = id.clone();
+                }
+            }
+            if grid.contains(&xy) {
+                grid.set_at(&xy, tile);
+            }
+        }
+    }
+    Ok(grid)
+}
+
+fn compact(bp: &Blueprints, grid: &Grid<BoardTile>) -> String {
+    let mut s = String::new();
+    for (xy, tile) in grid.iter() {
+        let glyph = &bp.get_terrain(&tile.terrain.blueprint_id).header.glyph;
+        s.push(match (&tile.spawn_point, &tile.terrain.resource) {
+            (Some(id), _) => char::from_digit(id.get(), 16).unwrap_or('-'),
+            (None, Some(Resource::Food)) => '+',
+            (None, Some(Resource::Gold)) if glyph.starts_with('/') => 'M',
+            (None, Some(Resource::Gold)) => 'm',
+            (None, None) if tile.terrain.has_road => '=',
+            (None, None) => glyph.chars().next().unwrap(),
+        });
+        if xy.x == grid.size.x - 1 {
+            s.push('\n');
+        }
+    }
+    s
+}
+
+/// Copies the area of `size` tiles from `offset` to a new map,
+/// the tiles outside of the old map are `fill`
+fn reframe(map: ParseMapResult, offset: IVec2, size: IVec2, fill: &BoardTile) -> ParseMapResult {
+    let size = size.max(IVec2::ONE);
+    let mut grid = Grid::fill(size, fill.clone());
+    for xy in iter_area(size) {
+        if map.grid.contains(&(xy + offset)) {
+            grid.set_at(&xy, map.grid.get_at(&(xy + offset)).clone());
+        }
+    }
+    let moved = |pos: &IVec2| grid.contains(&(*pos - offset)).then(|| *pos - offset);
+    let gaia_units = map
+        .gaia_units
+        .into_iter()
+        .filter_map(|(id, pos)| Some((id, moved(&pos)?)))
+        .collect();
+    let spawn_units = map
+        .spawn_units
+        .into_iter()
+        .filter_map(|(num, id, pos)| Some((num, id, moved(&pos)?)))
+        .collect();
+    with_grid(grid, gaia_units, spawn_units, map.starts)
+}
+
+/// Places the map at each image of the symmetry, the spawn points of
+/// each image get new numbers. The tiles left out are water
+fn symmetric(
+    bp: &Blueprints,
+    map: ParseMapResult,
+    symmetry: &MapSymmetry,
+) -> Result<ParseMapResult, MapToolError> {
+    let images = |xy: &IVec2| -> Vec<IVec2> {
+        symmetry
+            .get_symmetric((xy.x, xy.y))
+            .into_iter()
+            .map(|(x, y)| v!(x, y))
+            .collect()
+    };
+    let count = images(&IVec2::ZERO).len() as u32;
+    let spawn_count = map.spawn_points.len() * count as usize;
+    if spawn_count > MAX_SPAWN_POINTS as usize {
+        return Err(MapToolError::TooManySpawns(spawn_count));
+    }
+    let all: Vec<IVec2> = iter_area(map.grid.size)
+        .flat_map(|xy| images(&xy))
+        .collect();
+    let min = all.iter().fold(IVec2::ZERO, |acc, xy| acc.min(*xy));
+    let max = all.iter().fold(IVec2::ZERO, |acc, xy| acc.max(*xy));
+
+    let water = BoardTile {
+        terrain: TerrainTile {
+            blueprint_id: bp.get_terrain_from_name("Water").unwrap(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut grid = Grid::fill(max - min + IVec2::ONE, water);
+    let num = |id: u32, i: usize| id * count + i as u32;
+    for (xy, tile) in map.grid.iter() {
+        for (i, image) in images(&xy).into_iter().enumerate() {
+            let mut tile = tile.clone();
+            tile.spawn_point = tile.spawn_point.map(|id| PlayerId::new(num(id.get(), i)));
+            if grid.get_at(&(image - min)).spawn_point.is_some() {
+                return Err(MapToolError::SpawnOverlap(image - min));
+            }
+            grid.set_at(&(image - min), tile);
+        }
+    }
+    let gaia_units = map
+        .gaia_units
+        .into_iter()
+        .flat_map(|(id, pos)| {
+            images(&pos)
+                .into_iter()
+                .map(move |image| (id.clone(), image - min))
+        })
+        .collect();
+    let spawn_units = map
+        .spawn_units
+        .into_iter()
+        .flat_map(|(n, id, pos)| {
+            images(&pos)
+                .into_iter()
+                .enumerate()
+                .map(move |(i, image)| (num(n, i), id.clone(), image - min))
+        })
+        .collect();
+    let starts = map
+        .starts
+        .into_iter()
+        .flat_map(|(n, start)| (0..count as usize).map(move |i| (num(n, i), start.clone())))
+        .collect();
+    Ok(with_grid(grid, gaia_units, spawn_units, starts))
+}
+
+/// The units and starts of a number follow the first spawn point with that number,
+/// the ones of missing spawn points are removed
+fn renumber(map: ParseMapResult) -> Result<ParseMapResult, MapToolError> {
+    let mut grid = map.grid;
+    let mut spawns: Vec<(u32, IVec2)> = grid
+        .iter()
+        .filter_map(|(xy, tile)| tile.spawn_point.as_ref().map(|id| (id.get(), xy)))
+        .collect();
+    if spawns.len() > MAX_SPAWN_POINTS as usize {
+        return Err(MapToolError::TooManySpawns(spawns.len()));
+    }
+    spawns.sort_by_key(|(num, xy)| (*num, xy.y, xy.x));
+    let mut numbers = HashMap::new();
+    for (new, (old, xy)) in spawns.into_iter().enumerate() {
+        grid.get_at_mut(&xy).spawn_point = Some(PlayerId::new(new as u32));
+        numbers.entry(old).or_insert(new as u32);
+    }
+    let spawn_units = map
+        .spawn_units
+        .into_iter()
+        .filter_map(|(num, id, pos)| Some((*numbers.get(&num)?, id, pos)))
+        .collect();
+    let starts = map
+        .starts
+        .into_iter()
+        .filter_map(|(num, start)| Some((*numbers.get(&num)?, start)))
+        .collect();
+    Ok(with_grid(grid, map.gaia_units, spawn_units, starts))
+}
+
+/// Only the starts of spawn points still in the grid are kept
+fn with_grid(
+    grid: Grid<BoardTile>,
+    gaia_units: Vec<(UnitId, IVec2)>,
+    spawn_units: Vec<(u32, UnitId, IVec2)>,
+    starts: Vec<(u32, PlayerStart)>,
+) -> ParseMapResult {
+    let spawn_points: Vec<(u32, IVec2)> = grid
+        .iter()
+        .filter_map(|(xy, tile)| tile.spawn_point.as_ref().map(|id| (id.get(), xy)))
+        .collect();
+    let present = |num: &u32| spawn_points.iter().any(|(n, _)| n == num);
+    ParseMapResult {
+        spawn_units: spawn_units
+            .into_iter()
+            .filter(|(num, _, _)| present(num))
+            .collect(),
+        starts: starts.into_iter().filter(|(num, _)| present(num)).collect(),
+        grid,
+        spawn_points,
+        gaia_units,
+    }
+}
+
+/// https://en.wikipedia.org/wiki/Symmetry_group#Two_dimensions
+/// https://en.wikipedia.org/wiki/Point_groups_in_two_dimensions#More_general_groups
+///
+/// # Examples
+/// C1 is asymmetric
+/// C2 is point like symmetry: "Z"
+/// C3 like the flag of the ile of man
+/// C4 like a pinwheel
+///
+/// D1 is reflection on a single axis
+/// D2 is reflection on two orthogonal axis
+/// D3 is reflection on the axis formed by a equilateral triangle
+/// D4 square
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapSymmetry {
+    C(u8),
+    D(u8),
+}
+
+impl MapSymmetry {
+    pub fn get_symmetric(&self, (x, y): (i32, i32)) -> Vec<(i32, i32)> {
+        match *self {
+            MapSymmetry::C(0) | MapSymmetry::C(1) => vec![(x, y)],
+            MapSymmetry::C(2) => vec![(x, y), (-x, -y)],
+            MapSymmetry::C(n) => {
+                let (s, t) = (x as f32, y as f32);
+                let theta = TAU / n as f32;
+                (0..n)
+                    .map(|i| {
+                        let cos_theta = f32::cos(theta * i as f32);
+                        let sin_theta = f32::sin(theta * i as f32);
+                        (
+                            (s * cos_theta - t * sin_theta).round() as i32,
+                            (s * sin_theta + t * cos_theta).round() as i32,
+                        )
+                    })
+                    .collect()
+            }
+            MapSymmetry::D(0) | MapSymmetry::D(1) => vec![(x, y), (-x, y)],
+            MapSymmetry::D(2) => vec![(x, y), (-x, y), (x, -y), (-x, -y)],
+            MapSymmetry::D(n) => {
+                let (s, t) = (x as f32, y as f32);
+                let theta = TAU / n as f32;
+                let mut mirrors: Vec<(i32, i32)> = (0..n)
+                    .map(|i| {
+                        let cos_theta = f32::cos(theta * i as f32);
+                        let sin_theta = f32::sin(theta * i as f32);
+                        (
+                            (s * cos_theta + t * sin_theta).round() as i32,
+                            (s * sin_theta - t * cos_theta).round() as i32,
+                        )
+                    })
+                    .collect();
+                mirrors.extend(MapSymmetry::C(n).get_symmetric((x, y)));
+                mirrors
+            }
+        }
+    }
+}
+
+impl FromStr for MapSymmetry {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let axes = || {
+            s[1..]
+                .parse()
+                .map_err(|_| "Failed to read the number of axes".to_string())
+        };
+        match s.chars().next() {
+            Some('C') => Ok(MapSymmetry::C(axes()?)),
+            Some('D') => Ok(MapSymmetry::D(axes()?)),
+            _ => Err("Unsupported symmetry, use C<n> or D<n>".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn bp() -> Blueprints {
+        Blueprints::from_assets_location(&("../../".to_string() + BLUEPRINTS_PATH)).unwrap()
+    }
+
+    fn edit(
+        bp: &Blueprints,
+        map: &str,
+        f: impl FnOnce(ParseMapResult) -> Result<ParseMapResult, MapToolError>,
+    ) -> String {
+        write_parsed_map(bp, &f(parse_map(bp, map).unwrap()).unwrap())
+            .trim()
+            .to_string()
+    }
+
+    #[test]
+    fn expand_and_compact() {
+        let bp = bp();
+        let compact_map = ".#==\n=~M0\n";
+        let grid = expand(&bp, compact_map).unwrap();
+        assert_eq!(
+            write_map(&bp, &grid).trim(),
+            "... ### =-- =--\n=-- ~~~ $\\\\ -0-"
+        );
+        assert_eq!(compact(&bp, &grid), compact_map);
+        assert!(matches!(
+            expand(&bp, ".x"),
+            Err(MapToolError::UnknownChar('x', _))
+        ));
+    }
+
+    #[test]
+    fn symmetry_repeats_spawns_and_their_units() {
+        let bp = bp();
+        let map = "--- --- ###\n--- --- -0-\nstart 0 100 200 1\nspawn 0 1 1 Knights";
+        let mirrored = edit(&bp, map, |map| {
+            renumber(symmetric(&bp, map, &MapSymmetry::D(1))?)
+        });
+        assert_eq!(
+            mirrored,
+            "\
+### --- --- --- ###
+-1- --- --- --- -0-
+start 0 100 200 1
+start 1 100 200 1
+spawn 0 3 1 Knights
+spawn 1 1 1 Knights"
+        );
+    }
+
+    #[test]
+    fn crop_and_resize_move_the_units() {
+        let bp = bp();
+        let map = "--- --- ---\n--- -3- ---\ngaia 2 1 Wolf\nstart 3 10 10 0";
+        let cropped = edit(&bp, map, |map| {
+            Ok(reframe(map, v!(1, 1), v!(2, 1), &BoardTile::default()))
+        });
+        assert_eq!(cropped, "-3- ---\ngaia 1 0 Wolf\nstart 3 10 10 0");
+        let renumbered = edit(&bp, &cropped, renumber);
+        assert_eq!(renumbered, "-0- ---\ngaia 1 0 Wolf\nstart 0 10 10 0");
+
+        let fill = parse_map(&bp, "...")
+            .unwrap()
+            .grid
+            .get_at(&IVec2::ZERO)
+            .clone();
+        let resized = edit(&bp, map, |map| {
+            Ok(reframe(map, IVec2::ZERO, v!(1, 3), &fill))
+        });
+        assert_eq!(resized, "---\n---\n...");
+    }
+
+    #[test]
+    fn symmetry_refuses_spawns_it_cannot_write() {
+        let bp = bp();
+        let crowded = parse_map(&bp, "-0- -1- -2- -3- -4- -5- -6- -7- -8-").unwrap();
+        assert!(matches!(
+            symmetric(&bp, crowded, &MapSymmetry::D(1)),
+            Err(MapToolError::TooManySpawns(18))
+        ));
+        let seventeen: Vec<String> = (0..17).map(|_| "-0-".to_string()).collect();
+        assert!(matches!(
+            renumber(parse_map(&bp, &seventeen.join(" ")).unwrap()),
+            Err(MapToolError::TooManySpawns(17))
+        ));
+
+        // the spawn point is on the mirror axis, both images land on it
+        let on_axis = parse_map(&bp, "-0- --- ---\n--- --- ---").unwrap();
+        assert!(matches!(
+            symmetric(&bp, on_axis, &MapSymmetry::D(1)),
+            Err(MapToolError::SpawnOverlap(_))
+        ));
+    }
+}
@@ -0,0 +1,55 @@
+//! Writes a randomized copy of the blueprints, run as `tage randomize --seed <seed> <output>`.
+//! Every player using the same seed and bounds gets the same blueprints.
+
+use std::io;
+
+use clap::Args;
+use tage_core::prelude::*;
+
+#[derive(Args, Debug)]
+pub struct RandomizeArgs {
+    /// Directory to write the blueprints to, play them with `TAGE_BLUEPRINTS=<output> tage`
+    output: String,
+
+    #[arg(long, default_value_t = RandomizerSettings::default().seed)]
+    seed: u64,
+
+    /// Most percent a unit stat moves up or down
+    #[arg(long, default_value_t = RandomizerSettings::default().stats)]
+    stats: i32,
+
+    /// Most percent a unit or technology cost moves up or down
+    #[arg(long, default_value_t = RandomizerSettings::default().costs)]
+    costs: i32,
+
+    /// Most percent a bonus value moves up or down
+    #[arg(long, default_value_t = RandomizerSettings::default().bonuses)]
+    bonuses: i32,
+
+    /// Leaves every civilization its own bonuses
+    #[arg(long)]
+    keep_bonuses: bool,
+
+    /// Swaps the heroes between the civilizations
+    #[arg(long)]
+    shuffle_heroes: bool,
+}
+
+impl RandomizeArgs {
+    pub fn run(self, bp: &Blueprints) -> io::Result<()> {
+        let settings = RandomizerSettings {
+            seed: self.seed,
+            stats: self.stats,
+            costs: self.costs,
+            bonuses: self.bonuses,
+            shuffle_bonuses: !self.keep_bonuses,
+            shuffle_heroes: self.shuffle_heroes,
+        };
+        bp.randomized(&settings).to_assets_location(&self.output)?;
+        println!(
+            "Blueprints of seed {} written, play them with TAGE_BLUEPRINTS={} tage",
+            self.seed, self.output
+        );
+        Ok(())
+    }
+}
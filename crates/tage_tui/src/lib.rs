@@ -18,7 +18,7 @@ use std::{
     time::{Instant, SystemTime, UNIX_EPOCH},
 };
 use tage_core::{get_assets_dir, prelude::*};
-use tage_flow::StartFlow;
+use tage_flow::{get_data_dir, StartFlow};
 use tracing::{info, Level};
 use tracing_subscriber::{self};
 
@@ -55,6 +55,12 @@ use menu_lobby::*;
 mod menu_load;
 use menu_load::*;
 
+mod menu_campaign;
+use menu_campaign::*;
+
+mod menu_replay;
+use menu_replay::*;
+
 mod menu_profile;
 use menu_profile::*;
 
@@ -65,7 +71,7 @@ pub fn game_main(flow: StartFlow) -> io::Result<()> {
     setup_tracing();
     initialize_panic_handler();
 
-    let bp = Blueprints::from_assets().unwrap();
+    let (bp, mods) = tage_flow::load_blueprints();
 
     let settings = Settings::from_disk().unwrap_or_default();
 
@@ -92,9 +98,11 @@ pub fn game_main(flow: StartFlow) -> io::Result<()> {
             ),
             InterfaceState::default(),
         ),
+        StartFlow::Done => return Ok(()),
     };
 
     interface_state.settings = settings;
+    interface_state.mods = mods;
 
     if let Ok(grid) = load_map(
         &bp,
@@ -109,6 +117,12 @@ pub fn game_main(flow: StartFlow) -> io::Result<()> {
             player_turn_order: vec![],
             fog: HashMap::new(),
             fog_base: FogTile::Visible,
+            rng: BoardRng::default(),
+            victory: VictoryState::default(),
+            diplomacy: Diplomacy::default(),
+            rules: Rules::default(),
+            scenario: ScenarioState::default(),
+            events: GameEvents::default(),
         });
     }
 
@@ -154,13 +168,6 @@ pub fn game_main(flow: StartFlow) -> io::Result<()> {
     restore_terminal()
 }
 
-fn get_data_dir() -> Option<PathBuf> {
-    let mut path = dirs::data_dir()?;
-    path.push("tage/");
-    let _ = std::fs::create_dir_all(path.clone());
-    Some(path)
-}
-
 fn get_data_dir_sub(sub: &str) -> Option<PathBuf> {
     let mut path = get_data_dir()?;
     path.push(format!("{}/", sub));
@@ -194,38 +201,8 @@ fn setup_tracing() {
 }
 
 fn setup_gamestate(settings: MapSettings, bp: &Blueprints) -> Result<GameState, ParseMapError> {
-    let mut board = Board {
-        bp: Arc::new(bp.clone()),
-        grid: load_map(&bp, &settings)?,
-        players: settings
-            .players
-            .iter()
-            .map(|player| Player {
-                id: player.id.clone(),
-                color: player.color,
-                level: player.level,
-                resources: Resources::new(1500, 1500),
-                team: player.team.clone(),
-                name: player.name.clone(),
-                symbol: player.symbol.clone(),
-                civilization: player.civ(bp),
-                controller: player.controller.clone(),
-                ..Default::default()
-            })
-            .collect(),
-        day: 0,
-        current_player_turn: PlayerId::new(0),
-        player_turn_order: settings
-            .players
-            .iter()
-            .map(|player| player.id.clone())
-            .collect(),
-        fog: HashMap::new(),
-        fog_base: settings.fog_base,
-    };
-
-    board.init_fog();
-    board.refresh_fog();
+    let board = Board::from_settings(bp, &settings)?;
+    let replay = Replay::new(&settings, &board);
 
     Ok(GameState {
         board,
@@ -238,6 +215,9 @@ fn setup_gamestate(settings: MapSettings, bp: &Blueprints) -> Result<GameState,
             selection_type: None,
         }),
         turn_timeline: vec![],
+        undo_barrier: 0,
+        turn_history: vec![],
+        replay,
     })
 }
 
@@ -351,6 +331,8 @@ fn input(
                 if let Some(lastest) = dates.iter().max() {
                     path.push(format!("quicksave_{}.ron", lastest));
                     if let Ok(board) = Board::load(bp, path.to_str().unwrap()) {
+                        game_state.replay = Replay::new(&game_state.replay.settings, &board);
+                        game_state.turn_history.clear();
                         game_state.board = board;
                     }
                     ui_state.reset()
@@ -363,6 +345,14 @@ fn input(
         ui_state.queue_paused = !ui_state.queue_paused;
     }
 
+    // scenario messages hold the input until they are dismissed
+    if ui_state.messages_seen < game_state.board.scenario.messages.len() {
+        if game_input.select || game_input.back {
+            ui_state.messages_seen += 1;
+        }
+        game_input = GameInput::default();
+    }
+
     if game_input.select || game_input.back {
         ui_state.battle_state.temp_board = None;
     }
@@ -370,7 +360,7 @@ fn input(
     let mut send_board = false;
     let mut send_to_lobby = false;
 
-    if ui_state.winning_players.is_some() {
+    if ui_state.outcome.is_some() {
         let show_win = game_state.board.fog_base == FogTile::Visible;
 
         if show_win {
@@ -378,12 +368,15 @@ fn input(
                 if net.is_server() {
                     net.server_send(&ServerMessages::ToLobby);
                     send_to_lobby = true;
+                } else if let Some(mission) = ui_state.mission.take() {
+                    ui_state.main_menu =
+                        Some(MenuState::Campaign(MenuCampaign::returning(&mission)));
                 } else if !net.is_client() {
                     ui_state.main_menu = Some(MenuState::Home(MenuHome::new()));
                 }
             }
             if ui_state.close_on_end {
-                info!(target: "outcome", "winners {:?}", ui_state.winning_players);
+                info!(target: "outcome", "winners {:?}", ui_state.outcome);
                 *is_running = false;
             }
         }
@@ -398,6 +391,7 @@ fn input(
                         game_state.board = board;
                     }
                     ServerMessages::ToLobby => send_to_lobby = true,
+                    ServerMessages::Stats { stats } => ui_state.stats = Some(stats),
                     _ => {}
                 }
             }
@@ -406,18 +400,11 @@ fn input(
             while let Some((_client_id, message)) = server.queue.pop() {
                 match message {
                     ClientMessages::PlayerAction { action } => {
-                        game_state.turn_timeline.push(action.clone());
-
-                        let action = game_state.board.fog_bonk(action.clone());
-                        action.apply(&mut game_state.board);
-                        game_state.board.refresh_fog();
-
+                        game_state.apply_action(*action);
                         send_board = true;
                     }
                     ClientMessages::Undo => {
-                        if let Some(last_action) = game_state.turn_timeline.pop() {
-                            last_action.undo(&mut game_state.board)
-                        }
+                        game_state.undo();
                         send_board = true;
                     }
                     _ => {}
@@ -465,7 +452,9 @@ fn input(
                     let undo = game_state.turn_timeline.len() < num;
                     if net.is_client() {
                         if let Some(action) = action.clone() {
-                            net.client_send(&ClientMessages::PlayerAction { action })
+                            net.client_send(&ClientMessages::PlayerAction {
+                                action: Box::new(action),
+                            })
                         }
                         if undo && action.is_none() {
                             net.client_send(&ClientMessages::Undo)
@@ -497,7 +486,7 @@ fn input(
                 return Ok(());
             }
 
-            if !ui_state.winning_players.is_some() {
+            if ui_state.outcome.is_none() {
                 if ui_state.queued_actions.is_empty() {
                     ui_state.queued_actions =
                         get_machine_turn(&game_state.blueprints, &mut game_state.board, &machine);
@@ -510,6 +499,7 @@ fn input(
                                 let action = game_state.board.fog_bonk(action.clone());
                                 action.apply(&mut game_state.board);
                                 game_state.board.refresh_fog();
+                                game_state.replay.record(&action);
                             }
                             ui_state.queued_actions.clear();
                             if net.is_server() {
@@ -542,6 +532,7 @@ fn input(
                         let action = game_state.board.fog_bonk(action.clone());
                         action.apply(&mut game_state.board);
                         game_state.board.refresh_fog();
+                        game_state.replay.record(&action);
 
                         if let Some(cursor) = match &action {
                             PlayerAction::Unit { destination, .. } => Some(destination),
@@ -566,7 +557,7 @@ fn input(
     }
 
     if send_to_lobby {
-        ui_state.main_menu = Some(MenuState::Lobby(MenuLobby::new(ui_state, net)));
+        ui_state.main_menu = Some(MenuState::Lobby(Box::new(MenuLobby::new(ui_state, net))));
     }
 
     if send_board && net.is_server() {
@@ -588,14 +579,39 @@ fn input(
                 net.server_send_at(
                     id.clone(),
                     &ServerMessages::Board {
-                        board: BoardView::from(&game_state.blueprints, &stripped),
+                        board: Box::new(BoardView::from(&game_state.blueprints, &stripped)),
                     },
                 )
             }
         }
     }
 
-    ui_state.winning_players = game_state.board.get_winners();
+    let outcome = game_state.board.get_outcome();
+    if ui_state.outcome.is_none() && outcome.is_some() && !net.is_client() {
+        if let Some(mut path) = get_data_dir_sub("replays") {
+            path.push(format!(
+                "replay_{}.ron",
+                std::time::SystemTime::now()
+                    .duration_since(std::time::SystemTime::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs()
+            ));
+            let _ = game_state.replay.save(path.as_path().to_str().unwrap());
+        }
+        let stats = Stats::from_replay(&game_state.blueprints, &game_state.replay);
+        if net.is_server() {
+            net.server_send(&ServerMessages::Stats {
+                stats: stats.clone(),
+            });
+        }
+        ui_state.stats = Some(stats);
+        if let (Some(mission), Some(outcome)) = (&ui_state.mission, &outcome) {
+            if mission.is_winner(outcome) {
+                mission.win(&game_state.board);
+            }
+        }
+    }
+    ui_state.outcome = outcome;
 
     Ok(())
 }
@@ -980,15 +996,19 @@ fn render(
                                 .areas(inner);
                         frame.render_widget(Paragraph::new("End Turn"), title);
                         let player = game_state.board.get_current_player();
-                        if let Some(research) = &player.research_queued {
+                        if let Some((research, _)) = player.research_queue.first() {
                             let text = match research {
                                 QueuedResearch::Tech(id) => {
                                     game_state.blueprints.get_tech(id).name.clone()
                                 }
                                 QueuedResearch::AgeUp => view_level(player.level + 1),
                             };
+                            let queued = match player.research_queue.len() - 1 {
+                                0 => String::new(),
+                                n => format!(" ({} more queued)", n),
+                            };
                             frame.render_widget(
-                                Paragraph::new(format!("Researching {}", text)),
+                                Paragraph::new(format!("Researching {}{}", text, queued)),
                                 tech,
                             );
                         } else {
@@ -1003,6 +1023,43 @@ fn render(
                             ok,
                         )
                     }
+                    Some(action @ PlayerAction::ResearchQueue(_)) => {
+                        let inner = popup(frame, rest, Size::new(40, 8));
+                        let [title, _, descr, ok] =
+                            Layout::vertical([Length(1), Length(1), Fill(1), Length(1)])
+                                .areas(inner);
+                        frame.render_widget(Paragraph::new("Research Queue"), title);
+                        frame.render_widget(
+                            Paragraph::new(action.view(&game_state.blueprints))
+                                .wrap(Wrap { trim: false }),
+                            descr,
+                        );
+                        frame.render_widget(
+                            Button {
+                                string: "Confirm",
+                                pressed: true,
+                            },
+                            ok,
+                        )
+                    }
+                    Some(PlayerAction::Diplomacy(action)) => {
+                        let inner = popup(frame, rest, Size::new(40, 8));
+                        let [title, _, descr, ok] =
+                            Layout::vertical([Length(1), Length(1), Fill(1), Length(1)])
+                                .areas(inner);
+                        frame.render_widget(Paragraph::new("Diplomacy"), title);
+                        frame.render_widget(
+                            Paragraph::new(action.view()).wrap(Wrap { trim: false }),
+                            descr,
+                        );
+                        frame.render_widget(
+                            Button {
+                                string: "Confirm",
+                                pressed: true,
+                            },
+                            ok,
+                        )
+                    }
                     Some(PlayerAction::Building {
                         action: BuildingAction::AgeUp,
                         ..
@@ -1108,12 +1165,22 @@ fn render(
         }
 
         if !net.is_client() || board.fog_base == FogTile::Visible {
-            if let Some(winners) = &ui_state.winning_players {
+            if let (Some(outcome), Some(stats)) = (&ui_state.outcome, &ui_state.stats) {
+                let widget = StatsWidget {
+                    board: &game_state.board,
+                    blueprints: &game_state.blueprints,
+                    stats,
+                    outcome,
+                };
+                let inner = popup(frame, rest, Size::new(80, widget.height() + 2));
+                frame.render_widget(widget, inner);
+            } else if let Some(Outcome { winners, victory }) = &ui_state.outcome {
                 let inner = popup(frame, rest, Size::new(40, 18));
 
                 let [header, body] = Layout::vertical([Length(1), Fill(1)]).areas(inner);
                 frame.render_widget(
-                    Paragraph::new("Winners:").alignment(Alignment::Center),
+                    Paragraph::new(format!("{} victory! Winners:", victory.view()))
+                        .alignment(Alignment::Center),
                     header,
                 );
                 frame.render_widget(
@@ -1148,6 +1215,22 @@ fn render(
             }
         }
 
+        if let Some(message) = board.scenario.messages.get(ui_state.messages_seen) {
+            let inner = popup(frame, rest, Size::new(50, 10));
+            let [descr, ok] = Layout::vertical([Fill(1), Length(1)]).areas(inner);
+            frame.render_widget(
+                Paragraph::new(message.clone()).wrap(Wrap { trim: false }),
+                descr,
+            );
+            frame.render_widget(
+                Button {
+                    string: "Continue",
+                    pressed: true,
+                },
+                ok,
+            )
+        }
+
         //todo: step_queue ui
         if ui_state.queue_paused {
             let [_, inner] = Layout::vertical([Fill(1), Length(1)]).areas(rest);
@@ -1179,7 +1262,16 @@ struct InterfaceState {
     show_details: bool,
     map_only_player_color: bool,
     battle_state: BattleWidgetState,
-    winning_players: Option<Vec<PlayerId>>,
+    outcome: Option<Outcome>,
+
+    /// Stats of the ended game, shown instead of the winners when available
+    stats: Option<Stats>,
+
+    /// Scenario messages already dismissed
+    messages_seen: usize,
+
+    /// Set when playing a campaign mission, its victory is saved in the campaign progress
+    mission: Option<CampaignMission>,
     queued_actions: Vec<PlayerAction>,
     queued_path: Option<Path>,
     queue_paused: bool,
@@ -1190,6 +1282,9 @@ struct InterfaceState {
     close_on_end: bool,
     member_profile: Member,
     background_board: Option<Board>,
+
+    /// Mods laid over the blueprints and maps, only players with the same mods can join
+    mods: ModSet,
 }
 
 impl Default for InterfaceState {
@@ -1203,7 +1298,10 @@ impl Default for InterfaceState {
                 keybinds: Keybinds::default(),
                 machine_speed: MachineSpeed::StepMovesSlow,
             },
-            winning_players: None,
+            outcome: None,
+            stats: None,
+            messages_seen: 0,
+            mission: None,
             queued_actions: vec![],
             queued_path: None,
             queue_delay: 0,
@@ -1214,6 +1312,7 @@ impl Default for InterfaceState {
             close_on_end: false,
             member_profile: Member::default(),
             background_board: None,
+            mods: ModSet::default(),
         }
     }
 }
@@ -1221,7 +1320,9 @@ impl Default for InterfaceState {
 impl InterfaceState {
     fn reset(&mut self) {
         self.battle_state = BattleWidgetState::default();
-        self.winning_players = None;
+        self.outcome = None;
+        self.stats = None;
+        self.messages_seen = 0;
         self.queued_actions.clear();
         self.queued_path = None;
         self.queue_paused = false;
@@ -24,17 +24,24 @@ impl Default for Member {
 }
 
 impl Member {
-    pub fn from_user_data(user_data: &[u8; NETCODE_USER_DATA_BYTES]) -> Self {
-        bincode::decode_from_slice::<Self, Configuration>(user_data, Configuration::default())
-            .unwrap()
-            .0
+    /// The fingerprint of the blueprints, maps and mods of the client and its member
+    pub fn from_user_data(user_data: &[u8; NETCODE_USER_DATA_BYTES]) -> (u64, Self) {
+        bincode::decode_from_slice::<(u64, Self), Configuration>(
+            user_data,
+            Configuration::default(),
+        )
+        .unwrap()
+        .0
     }
 
-    pub fn to_user_data(&self) -> [u8; NETCODE_USER_DATA_BYTES] {
+    /// The fingerprint of the files goes first, the member may not fit whole
+    pub fn to_user_data(&self, mods: u64) -> [u8; NETCODE_USER_DATA_BYTES] {
         let mut user_data = [0u8; NETCODE_USER_DATA_BYTES];
-        let mut bin =
-            bincode::encode_to_vec::<&Member, Configuration>(self, Configuration::default())
-                .unwrap();
+        let mut bin = bincode::encode_to_vec::<(u64, &Member), Configuration>(
+            (mods, self),
+            Configuration::default(),
+        )
+        .unwrap();
         bin.truncate(NETCODE_USER_DATA_BYTES);
         while bin.len() < NETCODE_USER_DATA_BYTES {
             bin.push(0)
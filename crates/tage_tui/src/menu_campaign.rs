@@ -0,0 +1,209 @@
+use crate::*;
+
+/// Campaigns found in the assets and the missions of the open one
+#[derive(Debug, Clone)]
+pub struct MenuCampaign {
+    /// File name and definition of each campaign
+    campaigns: Vec<(String, Campaign)>,
+    cursor: i32,
+
+    /// The campaign whose missions are listed and its progress
+    open: Option<(usize, CampaignProgress)>,
+    pub started: bool,
+}
+
+/// The campaign mission being played
+#[derive(Debug, Clone)]
+pub struct CampaignMission {
+    file: String,
+    campaign: Campaign,
+    mission: usize,
+}
+
+impl CampaignMission {
+    /// Saves the victory in the progress of the campaign
+    pub fn win(&self, board: &Board) {
+        let mut progress = load_progress(&self.file);
+        progress.win(self.mission, board, &self.campaign.player);
+        if let Some(path) = progress_path(&self.file) {
+            let _ = progress.save(path.to_str().unwrap());
+        }
+    }
+
+    pub fn is_winner(&self, outcome: &Outcome) -> bool {
+        outcome.winners.contains(&self.campaign.player)
+    }
+}
+
+/// Progress is kept in the data dir, in a file named as the campaign
+fn progress_path(file: &str) -> Option<PathBuf> {
+    let mut path = get_data_dir_sub("campaigns")?;
+    path.push(file);
+    Some(path)
+}
+
+fn load_progress(file: &str) -> CampaignProgress {
+    progress_path(file)
+        .and_then(|path| CampaignProgress::load(path.to_str().unwrap()).ok())
+        .unwrap_or_default()
+}
+
+impl MenuCampaign {
+    pub fn new() -> MenuCampaign {
+        let mut campaigns: Vec<(String, Campaign)> =
+            if let Ok(paths) = fs::read_dir(format!("{}/campaigns", get_assets_dir())) {
+                paths
+                    .filter_map(|path| {
+                        let path = path.ok()?.path();
+                        let campaign = Campaign::load(path.to_str()?).ok()?;
+                        Some((path.file_name()?.to_str()?.to_string(), campaign))
+                    })
+                    .collect()
+            } else {
+                vec![]
+            };
+        campaigns.sort_by(|a, b| a.0.cmp(&b.0));
+        MenuCampaign {
+            campaigns,
+            cursor: 0,
+            open: None,
+            started: false,
+        }
+    }
+
+    /// Lists the missions of the campaign of `mission`
+    pub fn returning(mission: &CampaignMission) -> MenuCampaign {
+        let mut menu = Self::new();
+        if let Some(index) = menu.campaigns.iter().position(|(f, _)| f == &mission.file) {
+            menu.open = Some((index, load_progress(&mission.file)));
+        }
+        menu
+    }
+
+    pub fn input(
+        mut self,
+        input: MenuInput,
+        ui_state: &mut InterfaceState,
+        game_state: &mut Option<GameState>,
+        bp: &Blueprints,
+    ) -> Option<Self> {
+        let Some((index, progress)) = self.open.clone() else {
+            self.cursor = (self.cursor + input.acc.y).clamp(0, self.campaigns.len() as i32);
+            if input.back || (input.select && self.cursor == 0) {
+                return None;
+            }
+            if input.select {
+                let index = (self.cursor - 1) as usize;
+                self.open = Some((index, load_progress(&self.campaigns[index].0)));
+                self.cursor = 0;
+            }
+            return Some(self);
+        };
+
+        let (file, campaign) = &self.campaigns[index];
+        self.cursor = (self.cursor + input.acc.y).clamp(0, campaign.missions.len() as i32);
+        if input.back || (input.select && self.cursor == 0) {
+            self.cursor = index as i32 + 1;
+            self.open = None;
+            return Some(self);
+        }
+        let mission = (self.cursor - 1) as usize;
+        if input.select && progress.is_unlocked(mission) {
+            let Ok(settings) = campaign.mission_settings(&get_assets_dir(), mission) else {
+                return Some(self);
+            };
+            let Ok(mut state) = setup_gamestate(settings.clone(), bp) else {
+                return Some(self);
+            };
+            if let Some(carried) = progress.carried(mission) {
+                let carry = &campaign.missions[mission].carry;
+                carried.apply(&mut state.board, &campaign.player, carry);
+                state.replay = Replay::new(&settings, &state.board);
+            }
+            *game_state = Some(state);
+            ui_state.reset();
+            ui_state.mission = Some(CampaignMission {
+                file: file.clone(),
+                campaign: campaign.clone(),
+                mission,
+            });
+            self.started = true;
+        }
+        Some(self)
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        use Constraint::*;
+
+        frame.render_widget(Clear, area);
+
+        let [topbar, rest] = Layout::vertical([Length(1), Fill(1)]).areas(area);
+        let title = match &self.open {
+            Some((index, _)) => self.campaigns[*index].1.name.clone(),
+            None => "Play a campaign:".to_string(),
+        };
+        frame.render_widget(Paragraph::new(title).alignment(Alignment::Center), topbar);
+
+        let [list, decor] = Layout::horizontal([Max(50), Fill(1)]).areas(rest);
+        let [head, _, list] = Layout::vertical([Length(1), Length(1), Fill(1)]).areas(list);
+
+        let list = bordered(frame, list);
+
+        frame.render_widget(
+            Paragraph::new("Back")
+                .alignment(Alignment::Center)
+                .style(if self.cursor == 0 {
+                    Style::default().add_modifier(Modifier::REVERSED)
+                } else {
+                    Style::default()
+                }),
+            head,
+        );
+
+        let rows: Vec<Row> = match &self.open {
+            Some((index, progress)) => self.campaigns[*index]
+                .1
+                .missions
+                .iter()
+                .enumerate()
+                .map(|(i, mission)| {
+                    let status = if progress.is_won(i) {
+                        "won"
+                    } else if progress.is_unlocked(i) {
+                        ""
+                    } else {
+                        "locked"
+                    };
+                    Row::new(vec![
+                        Line::from(format!("{}. {}", i + 1, mission.name)),
+                        Line::from(status),
+                    ])
+                })
+                .collect(),
+            None => self
+                .campaigns
+                .iter()
+                .map(|(_, campaign)| {
+                    Row::new(vec![
+                        Line::from(campaign.name.clone()),
+                        Line::from(format!("{} missions", campaign.missions.len())),
+                    ])
+                })
+                .collect(),
+        };
+
+        let selected = if self.cursor > 0 {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default()
+        };
+        let mut state = TableState::new().with_selected((self.cursor - 1).max(0) as usize);
+        frame.render_stateful_widget(
+            Table::new(rows, [Fill(1), Length(10)]).highlight_style(selected),
+            list,
+            &mut state,
+        );
+
+        frame.render_widget(PanelWidget::new(DECOR_3), decor)
+    }
+}
@@ -53,13 +53,13 @@ impl MenuConnect {
             match self.cursor {
                 0 => MenuState::Home(MenuHome::new()),
                 1 => {
-                    net.open_server(ui_state.member_profile.clone());
-                    MenuState::Lobby(MenuLobby::new(ui_state, net))
+                    net.open_server(ui_state.member_profile.clone(), &ui_state.mods);
+                    MenuState::Lobby(Box::new(MenuLobby::new(ui_state, net)))
                 }
                 2 => match self.addr.parse() {
                     Ok(addr) => {
-                        net.open_client(ui_state.member_profile.clone(), addr);
-                        MenuState::Lobby(MenuLobby::new(ui_state, net))
+                        net.open_client(ui_state.member_profile.clone(), &ui_state.mods, addr);
+                        MenuState::Lobby(Box::new(MenuLobby::new(ui_state, net)))
                     }
                     Err(e) => {
                         let help = format!(
@@ -64,6 +64,9 @@ impl MenuLoad {
                         blueprints: bp.clone(),
                         navigator: None,
                         turn_timeline: vec![],
+                        undo_barrier: 0,
+                        turn_history: vec![],
+                        replay: Replay::new(&MapSettings::default(), board),
                         current_picker: UiPicker::Tile(UiTilePicker {
                             cursor: IVec2::ZERO,
                             unit: None,
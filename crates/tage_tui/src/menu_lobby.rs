@@ -1,4 +1,4 @@
-use std::{fs, sync::Arc};
+use std::sync::Arc;
 
 use tage_core::prelude::*;
 
@@ -16,6 +16,8 @@ pub enum LobbySection {
     SelectMap,
     WithHero,
     Fog,
+    Victory,
+    Rules,
     Start,
 }
 
@@ -24,59 +26,210 @@ pub struct LobbySelectMap {
     pub choices: Vec<String>,
     pub cursor: i32,
     pub maps: Vec<Board>,
+    pub files: Vec<MapFile>,
+
+    /// Settings of the random map, the first in the list
+    pub random: MapGenSettings,
+
+    /// Fairness of the maps, measured when the cursor is first on them
+    pub reports: Vec<Option<MapReport>>,
 }
 
 impl LobbySelectMap {
-    pub fn new(bp: &Blueprints, players: Vec<Player>, path: &str) -> Self {
+    /// Lists the maps in the assets and in the mods after a random map, generated with the seed
+    /// of the current settings if they already use one
+    pub fn new(
+        bp: &Blueprints,
+        players: Vec<Player>,
+        map_settings: &MapSettings,
+        mods: &ModSet,
+    ) -> Self {
         tracing::trace!("{}", format!("{}/maps/", get_assets_dir()));
-        let paths = fs::read_dir(format!("{}/maps/", get_assets_dir())).unwrap();
+        let paths = mods.map_paths(&get_assets_dir());
+
+        let preview = |grid| {
+            let mut board = Board {
+                bp: Arc::new(bp.clone()),
+                grid,
+                players: players.clone(),
+                day: 0,
+                current_player_turn: PlayerId::new(0),
+                player_turn_order: vec![],
+                fog: HashMap::new(),
+                fog_base: FogTile::Visible,
+                rng: BoardRng::default(),
+                victory: VictoryState::default(),
+                diplomacy: Diplomacy::default(),
+                rules: Rules::default(),
+                scenario: ScenarioState::default(),
+                events: GameEvents::default(),
+            };
+            board.add_gaia();
+            board
+        };
 
-        let mut choices: Vec<(String, Board)> = paths
+        let mut choices: Vec<(String, Board, MapFile)> = paths
+            .into_iter()
             .filter_map(|path| {
-                let path = path.unwrap().path().to_str().unwrap().to_string();
-                load_map(
+                let file = MapFile::load(&mods.locate(&path)).ok()?;
+                load_map_file_with_starts(
                     bp,
+                    &file,
                     &MapSettings {
-                        path: path.clone(),
+                        path: mods.locate(&path),
                         players: vec![],
                         place_hero: true,
                         fog_base: FogTile::Visible,
+                        seed: None,
+                        victory: VictoryConditions::default(),
+                        rules: file.rules.first().cloned().unwrap_or_default(),
+                        triggers: vec![],
+                        generator: None,
                     },
                 )
                 .ok()
-                .map(|grid| {
-                    let board = Board {
-                        bp: Arc::new(bp.clone()),
-                        grid,
-                        players: players.clone(),
-                        day: 0,
-                        current_player_turn: PlayerId::new(0),
-                        player_turn_order: vec![],
-                        fog: HashMap::new(),
-                        fog_base: FogTile::Visible,
-                    };
-                    (path, board)
-                })
+                .map(|(grid, _)| (path, preview(grid), file))
             })
             .collect();
 
         choices.sort_by(|a, b| a.0.cmp(&b.0));
 
+        let random = map_settings
+            .generator
+            .clone()
+            .unwrap_or_else(|| MapGenSettings {
+                seed: BoardRng::from_entropy().seed(),
+                players: players.len().max(2) as u32,
+                ..Default::default()
+            });
+        let grid = generate_map(bp, &random);
+        let file = random_map_file(bp, &random, &grid);
+        choices.insert(0, (String::new(), preview(grid), file));
+
         let cursor = choices
             .iter()
             .enumerate()
-            .find(|(_, (c, _))| c == path)
+            .find(|(_, (c, _, _))| map_settings.generator.is_none() && c == &map_settings.path)
             .map(|(i, _)| i as i32)
             .unwrap_or(0);
 
-        Self {
-            choices: choices.iter().map(|(c, _)| c.clone()).collect(),
+        let mut select_map = Self {
+            choices: choices.iter().map(|(c, _, _)| c.clone()).collect(),
             cursor,
-            maps: choices.into_iter().map(|(_, board)| board).collect(),
+            maps: choices.iter().map(|(_, board, _)| board.clone()).collect(),
+            reports: vec![None; choices.len()],
+            files: choices.into_iter().map(|(_, _, file)| file).collect(),
+            random,
+        };
+        select_map.analyze(bp);
+        select_map
+    }
+
+    /// Measures the fairness of the map under the cursor if it wasn't yet
+    pub fn analyze(&mut self, bp: &Blueprints) {
+        let c = self.cursor as usize;
+        if self.reports[c].is_none() {
+            self.reports[c] =
+                MapReport::from_map_file(bp, &self.files[c], &MapReportSettings::default()).ok();
         }
     }
 }
 
+/// Victory conditions picked in the lobby, other combinations can be set in a settings file
+fn victory_presets() -> Vec<VictoryConditions> {
+    vec![
+        VictoryConditions::default(),
+        VictoryConditions {
+            regicide: true,
+            ..Default::default()
+        },
+        VictoryConditions {
+            relic: Some(10),
+            ..Default::default()
+        },
+        VictoryConditions {
+            wonder: Some(20),
+            ..Default::default()
+        },
+        VictoryConditions {
+            score: Some(100),
+            ..Default::default()
+        },
+    ]
+}
+
+/// Rules picked in the lobby for maps that don't list theirs
+fn rules_presets() -> Vec<Rules> {
+    vec![
+        Rules::default(),
+        Rules {
+            no_rng: true,
+            ..Default::default()
+        },
+        Rules {
+            market: MarketModel::Shared,
+            ..Default::default()
+        },
+        Rules {
+            no_rng: true,
+            market: MarketModel::Shared,
+            ..Default::default()
+        },
+    ]
+}
+
+/// The choice after the current one, the first if the current one isn't a choice
+fn cycle<T: Clone + PartialEq>(choices: &[T], current: &T) -> T {
+    choices
+        .iter()
+        .position(|choice| choice == current)
+        .map_or(&choices[0], |i| &choices[(i + 1) % choices.len()])
+        .clone()
+}
+
+fn view_victory(victory: &VictoryConditions) -> String {
+    let mut ways = vec!["conquest".to_string()];
+    if victory.regicide {
+        ways.push("regicide".to_string());
+    }
+    if let Some(days) = victory.relic {
+        ways.push(format!("every relic held for {} days", days));
+    }
+    if let Some(days) = victory.wonder {
+        ways.push(format!("a wonder standing for {} days", days));
+    }
+    if let Some(day) = victory.score {
+        ways.push(format!("highest score on day {}", day));
+    }
+    format!("Victory by {}", ways.join(", "))
+}
+
+fn view_rules(rules: &Rules) -> String {
+    let mut changes = vec![];
+    if rules.no_rng {
+        changes.push(format!("no rng, ruins give {:?}", rules.ruins_reward));
+    }
+    if rules.market == MarketModel::Shared {
+        changes.push("shared market rates".to_string());
+    }
+    if changes.is_empty() {
+        "Standard rules".to_string()
+    } else {
+        format!("Rules: {}", changes.join(", "))
+    }
+}
+
+/// Describes a generated map as if it was loaded from a map file
+fn random_map_file(bp: &Blueprints, generator: &MapGenSettings, grid: &Grid<BoardTile>) -> MapFile {
+    MapFile {
+        description: format!(
+            "Seed {}, a new one every time the list is opened.",
+            generator.seed
+        ),
+        ..MapFile::from_grid("Random map", &write_map(bp, grid))
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct LobbySelectTeam {
     pub choices: Vec<Option<TeamId>>,
@@ -108,6 +261,7 @@ pub struct MenuLobby {
     cursor: i32,
     select_map: Option<LobbySelectMap>,
     chosen_map: Option<Board>,
+    chosen_file: Option<MapFile>,
     select_team: Option<LobbySelectTeam>,
     select_name: Option<String>,
     select_civilization: Option<LobbySelectCivilization>,
@@ -116,6 +270,9 @@ pub struct MenuLobby {
     select_controller: Option<LobbySelectController>,
     select_hero: bool,
     select_fog_base: FogTile,
+
+    /// Maps of the mods are sent with the paths of `ModSet::map_paths`, located on each machine
+    mods: ModSet,
 }
 
 // a bit long
@@ -144,11 +301,14 @@ impl MenuLobby {
                 LobbySection::SelectMap,
                 LobbySection::WithHero,
                 LobbySection::Fog,
+                LobbySection::Victory,
+                LobbySection::Rules,
                 LobbySection::Start,
             ],
             map_settings: MapSettings::default(),
             cursor: 0,
             select_hero: true,
+            mods: ui.mods.clone(),
             ..Default::default()
         }
     }
@@ -177,6 +337,26 @@ impl MenuLobby {
         &mut choices[*cursor as usize]
     }
 
+    /// Generates the random map again for the players in the lobby when their number changed,
+    /// so that the game starts on the map that is shown. Returns true if it did
+    fn fit_random_map(&mut self, bp: &Blueprints) -> bool {
+        let players = self.get_player_list().len().max(2) as u32;
+        let Some(generator) = &mut self.map_settings.generator else {
+            return false;
+        };
+        if generator.players == players {
+            return false;
+        }
+        generator.players = players;
+        let generator = generator.clone();
+        let grid = generate_map(bp, &generator);
+        self.chosen_file = Some(random_map_file(bp, &generator, &grid));
+        if let Some(board) = &mut self.chosen_map {
+            board.grid = grid;
+        }
+        true
+    }
+
     pub fn input(&self, input: MenuInput, bp: &Blueprints, net: &mut Net) -> MenuState {
         let mut next = self.clone();
 
@@ -220,6 +400,10 @@ impl MenuLobby {
             }
         });
 
+        if !net.is_client() && next.fit_random_map(bp) {
+            send_settings = true;
+        }
+
         if let Some(Connection::Client(client)) = &mut net.connection {
             while let Some(message) = client.queue.pop() {
                 match message {
@@ -229,16 +413,21 @@ impl MenuLobby {
                         let player_list = next.get_player_list();
                         player_list.clear();
                         player_list.append(&mut map_settings.players);
-                        if map_settings.path != "" {
-                            next.chosen_map = Some(Board {
+                        if map_settings.path != "" || map_settings.generator.is_some() {
+                            let mut board = Board {
                                 bp: Arc::new(bp.clone()),
                                 grid: load_map(
                                     bp,
                                     &MapSettings {
-                                        path: map_settings.path.clone(),
+                                        path: self.mods.locate(&map_settings.path),
                                         place_hero: self.select_hero,
                                         players: vec![],
                                         fog_base: self.select_fog_base.clone(),
+                                        seed: None,
+                                        victory: map_settings.victory.clone(),
+                                        rules: map_settings.rules.clone(),
+                                        triggers: vec![],
+                                        generator: map_settings.generator.clone(),
                                     },
                                 )
                                 .unwrap(),
@@ -255,7 +444,21 @@ impl MenuLobby {
                                 player_turn_order: vec![],
                                 fog: HashMap::new(),
                                 fog_base: self.select_fog_base.clone(),
-                            });
+                                rng: BoardRng::default(),
+                                victory: VictoryState::default(),
+                                diplomacy: Diplomacy::default(),
+                                rules: Rules::default(),
+                                scenario: ScenarioState::default(),
+                                events: GameEvents::default(),
+                            };
+                            board.add_gaia();
+                            next.chosen_file = match &map_settings.generator {
+                                Some(generator) => {
+                                    Some(random_map_file(bp, generator, &board.grid))
+                                }
+                                None => MapFile::load(&self.mods.locate(&map_settings.path)).ok(),
+                            };
+                            next.chosen_map = Some(board);
                         }
                     }
                     ServerMessages::ToGame => {
@@ -305,6 +508,7 @@ impl MenuLobby {
         if let Some(select_map) = &mut next.select_map {
             select_map.cursor =
                 (select_map.cursor + input.acc.y).clamp(0, select_map.choices.len() as i32 - 1);
+            select_map.analyze(bp);
         } else if let Some(select_team) = &mut next.select_team {
             select_team.cursor =
                 (select_team.cursor + input.acc.y).clamp(0, select_team.choices.len() as i32 - 1);
@@ -370,7 +574,7 @@ impl MenuLobby {
             }
         }
 
-        let mut next_state = MenuState::Lobby(next.clone());
+        let mut next_state = MenuState::Lobby(Box::new(next.clone()));
         if input.select {
             if let Some(_) = net.error.pop() {
                 return MenuState::Home(MenuHome::new());
@@ -379,17 +583,21 @@ impl MenuLobby {
             if let Some(select_map) = next.select_map.take() {
                 let c = select_map.cursor as usize;
                 if !net.is_client() {
+                    let file = select_map.files[c].clone();
                     next.map_settings.path = select_map.choices[c].clone();
+                    next.map_settings.generator = (c == 0).then(|| select_map.random.clone());
+                    next.map_settings.rules = file.rules.first().cloned().unwrap_or_default();
                     next.chosen_map = Some(select_map.maps[c].clone());
+                    next.chosen_file = Some(file);
                 }
-                next_state = MenuState::Lobby(next.clone());
+                next_state = MenuState::Lobby(Box::new(next.clone()));
             } else if let Some(select_team) = next.select_team.take() {
                 let c = select_team.cursor as usize;
                 next.get_selected_player().team = select_team.choices[c].clone();
-                next_state = MenuState::Lobby(next.clone());
+                next_state = MenuState::Lobby(Box::new(next.clone()));
             } else if let Some(select_name) = next.select_name.take() {
                 next.get_selected_player().name = select_name.clone();
-                next_state = MenuState::Lobby(next.clone());
+                next_state = MenuState::Lobby(Box::new(next.clone()));
                 if let Controller::Remote(id) = next.get_selected_player().controller {
                     match &mut net.connection {
                         Some(Connection::Client(client)) => {
@@ -406,7 +614,7 @@ impl MenuLobby {
                 }
             } else if let Some(select_symbol) = next.select_symbol.take() {
                 next.get_selected_player().symbol = select_symbol.clone();
-                next_state = MenuState::Lobby(next.clone());
+                next_state = MenuState::Lobby(Box::new(next.clone()));
                 if let Controller::Remote(id) = next.get_selected_player().controller {
                     match &mut net.connection {
                         Some(Connection::Client(client)) => {
@@ -423,7 +631,7 @@ impl MenuLobby {
                 }
             } else if let Some(select_color) = next.select_color.take() {
                 next.get_selected_player().color = color_to_u32(select_color.color);
-                next_state = MenuState::Lobby(next.clone());
+                next_state = MenuState::Lobby(Box::new(next.clone()));
                 if let Controller::Remote(id) = next.get_selected_player().controller {
                     match &mut net.connection {
                         Some(Connection::Server(server)) => {
@@ -446,14 +654,14 @@ impl MenuLobby {
             } else if let Some(select_controller) = next.select_controller.take() {
                 let c = select_controller.cursor as usize;
                 next.get_selected_player().controller = select_controller.choices[c].clone();
-                next_state = MenuState::Lobby(next.clone());
+                next_state = MenuState::Lobby(Box::new(next.clone()));
             } else if let Some(select_civilization) = next.select_civilization.take() {
                 let c = select_civilization.cursor as usize;
                 next.get_selected_player().civilization = bp
                     .get_civilization(&select_civilization.choices[c])
                     .name
                     .clone();
-                next_state = MenuState::Lobby(next.clone());
+                next_state = MenuState::Lobby(Box::new(next.clone()));
                 if let Controller::Remote(id) = next.get_selected_player().controller {
                     match &mut net.connection {
                         Some(Connection::Client(client)) => {
@@ -511,7 +719,7 @@ impl MenuLobby {
                                 symbol: "'".to_string(),
                                 ..Default::default()
                             });
-                            next_state = MenuState::Lobby(next.clone());
+                            next_state = MenuState::Lobby(Box::new(next.clone()));
                         }
                     }
                     LobbySection::SelectMap => {
@@ -525,9 +733,10 @@ impl MenuLobby {
                                     ..Default::default()
                                 })
                                 .collect(),
-                            next.map_settings.path.as_str(),
+                            &next.map_settings,
+                            &next.mods,
                         ));
-                        next_state = MenuState::Lobby(next.clone());
+                        next_state = MenuState::Lobby(Box::new(next.clone()));
                     }
                     LobbySection::PlayerList {
                         choices,
@@ -551,11 +760,11 @@ impl MenuLobby {
                                             .collect::<Vec<Option<TeamId>>>(),
                                         cursor: 0,
                                     });
-                                    next_state = MenuState::Lobby(next.clone());
+                                    next_state = MenuState::Lobby(Box::new(next.clone()));
                                 }
                                 1 if local => {
                                     next.select_name = Some(choices[*cursor as usize].name.clone());
-                                    next_state = MenuState::Lobby(next.clone());
+                                    next_state = MenuState::Lobby(Box::new(next.clone()));
                                 }
                                 2 if local => {
                                     let u = choices[*cursor as usize].color;
@@ -566,12 +775,12 @@ impl MenuLobby {
                                         color: [r, g, b],
                                         cursor: 0,
                                     });
-                                    next_state = MenuState::Lobby(next.clone());
+                                    next_state = MenuState::Lobby(Box::new(next.clone()));
                                 }
                                 3 if local => {
                                     next.select_symbol =
                                         Some(choices[*cursor as usize].symbol.clone());
-                                    next_state = MenuState::Lobby(next.clone());
+                                    next_state = MenuState::Lobby(Box::new(next.clone()));
                                 }
                                 4 if !net.is_client() => {
                                     next.select_controller = Some(LobbySelectController {
@@ -586,7 +795,7 @@ impl MenuLobby {
                                         ],
                                         cursor: 0,
                                     });
-                                    next_state = MenuState::Lobby(next.clone());
+                                    next_state = MenuState::Lobby(Box::new(next.clone()));
                                 }
                                 5 if !net.is_client() => {
                                     next.select_civilization = Some(LobbySelectCivilization {
@@ -597,7 +806,7 @@ impl MenuLobby {
                                             .collect(),
                                         cursor: 0,
                                     });
-                                    next_state = MenuState::Lobby(next.clone());
+                                    next_state = MenuState::Lobby(Box::new(next.clone()));
                                 }
                                 6 if !net.is_client() => {
                                     let player_setting = choices.remove(*cursor as usize);
@@ -606,7 +815,7 @@ impl MenuLobby {
                                             net.kick(raw!(*id));
                                         }
                                     }
-                                    next_state = MenuState::Lobby(next.clone());
+                                    next_state = MenuState::Lobby(Box::new(next.clone()));
                                 }
                                 _ => {}
                             }
@@ -636,7 +845,11 @@ impl MenuLobby {
                     }
                     LobbySection::WithHero if !net.is_client() => {
                         next.select_hero = !next.select_hero;
-                        next_state = MenuState::Lobby(next.clone());
+                        // regicide needs the heroes placed at the start
+                        if !next.select_hero {
+                            next.map_settings.victory.regicide = false;
+                        }
+                        next_state = MenuState::Lobby(Box::new(next.clone()));
                     }
                     LobbySection::Fog if !net.is_client() => {
                         next.select_fog_base = match next.select_fog_base {
@@ -644,7 +857,24 @@ impl MenuLobby {
                             FogTile::Explored => FogTile::Hidden,
                             FogTile::Hidden => FogTile::Visible,
                         };
-                        next_state = MenuState::Lobby(next.clone());
+                        next_state = MenuState::Lobby(Box::new(next.clone()));
+                    }
+                    LobbySection::Victory if !net.is_client() => {
+                        let allowed: Vec<VictoryConditions> = victory_presets()
+                            .into_iter()
+                            .filter(|victory| next.select_hero || !victory.regicide)
+                            .collect();
+                        next.map_settings.victory = cycle(&allowed, &next.map_settings.victory);
+                        next_state = MenuState::Lobby(Box::new(next.clone()));
+                    }
+                    LobbySection::Rules if !net.is_client() => {
+                        // maps that list their rules can only be played with those
+                        let allowed = match &next.chosen_file {
+                            Some(file) if !file.rules.is_empty() => file.rules.clone(),
+                            _ => rules_presets(),
+                        };
+                        next.map_settings.rules = cycle(&allowed, &next.map_settings.rules);
+                        next_state = MenuState::Lobby(Box::new(next.clone()));
                     }
                     _ => {}
                 }
@@ -687,7 +917,18 @@ impl MenuLobby {
         use Constraint::*;
 
         let [topbar, rest] = Layout::vertical([Length(1), Fill(1)]).areas(area);
-        frame.render_widget(Paragraph::new("Lobby").alignment(Alignment::Center), topbar);
+        let title = if self.mods.mods.is_empty() {
+            "Lobby".to_string()
+        } else if self.mods.conflicts.is_empty() {
+            format!("Lobby - Mods: {}", self.mods.names().join(", "))
+        } else {
+            format!(
+                "Lobby - Mods: {} ({} conflicts)",
+                self.mods.names().join(", "),
+                self.mods.conflicts.len()
+            )
+        };
+        frame.render_widget(Paragraph::new(title).alignment(Alignment::Center), topbar);
 
         let [d0, center, d1] = Layout::horizontal([Fill(1), Max(78), Fill(1)]).areas(rest);
 
@@ -702,6 +943,8 @@ impl MenuLobby {
                 LobbySection::SelectMap => Fill(1),
                 LobbySection::WithHero => Length(1),
                 LobbySection::Fog => Length(1),
+                LobbySection::Victory => Length(1),
+                LobbySection::Rules => Length(1),
                 LobbySection::Start => Length(3),
             }))
             .split(center)
@@ -722,7 +965,7 @@ impl MenuLobby {
                     frame.render_widget(Paragraph::new("Start").centered().style(selected), center)
                 }
                 LobbySection::SelectMap => {
-                    if self.map_settings.path == "" {
+                    if self.chosen_map.is_none() {
                         frame.render_widget(
                             Paragraph::new("Select Map").centered().style(selected),
                             *sect_area,
@@ -730,15 +973,22 @@ impl MenuLobby {
                     } else {
                         let [mapname, map] =
                             Layout::vertical([Length(1), Fill(1)]).areas(*sect_area);
+                        let preview = self.chosen_file.as_ref().map(|file| &file.preview);
                         frame.render_widget(
                             Paragraph::new(format!(
                                 "Map: {}",
-                                self.map_settings
-                                    .path
-                                    .split("/")
-                                    .last()
-                                    .unwrap()
-                                    .trim_end_matches(".txt")
+                                match (&self.map_settings.generator, &self.chosen_file) {
+                                    (Some(generator), _) =>
+                                        format!("Random map, seed {}", generator.seed),
+                                    (None, Some(file)) => file.name.clone(),
+                                    (None, None) => self
+                                        .map_settings
+                                        .path
+                                        .split("/")
+                                        .last()
+                                        .unwrap()
+                                        .to_string(),
+                                }
                             ))
                             .centered()
                             .style(selected),
@@ -749,12 +999,14 @@ impl MenuLobby {
                                 BoardWidget {
                                     board,
                                     blueprints: bp,
-                                    cursor: board.grid.size / 2,
+                                    cursor: preview
+                                        .and_then(|p| p.center)
+                                        .unwrap_or(board.grid.size / 2),
                                     attack_tiles: &vec![],
                                     movement_tiles: &vec![],
                                     target_tiles: &vec![],
                                     only_player_color: false,
-                                    zoom: 3,
+                                    zoom: preview.and_then(|p| p.zoom).unwrap_or(3),
                                     show_spawns: false,
                                     travel_path: &vec![],
                                     fog_player: &PlayerId::new(0),
@@ -837,6 +1089,9 @@ impl MenuLobby {
                                     Controller::Machine(MachineOpponent::Peak(ref _peak)) => {
                                         format!("Tuned Peak")
                                     }
+                                    Controller::Machine(MachineOpponent::Gaia) => {
+                                        format!("Gaia")
+                                    }
                                 },
                                 None,
                             ),
@@ -899,6 +1154,18 @@ impl MenuLobby {
                     .style(selected),
                     *sect_area,
                 ),
+                LobbySection::Victory => frame.render_widget(
+                    Paragraph::new(view_victory(&self.map_settings.victory))
+                        .centered()
+                        .style(selected),
+                    *sect_area,
+                ),
+                LobbySection::Rules => frame.render_widget(
+                    Paragraph::new(view_rules(&self.map_settings.rules))
+                        .centered()
+                        .style(selected),
+                    *sect_area,
+                ),
             }
 
             if let Some(select_map) = &self.select_map {
@@ -916,26 +1183,62 @@ impl MenuLobby {
                 let [topbar, list] = Layout::vertical([Length(1), Fill(1)]).areas(list);
                 frame.render_widget(Paragraph::new("Select Map:"), topbar);
 
-                let rows = select_map.choices.iter().enumerate().map(|(y, path)| {
+                let rows = select_map.files.iter().enumerate().map(|(y, file)| {
                     let style = if y as i32 == select_map.cursor {
                         Style::default().add_modifier(Modifier::REVERSED)
                     } else {
                         Style::default()
                     };
-                    let name = path.split("/").last().unwrap().trim_end_matches(".txt");
-                    Row::new(vec![Cell::new(Line::from(name).style(style))])
+                    Row::new(vec![Cell::new(Line::from(file.name.clone()).style(style))])
                 });
 
                 let mut state = TableState::new().with_selected(select_map.cursor as usize);
                 frame.render_stateful_widget(Table::new(rows, [30]), list, &mut state);
 
                 let chosen_map = &select_map.maps[select_map.cursor as usize];
+                let chosen_file = &select_map.files[select_map.cursor as usize];
+
+                let [info, map] = Layout::vertical([Length(6), Fill(1)]).areas(map);
+                let players = match chosen_file.recommended_players {
+                    Some(recommended) => format!(
+                        "{} players, up to {}",
+                        recommended,
+                        chosen_file.spawn_count()
+                    ),
+                    None => format!("Up to {} players", chosen_file.spawn_count()),
+                };
+                let author = if chosen_file.author.is_empty() {
+                    String::new()
+                } else {
+                    format!(" by {}", chosen_file.author)
+                };
+                let balance = match &select_map.reports[select_map.cursor as usize] {
+                    Some(report) if report.is_fair() => "Balanced spawn points".to_string(),
+                    Some(report) => report
+                        .warnings
+                        .iter()
+                        .map(|warning| warning.to_string())
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                    None => String::new(),
+                };
+                frame.render_widget(
+                    Paragraph::new(format!(
+                        "{}{}. {}\n{}\n{}",
+                        chosen_file.name, author, players, chosen_file.description, balance
+                    ))
+                    .wrap(Wrap { trim: true }),
+                    info,
+                );
 
                 frame.render_widget(
                     BoardWidget {
                         board: chosen_map,
                         blueprints: bp,
-                        cursor: chosen_map.grid.size / 2,
+                        cursor: chosen_file
+                            .preview
+                            .center
+                            .unwrap_or(chosen_map.grid.size / 2),
                         attack_tiles: &vec![],
                         movement_tiles: &vec![],
                         target_tiles: &vec![],
@@ -1167,6 +1470,7 @@ impl MenuLobby {
                         Controller::Machine(MachineOpponent::Peak(peak)) => {
                             format!("Machine(Peak({:?}))", peak)
                         }
+                        Controller::Machine(MachineOpponent::Gaia) => format!("Machine Gaia"),
                     }))])
                 });
                 let mut state = TableState::new().with_selected(select_controller.cursor as usize);
@@ -34,11 +34,13 @@ pub static ALLOWED_IP_CHARS: [char; 12] =
 #[derive(Debug, Clone)]
 pub enum MenuState {
     Home(MenuHome),
-    Lobby(MenuLobby),
+    Lobby(Box<MenuLobby>),
     Profile(MenuProfile),
     Connect(MenuConnect),
     Settings(MenuSettings),
     Load(MenuLoad),
+    Replay(MenuReplay),
+    Campaign(MenuCampaign),
     Close,
     Play(MapSettings),
 }
@@ -72,6 +74,7 @@ impl MenuState {
             MenuState::Load(load) => {
                 if let Some(load) = load.input(input, bp, game_state) {
                     if load.loaded {
+                        interface_state.mission = None;
                         None
                     } else {
                         Some(MenuState::Load(load))
@@ -80,8 +83,31 @@ impl MenuState {
                     Some(MenuState::Home(MenuHome::new()))
                 }
             }
+            MenuState::Replay(replay) => {
+                if let Some(replay) = replay.input(input, interface_state, bp) {
+                    Some(MenuState::Replay(replay))
+                } else {
+                    Some(MenuState::Home(MenuHome::new()))
+                }
+            }
+            MenuState::Campaign(campaign) => {
+                if let Some(campaign) = campaign.input(input, interface_state, game_state, bp) {
+                    if campaign.started {
+                        None
+                    } else {
+                        Some(MenuState::Campaign(campaign))
+                    }
+                } else {
+                    Some(MenuState::Home(MenuHome::new()))
+                }
+            }
             MenuState::Play(map_settings) => {
-                let _ = game_state.insert(setup_gamestate(map_settings.clone(), bp).unwrap());
+                interface_state.mission = None;
+                let map_settings = MapSettings {
+                    path: interface_state.mods.locate(&map_settings.path),
+                    ..map_settings.clone()
+                };
+                let _ = game_state.insert(setup_gamestate(map_settings, bp).unwrap());
                 interface_state.main_menu = None;
                 None
             }
@@ -102,6 +128,8 @@ impl MenuState {
                 settings_menu.render(frame, frame.size(), &ui_state.settings)
             }
             MenuState::Load(load) => load.render(frame, frame.size(), bp),
+            MenuState::Replay(replay) => replay.render(frame, frame.size(), bp, ui_state),
+            MenuState::Campaign(campaign) => campaign.render(frame, frame.size()),
             MenuState::Close => {}
             MenuState::Play(_) => {}
         };
@@ -119,8 +147,10 @@ impl MenuHome {
         Self {
             choices: [
                 "Singleplayer",
+                "Campaign",
                 "Multiplayer",
                 "Load Save",
+                "Watch Replay",
                 "Settings",
                 "Close",
             ]
@@ -140,9 +170,10 @@ impl MenuHome {
                     if let Some(member) = Member::from_disk() {
                         ui_state.member_profile = member;
                     }
-                    MenuState::Lobby(MenuLobby::new(ui_state, net))
+                    MenuState::Lobby(Box::new(MenuLobby::new(ui_state, net)))
                 }
-                1 => {
+                1 => MenuState::Campaign(MenuCampaign::new()),
+                2 => {
                     if let Some(member) = Member::from_disk() {
                         ui_state.member_profile = member;
                         MenuState::Connect(MenuConnect::new())
@@ -150,9 +181,10 @@ impl MenuHome {
                         MenuState::Profile(MenuProfile::new())
                     }
                 }
-                2 => MenuState::Load(MenuLoad::new()),
-                3 => MenuState::Settings(MenuSettings::new(ui_state)),
-                4 => MenuState::Close,
+                3 => MenuState::Load(MenuLoad::new()),
+                4 => MenuState::Replay(MenuReplay::new()),
+                5 => MenuState::Settings(MenuSettings::new(ui_state)),
+                6 => MenuState::Close,
                 _ => unreachable!(),
             }
         } else {
@@ -165,7 +197,7 @@ impl MenuHome {
 
         render_background(frame, area, ui_state);
 
-        let center = popup(frame, area, Size::new(56, 18));
+        let center = popup(frame, area, Size::new(56, 20));
 
         let [title, _, rest] = Layout::vertical([Length(10), Length(1), Fill(1)]).areas(center);
 
@@ -57,6 +57,7 @@ impl PauseMenuState {
             self.load = load.input(input.clone(), bp, game_state);
             if let Some(ref load) = self.load {
                 if load.loaded {
+                    ui_state.mission = None;
                     return None;
                 }
             }
@@ -96,6 +97,10 @@ impl PauseMenuState {
                         path.push(format!("{}.ron", save));
                         tracing::trace!("saving to {:?}", path);
                         let _ = game_state.board.save(path.to_str().unwrap());
+                        if let Some(mut path) = get_data_dir_sub("replays") {
+                            path.push(format!("{}.ron", save));
+                            let _ = game_state.replay.save(path.to_str().unwrap());
+                        }
                         //todo: report failure
                         return None;
                     }
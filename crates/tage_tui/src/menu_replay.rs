@@ -0,0 +1,539 @@
+use std::collections::HashMap;
+
+use crate::*;
+
+#[derive(Debug, Clone)]
+pub struct MenuReplay {
+    choices: Vec<String>,
+    cursor: i32,
+    replays: HashMap<String, Replay>,
+    viewer: Option<Box<ReplayViewer>>,
+}
+
+impl MenuReplay {
+    pub fn new() -> MenuReplay {
+        let mut choices = if let Some(path) = get_data_dir_sub("replays") {
+            if let Ok(paths) = fs::read_dir(path) {
+                paths
+                    .map(|path| path.unwrap().file_name().to_str().unwrap().to_string())
+                    .collect()
+            } else {
+                vec![]
+            }
+        } else {
+            vec![]
+        };
+        choices.sort();
+        MenuReplay {
+            choices,
+            cursor: 0,
+            replays: HashMap::new(),
+            viewer: None,
+        }
+    }
+
+    pub fn input(
+        mut self,
+        input: MenuInput,
+        ui_state: &mut InterfaceState,
+        bp: &Blueprints,
+    ) -> Option<Self> {
+        if let Some(viewer) = self.viewer.take() {
+            self.viewer = viewer.input(input, ui_state).map(Box::new);
+            return Some(self);
+        }
+
+        self.cursor = (self.cursor + input.acc.y).clamp(0, self.choices.len() as i32);
+        if self.cursor > 0 {
+            let name = &self.choices[(self.cursor - 1).max(0) as usize];
+            if !self.replays.contains_key(name) {
+                let mut path = get_data_dir_sub("replays").unwrap();
+                path.push(name);
+                if let Ok(replay) = Replay::load(path.to_str().unwrap()) {
+                    self.replays.insert(name.clone(), replay);
+                }
+            }
+        }
+        if input.back {
+            return None;
+        }
+        if input.select {
+            if self.cursor > 0 {
+                let name = &self.choices[(self.cursor - 1).max(0) as usize];
+                if let Some(replay) = self.replays.get(name) {
+                    self.viewer = Some(Box::new(ReplayViewer::new(
+                        name.clone(),
+                        replay.clone(),
+                        bp,
+                        ui_state.settings.machine_speed.clone(),
+                    )));
+                }
+                Some(self)
+            } else {
+                None
+            }
+        } else {
+            Some(self)
+        }
+    }
+
+    pub fn render(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        bp: &Blueprints,
+        ui_state: &InterfaceState,
+    ) {
+        use Constraint::*;
+
+        if let Some(viewer) = &self.viewer {
+            viewer.render(frame, area, bp, ui_state);
+            return;
+        }
+
+        frame.render_widget(Clear, area);
+
+        let [topbar, rest] = Layout::vertical([Length(1), Fill(1)]).areas(area);
+        frame.render_widget(
+            Paragraph::new("Watch a recorded game:").alignment(Alignment::Center),
+            topbar,
+        );
+
+        let [list, map] = Layout::horizontal([Max(50), Fill(1)]).areas(rest);
+        let [head, _, list] = Layout::vertical([Length(1), Length(1), Fill(1)]).areas(list);
+
+        let list = bordered(frame, list);
+
+        frame.render_widget(
+            Paragraph::new("Back")
+                .alignment(Alignment::Center)
+                .style(if self.cursor == 0 {
+                    Style::default().add_modifier(Modifier::REVERSED)
+                } else {
+                    Style::default()
+                }),
+            head,
+        );
+
+        let selected = if self.cursor > 0 {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default()
+        };
+        let mut state = TableState::new().with_selected((self.cursor - 1).max(0) as usize);
+        frame.render_stateful_widget(
+            Table::new(
+                self.choices.iter().map(|c| {
+                    Row::new(vec![Cell::new(
+                        Line::from(c.clone()).alignment(Alignment::Left),
+                    )])
+                }),
+                [40],
+            )
+            .highlight_style(selected),
+            list,
+            &mut state,
+        );
+
+        if self.cursor > 0 {
+            let name = &self.choices[(self.cursor - 1).max(0) as usize];
+            if let Some(replay) = self.replays.get(name) {
+                let board = replay.start_board(bp);
+                frame.render_widget(
+                    BoardWidget {
+                        board: &board,
+                        blueprints: bp,
+                        cursor: board.grid.size / 2,
+                        attack_tiles: &vec![],
+                        movement_tiles: &vec![],
+                        target_tiles: &vec![],
+                        only_player_color: false,
+                        zoom: 3,
+                        show_spawns: false,
+                        travel_path: &vec![],
+                        fog_player: &PlayerId::new(0),
+                    },
+                    map,
+                );
+            } else {
+                frame.render_widget(PanelWidget::new(DECOR_1), map);
+                let inner = popup(frame, map, Size::new(30, 5));
+                frame.render_widget(Paragraph::new("Failed to load."), inner);
+            }
+        } else {
+            frame.render_widget(PanelWidget::new(DECOR_3), map)
+        }
+    }
+}
+
+/// Boards kept every this many actions, stepping backwards replays from the closest one
+const SNAPSHOT_INTERVAL: usize = 50;
+
+/// Plays back a replay, the board is rebuilt from the closest snapshot when stepping backwards
+#[derive(Debug, Clone)]
+pub struct ReplayViewer {
+    name: String,
+    replay: Replay,
+    days: Vec<u32>,
+
+    /// Boards after every `SNAPSHOT_INTERVAL` actions, starting from the first board
+    snapshots: Vec<Board>,
+
+    /// Number of actions applied to `board`
+    index: usize,
+    board: Board,
+
+    /// Board before the last action and the positions of the battle, if the last action was an attack
+    battle: Option<(Board, IVec2, IVec2)>,
+
+    cursor: IVec2,
+    playing: bool,
+    speed: MachineSpeed,
+    delay: u32,
+
+    /// Fog of which player is shown, `None` shows the full map
+    perspective: Option<PlayerId>,
+
+    /// Day being typed in the jump to day prompt
+    jump_day: Option<String>,
+}
+
+impl ReplayViewer {
+    pub fn new(name: String, replay: Replay, bp: &Blueprints, speed: MachineSpeed) -> Self {
+        let mut board = replay.start_board(bp);
+        board.refresh_fog();
+
+        let mut days = vec![];
+        let mut snapshots = vec![board.clone()];
+        let mut played = board.clone();
+        for (i, action) in replay.actions.iter().enumerate() {
+            days.push(played.day);
+            action.apply(&mut played);
+            if (i + 1) % SNAPSHOT_INTERVAL == 0 {
+                snapshots.push(played.clone());
+            }
+        }
+
+        Self {
+            name,
+            days,
+            snapshots,
+            index: 0,
+            cursor: board.grid.size / 2,
+            board,
+            replay,
+            battle: None,
+            playing: false,
+            speed,
+            delay: 0,
+            perspective: None,
+            jump_day: None,
+        }
+    }
+
+    pub fn input(mut self, input: MenuInput, ui_state: &mut InterfaceState) -> Option<Self> {
+        if let Some(mut day) = self.jump_day.take() {
+            match input.keycode {
+                Some(KeyCode::Enter) => {
+                    if let Ok(day) = day.parse::<u32>() {
+                        let index = self.day_start(day.saturating_sub(1));
+                        self.seek(index);
+                    }
+                }
+                Some(KeyCode::Backspace) => {
+                    day.pop();
+                    self.jump_day = Some(day);
+                }
+                Some(KeyCode::Char(c)) if c.is_ascii_digit() && day.len() < 6 => {
+                    day.push(c);
+                    self.jump_day = Some(day);
+                }
+                Some(KeyCode::Esc) => {}
+                _ => self.jump_day = Some(day),
+            }
+            return Some(self);
+        }
+
+        if input.back || input.quit {
+            return None;
+        }
+
+        let keybinds = &ui_state.settings.keybinds;
+        match input.keycode {
+            Some(code) if code == keybinds.replay_jump => {
+                self.jump_day = Some(String::new());
+                return Some(self);
+            }
+            Some(code) if code == keybinds.replay_speed => {
+                self.speed = match self.speed {
+                    MachineSpeed::Skip => MachineSpeed::StepMoves,
+                    MachineSpeed::StepMoves => MachineSpeed::StepMovesSlow,
+                    _ => MachineSpeed::Skip,
+                };
+            }
+            Some(code) if code == keybinds.next => {
+                // cycles through the players and the full map
+                let players: Vec<PlayerId> =
+                    self.board.players.iter().map(|p| p.id.clone()).collect();
+                self.perspective = match &self.perspective {
+                    None => players.first().cloned(),
+                    Some(id) => players.iter().skip_while(|p| *p != id).nth(1).cloned(),
+                };
+            }
+            Some(code) if code == keybinds.zoom_in => ui_state.zoom = (ui_state.zoom - 1).max(1),
+            Some(code) if code == keybinds.zoom_out => ui_state.zoom = (ui_state.zoom + 1).min(5),
+            _ => {}
+        }
+
+        if input.select {
+            self.playing = !self.playing;
+            self.delay = 0;
+        }
+
+        if input.acc.x != 0 {
+            self.playing = false;
+            let index = (self.index as i32 + input.acc.x).max(0) as usize;
+            self.seek(index);
+        }
+
+        if input.acc.y != 0 {
+            self.playing = false;
+            let day = self.board.day as i32 + input.acc.y;
+            let index = self.day_start(day.max(0) as u32);
+            self.seek(index);
+        }
+
+        if self.playing {
+            match self.speed {
+                MachineSpeed::Skip => {
+                    // plays the whole turn of the current player
+                    let turn_end = self.replay.actions[self.index.min(self.replay.actions.len())..]
+                        .iter()
+                        .position(|action| action == &PlayerAction::PassTurn)
+                        .map_or(self.replay.actions.len(), |i| self.index + i + 1);
+                    self.seek(turn_end);
+                }
+                MachineSpeed::StepMoves => {
+                    self.seek(self.index + 1);
+                }
+                MachineSpeed::StepMovesSlow | MachineSpeed::StepSelects => {
+                    self.delay += 1;
+                    if self.delay > 10 {
+                        self.delay = 0;
+                        self.seek(self.index + 1);
+                    }
+                }
+            }
+            if self.index >= self.replay.actions.len() {
+                self.playing = false;
+            }
+        }
+
+        Some(self)
+    }
+
+    /// Same as `Replay::day_start`, without replaying the whole game
+    fn day_start(&self, day: u32) -> usize {
+        self.days
+            .iter()
+            .position(|d| *d >= day)
+            .unwrap_or(self.replay.actions.len())
+    }
+
+    /// The board after the first `index` actions, played from the closest snapshot before it
+    fn board_at(&self, index: usize) -> Board {
+        let snapshot = (index / SNAPSHOT_INTERVAL).min(self.snapshots.len() - 1);
+        let mut board = self.snapshots[snapshot].clone();
+        for action in &self.replay.actions[snapshot * SNAPSHOT_INTERVAL..index] {
+            action.apply(&mut board);
+        }
+        board
+    }
+
+    /// Moves the playback to after the first `index` actions
+    fn seek(&mut self, index: usize) {
+        let index = index.min(self.replay.actions.len());
+        if index == self.index {
+            return;
+        }
+
+        let before = if index == self.index + 1 {
+            self.board.clone()
+        } else {
+            self.board_at(index.saturating_sub(1))
+        };
+
+        let mut board = before.clone();
+        self.battle = None;
+        if let Some(action) = index.checked_sub(1).map(|i| &self.replay.actions[i]) {
+            action.apply(&mut board);
+
+            if let PlayerAction::Unit {
+                action: UnitAction::Attack(UnitTarget { at: def_pos, .. }),
+                destination,
+                ..
+            } = action
+            {
+                // the battle overview shows the attacker already moved
+                let mut moved = before;
+                if let PlayerAction::Unit {
+                    target,
+                    destination,
+                    pickup,
+                    path,
+                    ..
+                } = action.clone()
+                {
+                    PlayerAction::Unit {
+                        target,
+                        destination,
+                        pickup,
+                        path,
+                        action: UnitAction::Done,
+                    }
+                    .apply(&mut moved);
+                }
+                self.battle = Some((moved, *destination, *def_pos));
+            }
+
+            if let Some(cursor) = match action {
+                PlayerAction::Unit { destination, .. } => Some(destination),
+                PlayerAction::Building {
+                    target: UnitTarget { at, .. },
+                    ..
+                } => Some(at),
+                _ => None,
+            } {
+                self.cursor = *cursor;
+            }
+        }
+        board.refresh_fog();
+
+        self.board = board;
+        self.index = index;
+    }
+
+    pub fn render(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        bp: &Blueprints,
+        ui_state: &InterfaceState,
+    ) {
+        use Constraint::*;
+
+        frame.render_widget(Clear, area);
+
+        let [topbar, map, bottombar] = Layout::vertical([Length(1), Min(0), Length(1)]).areas(area);
+
+        let player = self.board.get_current_player();
+        let perspective = match &self.perspective {
+            Some(id) => format!("fog of {}", self.board.get_player(id).name),
+            None => "full map".to_string(),
+        };
+        let speed = match self.speed {
+            MachineSpeed::Skip => "turns",
+            MachineSpeed::StepMoves => "fast",
+            MachineSpeed::StepMovesSlow | MachineSpeed::StepSelects => "slow",
+        };
+        let status = format!(
+            "{} | Day {}/{}; Player {} ({}) | Action {}/{} | {} ({}) | {}",
+            self.name,
+            self.board.day + 1,
+            self.days.last().map_or(1, |d| d + 1),
+            player.name,
+            player.symbol,
+            self.index,
+            self.replay.actions.len(),
+            if self.playing { "Playing" } else { "Paused" },
+            speed,
+            perspective,
+        );
+        frame.render_widget(
+            Paragraph::new(status).style(
+                Style::default()
+                    .bg(Color::from_u32(player.color))
+                    .fg(Color::Black),
+            ),
+            topbar,
+        );
+
+        // the full map is shown by removing the fog of every player
+        let full_map;
+        let (board, fog_player) = match &self.perspective {
+            Some(id) => (&self.board, id),
+            None => {
+                full_map = Board {
+                    fog: HashMap::new(),
+                    ..self.board.clone()
+                };
+                (&full_map, &self.board.current_player_turn)
+            }
+        };
+        frame.render_widget(
+            BoardWidget {
+                board,
+                blueprints: bp,
+                cursor: self.cursor,
+                movement_tiles: &vec![],
+                attack_tiles: &vec![],
+                target_tiles: &vec![],
+                only_player_color: ui_state.map_only_player_color,
+                zoom: ui_state.zoom,
+                show_spawns: false,
+                travel_path: &vec![],
+                fog_player,
+            },
+            map,
+        );
+
+        if let Some((moved, atk_pos_moved, def_pos)) = &self.battle {
+            let [_, inner, _] = Layout::vertical([Fill(1), Min(17), Fill(1)]).areas(map);
+            let [_, inner, _] = Layout::horizontal([Fill(1), Min(60), Fill(1)]).areas(inner);
+            frame.render_stateful_widget(
+                BattleWidget {
+                    board: moved,
+                    blueprints: bp,
+                    atk_pos_moved: *atk_pos_moved,
+                    def_pos: *def_pos,
+                },
+                inner,
+                &mut BattleWidgetState {
+                    temp_board: Some(self.board.clone()),
+                },
+            );
+        }
+
+        if let Some(day) = &self.jump_day {
+            let inner = popup(frame, map, Size::new(30, 3));
+            frame.render_widget(Paragraph::new(format!("Jump to day: {}_", day)), inner);
+        }
+
+        let keys = &ui_state.settings.keybinds;
+        let hints = format!(
+            "{}: play/pause, {}/{}: step, {}/{}: day, {}: jump to day, {}: speed, {}: fog, {}: back",
+            key_name(&keys.forward),
+            key_name(&keys.right),
+            key_name(&keys.left),
+            key_name(&keys.up),
+            key_name(&keys.down),
+            key_name(&keys.replay_jump),
+            key_name(&keys.replay_speed),
+            key_name(&keys.next),
+            key_name(&keys.back),
+        );
+        frame.render_widget(
+            Paragraph::new(hints).alignment(Alignment::Center),
+            bottombar,
+        );
+    }
+}
+
+/// How a key is written in the hints
+fn key_name(key: &KeyCode) -> String {
+    match key {
+        KeyCode::Char(' ') => "space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        key => format!("{:?}", key),
+    }
+}
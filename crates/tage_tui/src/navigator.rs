@@ -101,6 +101,18 @@ impl Path {
                         selects.push(Select::Menu(MenuChoice::PowerId(power_id)));
                         selects.push(Select::Confirm);
                     }
+                    UnitAction::Embark(target) => {
+                        selects.push(Select::Menu(MenuChoice::Embark));
+                        selects.push(Select::tile_target(target));
+                    }
+                    UnitAction::Unload(index, destination) => {
+                        selects.push(Select::Menu(MenuChoice::Unload));
+                        selects.push(Select::Menu(MenuChoice::UnloadCargo(
+                            index,
+                            target.unit.cargo[index].blueprint_id.clone(),
+                        )));
+                        selects.push(Select::tile(destination));
+                    }
                     UnitAction::Done => selects.push(Select::Menu(MenuChoice::Done)),
                 }
             }
@@ -141,6 +153,26 @@ impl Path {
                 selects.push(Select::Menu(MenuChoice::Research));
                 selects.push(Select::Menu(MenuChoice::ResearchId(id)));
             }
+            PlayerAction::ResearchQueue(action) => {
+                selects.push(Select::tile(at));
+                selects.push(Select::Menu(MenuChoice::ResearchQueue));
+                match action {
+                    ResearchQueueAction::Cancel(index, ..) => {
+                        selects.push(Select::Menu(MenuChoice::CancelResearch(index)));
+                        selects.push(Select::Confirm);
+                    }
+                    ResearchQueueAction::Switch(index, .., id) => {
+                        selects.push(Select::Menu(MenuChoice::SwitchResearch(index)));
+                        selects.push(Select::Menu(MenuChoice::ResearchId(id)));
+                    }
+                }
+            }
+            PlayerAction::Diplomacy(action) => {
+                selects.push(Select::tile(at));
+                selects.push(Select::Menu(MenuChoice::Diplomacy));
+                selects.push(Select::Menu(MenuChoice::DiplomacyId(action)));
+                selects.push(Select::Confirm);
+            }
             PlayerAction::PassTurn => {
                 selects.push(Select::tile(at));
                 selects.push(Select::Menu(MenuChoice::EndDay));
@@ -300,7 +332,7 @@ pub struct PathNode {
 pub enum NavigatorError {
     InvalidPush,
     Empty,
-    Bonked(PlayerAction),
+    Bonked(Box<PlayerAction>),
 }
 
 impl Navigator {
@@ -353,13 +385,13 @@ impl Navigator {
                 };
 
                 if let Some(reachable) = act.has_bonked(clear_board) {
-                    return Some(NavigatorError::Bonked(PlayerAction::Unit {
+                    return Some(NavigatorError::Bonked(Box::new(PlayerAction::Unit {
                         target: target.clone(),
                         destination: reachable.destination,
                         pickup: None,
                         action: UnitAction::Done,
                         path: path.clone(),
-                    }));
+                    })));
                 }
                 act.apply(&mut preview_board);
 
@@ -416,12 +448,30 @@ impl Navigator {
     }
 }
 
+/// How many passed turns can be taken back
+pub const TURN_HISTORY_LEN: usize = 32;
+
+/// The game as it was just before a turn was passed.
+/// `ActEndTurn` can't be undone (rolls, production, upgrades, captures), so the whole board is kept.
+pub struct TurnSnapshot {
+    pub board: Board,
+    pub turn_timeline: Vec<PlayerAction>,
+    pub undo_barrier: usize,
+    pub replay_len: usize,
+}
+
 pub struct GameState {
     pub board: Board,
     pub blueprints: Blueprints,
     pub navigator: Option<Navigator>,
     pub current_picker: UiPicker,
     pub turn_timeline: Vec<PlayerAction>,
+
+    /// Actions of the timeline before this index can't be undone, they revealed hidden information
+    pub undo_barrier: usize,
+
+    pub turn_history: Vec<TurnSnapshot>,
+    pub replay: Replay,
 }
 
 impl GameState {
@@ -464,7 +514,7 @@ impl GameState {
                 if let Some(err) = nav.push(self.current_picker.clone(), &self.board) {
                     warn!("error while selecting {:?}", err);
                     match err {
-                        NavigatorError::Bonked(action) => self.apply_bonk(action),
+                        NavigatorError::Bonked(action) => self.apply_bonk(*action),
                         _ => {}
                     }
                 } else {
@@ -476,7 +526,7 @@ impl GameState {
         }
 
         if input.back {
-            if self.board.can_undo() {
+            if self.board.fog_allows_undo() {
                 if let Some(nav) = &mut self.navigator {
                     if nav.stack.len() > 1 {
                         let layer = nav.stack.pop().unwrap();
@@ -491,9 +541,7 @@ impl GameState {
                         self.navigator = None;
                     }
                 } else {
-                    if let Some(last_action) = self.turn_timeline.pop() {
-                        last_action.undo(&mut self.board)
-                    }
+                    self.undo();
                 }
             } else {
                 if let Some(nav) = &mut self.navigator {
@@ -506,6 +554,8 @@ impl GameState {
                         });
                         self.navigator = None;
                     }
+                } else {
+                    self.undo();
                 }
             }
         }
@@ -589,7 +639,7 @@ impl GameState {
                 if let Some(err) = nav.push(self.current_picker.clone(), &self.board) {
                     warn!("error while selecting {:?}", err);
                     match err {
-                        NavigatorError::Bonked(action) => self.apply_bonk(action),
+                        NavigatorError::Bonked(action) => self.apply_bonk(*action),
                         _ => {}
                     }
                 } else {
@@ -605,7 +655,7 @@ impl GameState {
                     if let Some(err) = nav.push(self.current_picker.clone(), &self.board) {
                         warn!("error while selecting {:?}", err);
                         match err {
-                            NavigatorError::Bonked(action) => self.apply_bonk(action),
+                            NavigatorError::Bonked(action) => self.apply_bonk(*action),
                             _ => {}
                         }
                     } else {
@@ -637,6 +687,54 @@ impl GameState {
         None
     }
 
+    /// Passed turns can be taken back only in hotseat and local games against machines,
+    /// without fog as the other players turns would be revealed
+    pub fn can_undo_turns(&self) -> bool {
+        self.board.fog_allows_undo()
+            && self
+                .board
+                .players
+                .iter()
+                .all(|player| !matches!(player.controller, Controller::Remote(_)))
+    }
+
+    /// Takes back the last action of the turn, or the last passed turn if none was taken
+    pub fn undo(&mut self) {
+        if self.turn_timeline.len() > self.undo_barrier {
+            if let Some(last_action) = self.turn_timeline.pop() {
+                last_action.undo(&mut self.board);
+                self.board.refresh_fog();
+                self.replay.undo();
+            }
+        } else if self.turn_timeline.is_empty() && self.can_undo_turns() {
+            if let Some(snapshot) = self.turn_history.pop() {
+                self.board = snapshot.board;
+                self.turn_timeline = snapshot.turn_timeline;
+                self.undo_barrier = snapshot.undo_barrier;
+                self.replay.actions.truncate(snapshot.replay_len);
+            }
+        }
+    }
+
+    /// Applies an action taken by the player and records it in the timeline
+    pub fn apply_action(&mut self, action: PlayerAction) -> PlayerAction {
+        let (action, undoable) = self.board.apply_fogged(action);
+        self.replay.record(&action);
+        match action {
+            PlayerAction::PassTurn => {
+                self.turn_timeline.clear();
+                self.undo_barrier = 0;
+            }
+            _ => {
+                self.turn_timeline.push(action.clone());
+                if !undoable {
+                    self.undo_barrier = self.turn_timeline.len();
+                }
+            }
+        }
+        action
+    }
+
     pub fn apply_bonk(&mut self, action: PlayerAction) {
         self.current_picker = UiPicker::Tile(UiTilePicker {
             cursor: self.top_tile_picker().cursor,
@@ -649,7 +747,10 @@ impl GameState {
         action.apply(&mut self.board);
         self.board.refresh_fog();
 
+        // the move was truncated by the fog, undoing it would reveal what is there
         self.turn_timeline.push(action.clone());
+        self.undo_barrier = self.turn_timeline.len();
+        self.replay.record(&action);
     }
 
     pub fn open_picker(&mut self) -> Option<PlayerAction> {
@@ -677,15 +778,18 @@ impl GameState {
             });
             self.navigator = None;
             if let Some(action) = &top.selected_action {
-                let action = self.board.fog_bonk(action.clone());
-                action.apply(&mut self.board);
-                self.board.refresh_fog();
-
-                match action {
-                    PlayerAction::PassTurn => self.turn_timeline.clear(),
-                    _ => self.turn_timeline.push(action.clone()),
+                if action == &PlayerAction::PassTurn && self.can_undo_turns() {
+                    if self.turn_history.len() >= TURN_HISTORY_LEN {
+                        self.turn_history.remove(0);
+                    }
+                    self.turn_history.push(TurnSnapshot {
+                        board: self.board.clone(),
+                        turn_timeline: self.turn_timeline.clone(),
+                        undo_barrier: self.undo_barrier,
+                        replay_len: self.replay.actions.len(),
+                    });
                 }
-                return Some(action.clone());
+                return Some(self.apply_action(action.clone()));
             } else {
                 return None;
             }
@@ -768,6 +872,9 @@ pub enum MenuChoice {
     RelicPickup,
     RelicDropoff,
     Merge,
+    Embark,
+    Unload,
+    UnloadCargo(usize, UnitId),
     Power,
     PowerId(PowerId),
     Repair,
@@ -779,6 +886,11 @@ pub enum MenuChoice {
     Done,
     Research,
     ResearchId(TechId),
+    ResearchQueue,
+    CancelResearch(usize),
+    SwitchResearch(usize),
+    Diplomacy,
+    DiplomacyId(DiplomacyAction),
     EndDay,
 }
 
@@ -794,6 +906,9 @@ impl MenuChoice {
             Self::Heal => 1000,
             Self::Convert => 1001,
             Self::Merge => 1002,
+            Self::Embark => 1003,
+            Self::Unload => 1004,
+            Self::UnloadCargo(index, _) => 1005 + *index as u32,
             Self::Power => 2000,
             Self::PowerId(id) => 2001 + id.0,
             Self::Repair => 3000,
@@ -810,6 +925,23 @@ impl MenuChoice {
             Self::Done => 4004,
             Self::Research => 4005,
             Self::ResearchId(id) => 4006 + id.0,
+            Self::ResearchQueue => 4800,
+            // the queue has no limit, keep its ids clear of the other choices
+            Self::CancelResearch(index) => 30000 + *index as u32 * 2,
+            Self::SwitchResearch(index) => 30001 + *index as u32 * 2,
+            Self::Diplomacy => 4900,
+            // ids grow with the number of players, keep them clear of the other choices
+            Self::DiplomacyId(action) => {
+                20000
+                    + match action {
+                        DiplomacyAction::Propose(id, Relation::Alliance) => id.get() * 8,
+                        DiplomacyAction::Propose(id, _) => id.get() * 8 + 1,
+                        DiplomacyAction::Accept(id) => id.get() * 8 + 2,
+                        DiplomacyAction::Break(id) => id.get() * 8 + 3,
+                        DiplomacyAction::Tribute(id, Resource::Food) => id.get() * 8 + 4,
+                        DiplomacyAction::Tribute(id, Resource::Gold) => id.get() * 8 + 5,
+                    }
+            }
             Self::EndDay => 5000,
             Self::TileStack(target) => match target {
                 TileStackTarget::Top => 10000,
@@ -843,6 +975,11 @@ impl MenuChoice {
             MenuChoice::RelicPickup => "Capture Relic".to_string(),
             MenuChoice::RelicDropoff => "Donate Relic".to_string(),
             MenuChoice::Merge => "Merge".to_string(),
+            MenuChoice::Embark => "Embark".to_string(),
+            MenuChoice::Unload => "Unload".to_string(),
+            MenuChoice::UnloadCargo(_, id) => {
+                format!("Unload {}", board.bp.get_unit(id).header.name)
+            }
             MenuChoice::Power => "Powers".to_string(),
             MenuChoice::PowerId(id) => board.bp.get_power(id).name.clone(),
             MenuChoice::Repair => "Repair".to_string(),
@@ -857,7 +994,7 @@ impl MenuChoice {
                 )
             }
             MenuChoice::TradeResource(resource) => {
-                let rate = ActTrade::get_rate(&board.current_player_turn, board);
+                let rate = ActTrade::get_rate(&board.current_player_turn, resource, board);
                 match resource {
                     Resource::Food => format!("Trade {} Food for 100 Gold", rate),
                     Resource::Gold => format!("Trade {} Gold for 100 Food", rate),
@@ -871,6 +1008,32 @@ impl MenuChoice {
                 let tech = board.bp.get_tech(id);
                 format!("Tech {}", tech.name)
             }
+            MenuChoice::ResearchQueue => "Research Queue".to_string(),
+            MenuChoice::CancelResearch(index) => format!(
+                "Cancel {}",
+                board.get_current_player().research_queue[*index]
+                    .0
+                    .view(&board.bp)
+            ),
+            MenuChoice::SwitchResearch(index) => format!(
+                "Switch {}",
+                board.get_current_player().research_queue[*index]
+                    .0
+                    .view(&board.bp)
+            ),
+            MenuChoice::Diplomacy => "Diplomacy".to_string(),
+            MenuChoice::DiplomacyId(action) => match action {
+                DiplomacyAction::Accept(id) => format!(
+                    "Accept {} with {}",
+                    board
+                        .diplomacy
+                        .get_proposal(id, &board.current_player_turn)
+                        .unwrap_or_default()
+                        .view(),
+                    id.view()
+                ),
+                _ => action.view(),
+            },
             MenuChoice::EndDay => "End Day".to_string(),
         }
     }
@@ -15,6 +15,8 @@ use renet::{
 use tage_core::{
     actions::PlayerAction,
     game::{BoardView, MapSettings},
+    mods::ModSet,
+    stats::Stats,
 };
 use tracing::{error, info};
 
@@ -24,7 +26,10 @@ use crate::Member;
 pub const PORT: u16 = 45557;
 
 // Game unique identifier, randomly generated by my head
-pub const PROTOCOL_ID: u64 = 4555706555;
+pub const PROTOCOL_ID: u64 = 4555706556;
+
+// Time a rejected client has to read the reason before the server drops it
+const REJECTED_GRACE: Duration = Duration::from_secs(2);
 
 // Static id of the host
 pub const HOST_CLIENT_ID: ClientId = ClientId::from_raw(1);
@@ -80,12 +85,16 @@ impl Net {
         }
     }
 
-    pub fn open_client(&mut self, member: Member, server_address: SocketAddr) {
-        self.connection = Some(Connection::Client(Client::new(member, server_address)))
+    pub fn open_client(&mut self, member: Member, mods: &ModSet, server_address: SocketAddr) {
+        self.connection = Some(Connection::Client(Client::new(
+            member,
+            mods.fingerprint,
+            server_address,
+        )))
     }
 
-    pub fn open_server(&mut self, member: Member) {
-        self.connection = Some(Connection::Server(Server::new(member)))
+    pub fn open_server(&mut self, member: Member, mods: &ModSet) {
+        self.connection = Some(Connection::Server(Server::new(member, mods.clone())))
     }
 
     pub fn update(&mut self, duration: Duration) {
@@ -176,6 +185,13 @@ pub struct Server {
     pub members: HashMap<ClientId, Member>,
     pub queue: Vec<(ClientId, ClientMessages)>,
     pub connection_queue: Vec<ServerConnectionMessages>,
+
+    /// Only clients with the same blueprints, maps and mods can join
+    pub mods: ModSet,
+
+    /// Clients with other files and the time left before they are disconnected,
+    /// they leave on their own once they got the reason
+    pub rejected: Vec<(ClientId, Duration)>,
 }
 
 #[allow(dead_code)]
@@ -187,24 +203,43 @@ pub enum ServerConnectionMessages {
 /// From server to client
 #[derive(Debug, bincode::Encode, bincode::Decode)]
 pub enum ServerMessages {
-    ClientConnected { client_id: u64, member: Member },
-    ClientDisconnected { client_id: u64 },
-    MapSettings { map_settings: MapSettings },
-    Board { board: BoardView },
+    ClientConnected {
+        client_id: u64,
+        member: Member,
+    },
+    ClientDisconnected {
+        client_id: u64,
+    },
+    MapSettings {
+        map_settings: MapSettings,
+    },
+    Board {
+        board: Box<BoardView>,
+    },
     ToGame,
     ToLobby,
+
+    /// Statistics of the ended game, only the server has the replay to compute them
+    Stats {
+        stats: Stats,
+    },
+
+    /// The client can't join, sent right before disconnecting it
+    Rejected {
+        reason: String,
+    },
 }
 
 /// From client to server
 #[derive(Debug, bincode::Encode, bincode::Decode)]
 pub enum ClientMessages {
     MemberChange { member: Member },
-    PlayerAction { action: PlayerAction },
+    PlayerAction { action: Box<PlayerAction> },
     Undo,
 }
 
 impl Server {
-    pub fn new(member: Member) -> Self {
+    pub fn new(member: Member, mods: ModSet) -> Self {
         let socket = UdpSocket::bind(format!("0.0.0.0:{}", PORT)).unwrap();
         let current_time = SystemTime::now()
             .duration_since(SystemTime::UNIX_EPOCH)
@@ -225,6 +260,8 @@ impl Server {
             members: [(HOST_CLIENT_ID, member)].into_iter().collect(),
             queue: vec![],
             connection_queue: vec![],
+            mods,
+            rejected: vec![],
         }
     }
 
@@ -233,6 +270,18 @@ impl Server {
     }
 
     pub fn update(&mut self, duration: Duration) -> Result<(), String> {
+        self.rejected
+            .retain_mut(|(client_id, left)| match left.checked_sub(duration) {
+                Some(rest) => {
+                    *left = rest;
+                    true
+                }
+                None => {
+                    self.server.disconnect(*client_id);
+                    false
+                }
+            });
+
         self.server.update(duration);
         if let Err(e) = self.transport.update(duration, &mut self.server) {
             error!(target: "server", "{}", e);
@@ -242,6 +291,32 @@ impl Server {
         while let Some(event) = self.server.get_event() {
             match event {
                 ServerEvent::ClientConnected { client_id } => {
+                    let user_data = self.transport.user_data(client_id).unwrap();
+                    let (mods, member) = Member::from_user_data(&user_data);
+                    if mods != self.mods.fingerprint {
+                        let reason = if self.mods.mods.is_empty() {
+                            "The server plays without mods, or with other blueprints or maps"
+                                .to_string()
+                        } else {
+                            format!(
+                                "The server plays with the mods: {}, or with other blueprints or maps",
+                                self.mods.names().join(", ")
+                            )
+                        };
+                        info!(target: "server", "rejected client({}): {}", client_id, reason);
+                        self.server.send_message(
+                            client_id,
+                            DefaultChannel::ReliableOrdered,
+                            bincode::encode_to_vec::<ServerMessages, Configuration>(
+                                ServerMessages::Rejected { reason },
+                                Configuration::default(),
+                            )
+                            .unwrap(),
+                        );
+                        self.rejected.push((client_id, REJECTED_GRACE));
+                        continue;
+                    }
+
                     for (id, member) in self.members.iter() {
                         self.server.send_message(
                             client_id,
@@ -257,8 +332,6 @@ impl Server {
                         );
                     }
 
-                    let user_data = self.transport.user_data(client_id).unwrap();
-                    let member = Member::from_user_data(&user_data);
                     self.members.insert(client_id, member.clone());
                     self.server.broadcast_message(
                         DefaultChannel::ReliableOrdered,
@@ -276,7 +349,11 @@ impl Server {
                         .push(ServerConnectionMessages::Connected(client_id))
                 }
                 ServerEvent::ClientDisconnected { client_id, .. } => {
-                    self.members.remove(&client_id);
+                    // rejected clients never joined
+                    self.rejected.retain(|(id, _)| *id != client_id);
+                    if self.members.remove(&client_id).is_none() {
+                        continue;
+                    }
                     self.server.broadcast_message(
                         DefaultChannel::ReliableOrdered,
                         bincode::encode_to_vec::<ServerMessages, Configuration>(
@@ -297,6 +374,9 @@ impl Server {
         }
 
         for client_id in self.server.clients_id() {
+            if self.rejected.iter().any(|(id, _)| *id == client_id) {
+                continue;
+            }
             while let Some(message) = self
                 .server
                 .receive_message(client_id, DefaultChannel::ReliableOrdered)
@@ -344,7 +424,7 @@ pub struct Client {
 }
 
 impl Client {
-    pub fn new(member: Member, server_address: SocketAddr) -> Self {
+    pub fn new(member: Member, mods: u64, server_address: SocketAddr) -> Self {
         let connection_config = ConnectionConfig::default();
         let client = RenetClient::new(connection_config);
 
@@ -357,7 +437,7 @@ impl Client {
         let authentication = ClientAuthentication::Unsecure {
             server_addr: server_address,
             client_id,
-            user_data: Some(member.to_user_data()),
+            user_data: Some(member.to_user_data(mods)),
             protocol_id: PROTOCOL_ID,
         };
         let transport = NetcodeClientTransport::new(current_time, authentication, socket).unwrap();
@@ -401,6 +481,11 @@ impl Client {
                         ServerMessages::ClientDisconnected { client_id } => {
                             self.members.remove(&raw!(*client_id));
                         }
+                        ServerMessages::Rejected { reason } => {
+                            error!(target: "client", "rejected: {}", reason);
+                            self.client.disconnect();
+                            return Err(reason.clone());
+                        }
                         _ => self.queue.push(message),
                     }
                 }
@@ -46,6 +46,7 @@ impl Settings {
 macro_rules! define_keybinds {
     ( $($field:ident),* ) => {
         #[derive(Debug, Clone, Serialize, Deserialize)]
+        #[serde(default)]
         pub struct Keybinds {
             $(pub $field: KeyCode,)*
         }
@@ -92,7 +93,9 @@ define_keybinds!(
     short_build,
     short_done,
     short_tech,
-    short_pass
+    short_pass,
+    replay_jump,
+    replay_speed
 );
 
 impl Default for Keybinds {
@@ -125,6 +128,8 @@ impl Default for Keybinds {
             short_done: KeyCode::Char('g'),
             short_tech: KeyCode::Char('t'),
             short_pass: KeyCode::Tab,
+            replay_jump: KeyCode::Char('j'),
+            replay_speed: KeyCode::Char('r'),
         }
     }
 }
@@ -159,6 +164,8 @@ impl Keybinds {
             short_done: KeyCode::Char('g'),
             short_tech: KeyCode::Char('t'),
             short_pass: KeyCode::Tab,
+            replay_jump: KeyCode::Char('G'),
+            replay_speed: KeyCode::Char('r'),
         }
     }
 
@@ -200,6 +207,8 @@ impl Keybinds {
             ("short_tech", "Hotkey to open the research menu"),
             ("short_pass", "Hotkey to end the day"),
             ("short_done", "Hotkey to set the unit as done"),
+            ("replay_jump", "Asks for a day to jump to in the replay viewer"),
+            ("replay_speed", "Changes how fast the replay viewer plays"),
         ]
         .into_iter()
         .collect()
@@ -234,6 +243,8 @@ impl Keybinds {
             "short_tech" => "Hotkey Research",
             "short_pass" => "Hotkey End Day",
             "short_done" => "Hotkey Done",
+            "replay_jump" => "Replay Jump",
+            "replay_speed" => "Replay Speed",
             _ => unreachable!(),
         }
     }
@@ -80,6 +80,15 @@ impl<'a> Widget for UnitBlueprintWidget<'a> {
                 bp.defence_bonus_to_adjacent_buildings
             ))
         }
+        if bp.domain != MovementDomain::Land {
+            line(format!("Moves on {}", bp.domain.view()))
+        }
+        if bp.capacity != 0 {
+            line(format!("Carries {} land units", bp.capacity))
+        }
+        if bp.barrier != Barrier::None {
+            line(format!("Barrier: {}", bp.barrier.view()))
+        }
         if !bp.abilities.is_empty() {
             line(format!("Abilities:"));
             for ability in bp.abilities.iter() {
@@ -7,6 +7,7 @@ pub mod cursor_widget;
 pub mod member_widget;
 pub mod panel_widget;
 pub mod research_widget;
+pub mod stats_widget;
 pub mod terrain_widget;
 pub mod unit_stats_widget;
 pub mod unit_widget;
@@ -20,6 +21,7 @@ pub use cursor_widget::*;
 pub use member_widget::*;
 pub use panel_widget::*;
 pub use research_widget::*;
+pub use stats_widget::*;
 pub use terrain_widget::*;
 pub use unit_stats_widget::*;
 pub use unit_widget::*;
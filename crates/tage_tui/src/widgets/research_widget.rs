@@ -104,8 +104,7 @@ impl<'a> Widget for ResearchWidget<'a> {
                 .map(|id| {
                     let available = self.tech_picker.choices.contains(id);
                     let researched = player.researched_technologies.contains(&id);
-                    let researching =
-                        player.research_queued == Some(QueuedResearch::Tech(id.clone()));
+                    let researching = player.is_researching(&QueuedResearch::Tech(id.clone()));
                     let style = if researching {
                         Style::default().fg(Color::Blue)
                     } else if researched {
@@ -0,0 +1,133 @@
+use ratatui::{layout::Alignment, prelude::*, widgets::*};
+use tage_core::prelude::*;
+
+/// End of game summary: totals of every player and their day by day charts
+#[derive(Debug, Clone, Copy)]
+pub struct StatsWidget<'a> {
+    pub board: &'a Board,
+    pub blueprints: &'a Blueprints,
+    pub stats: &'a Stats,
+    pub outcome: &'a Outcome,
+}
+
+impl<'a> StatsWidget<'a> {
+    /// Rows needed to show every player
+    pub fn height(&self) -> u16 {
+        let players = self.players().count() as u16;
+        // header, table with its header, charts with their header
+        1 + 1 + (1 + players) + 1 + (1 + players * 2)
+    }
+
+    fn players(&self) -> impl Iterator<Item = (&'a Player, &'a PlayerStats)> {
+        let stats = self.stats;
+        self.board
+            .players
+            .iter()
+            .filter(|player| !player.id.is_gaia())
+            .filter_map(move |player| stats.get(&player.id).map(|s| (player, s)))
+    }
+}
+
+impl<'a> Widget for StatsWidget<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        use Constraint::*;
+
+        let players = self.players().count() as u16;
+        let [header, _, table, _, charts] = Layout::vertical([
+            Length(1),
+            Length(1),
+            Length(1 + players),
+            Length(1),
+            Fill(1),
+        ])
+        .areas(area);
+
+        Paragraph::new(format!("{} victory!", self.outcome.victory.view()))
+            .alignment(Alignment::Center)
+            .render(header, buf);
+
+        let style = |player: &Player| {
+            Style::default()
+                .bg(Color::from_u32(player.color))
+                .fg(Color::Black)
+        };
+
+        Widget::render(
+            Table::new(
+                self.players().map(|(player, stats)| {
+                    let produced = stats.total(|d| d.produced.food + d.produced.gold);
+                    let spent = stats.total(|d| d.spent.food + d.spent.gold);
+                    let army = stats.days.last().map_or(0, |d| d.army_value);
+                    let winner = if self.outcome.winners.contains(&player.id) {
+                        "won"
+                    } else {
+                        ""
+                    };
+                    Row::new(vec![
+                        Line::from(player.symbol.clone()).style(style(player)),
+                        Line::from(
+                            self.blueprints
+                                .get_civilization(&player.civilization)
+                                .name
+                                .clone(),
+                        ),
+                        Line::from(winner),
+                        Line::from(produced.to_string()),
+                        Line::from(spent.to_string()),
+                        Line::from(stats.total(|d| d.trained).to_string()),
+                        Line::from(stats.total(|d| d.lost).to_string()),
+                        Line::from(stats.total(|d| d.kills).to_string()),
+                        Line::from(stats.total(|d| d.techs).to_string()),
+                        Line::from(army.to_string()),
+                    ])
+                }),
+                [1, 16, 4, 9, 9, 8, 5, 6, 6, 5],
+            )
+            .header(Row::new(vec![
+                "", "", "", "Produced", "Spent", "Trained", "Lost", "Kills", "Techs", "Army",
+            ])),
+            table,
+            buf,
+        );
+
+        let [army_header, production_header] = Layout::horizontal([Fill(1), Fill(1)]).areas(Rect {
+            height: 1,
+            ..charts
+        });
+        Paragraph::new("Army value")
+            .alignment(Alignment::Center)
+            .render(army_header, buf);
+        Paragraph::new("Production")
+            .alignment(Alignment::Center)
+            .render(production_header, buf);
+
+        let rows = Layout::vertical(self.players().map(|_| Length(2))).split(Rect {
+            y: charts.y + 1,
+            height: charts.height.saturating_sub(1),
+            ..charts
+        });
+        for ((player, stats), row) in self.players().zip(rows.iter()) {
+            let [symbol, army, production] =
+                Layout::horizontal([Length(2), Fill(1), Fill(1)]).areas(*row);
+            Paragraph::new(player.symbol.clone())
+                .style(style(player))
+                .render(Rect { width: 1, ..symbol }, buf);
+            let series = |f: fn(&DayStats) -> i32| -> Vec<u64> {
+                stats
+                    .series(f)
+                    .into_iter()
+                    .map(|v| v.max(0) as u64)
+                    .collect()
+            };
+            let color = Color::from_u32(player.color);
+            Sparkline::default()
+                .data(&series(|d| d.army_value))
+                .style(Style::default().fg(color))
+                .render(army, buf);
+            Sparkline::default()
+                .data(&series(|d| d.produced.food + d.produced.gold))
+                .style(Style::default().fg(color))
+                .render(production, buf);
+        }
+    }
+}
@@ -82,6 +82,15 @@ impl<'a> Widget for UnitWidget<'a> {
             Paragraph::new(format!("Holding Relic"))
                 .style(style)
                 .render(special, buf)
+        } else if !self.unit.cargo.is_empty() {
+            Paragraph::new(format!(
+                "Carrying {}/{}",
+                self.unit.cargo.len(),
+                bp.capacity
+            ))
+            .style(style)
+            .alignment(Alignment::Right)
+            .render(special, buf)
         }
     }
 }
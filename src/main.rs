@@ -2,6 +2,9 @@ use tage_flow::StartFlow;
 
 fn main() {
     let flow = StartFlow::from_args();
+    if let StartFlow::Done = flow {
+        return;
+    }
 
     // Other ui interfaces can be implemented.
     // Switch between them via feature flags.